@@ -8,10 +8,29 @@ export type EngineInfo = {
   hostname: string | null;
   port: number | null;
   pid: number | null;
+  launcher: string | null;
+  sandboxed: boolean;
+  sandboxDenials: string[];
+  limits: ProcessLimits | null;
+  exitReason: string | null;
   lastStdout: string | null;
   lastStderr: string | null;
 };
 
+export type ResourceLimits = {
+  memoryMb?: number | null;
+  nice?: number | null;
+  openFiles?: number | null;
+  maxProcesses?: number | null;
+};
+
+export type ProcessLimits = {
+  limits: ResourceLimits;
+  mechanism: "cgroup" | "rlimit" | "none";
+  cgroup: string | null;
+  notes: string[];
+};
+
 export type OpenworkServerInfo = {
   running: boolean;
   host: string | null;
@@ -37,6 +56,100 @@ export type EngineDoctorResult = {
   serveHelpStatus: number | null;
   serveHelpStdout: string | null;
   serveHelpStderr: string | null;
+  preflight: RuntimePreflight;
+  nodeRuntime: NodeRuntimeStatus | null;
+  providers: ProviderAuthReport;
+  sandbox: SandboxStatus;
+};
+
+export type SandboxStatus = {
+  supported: boolean;
+  bwrapPath: string | null;
+  enabled: boolean;
+  writable: string[];
+  readOnly: string[];
+  denials: string[];
+  notes: string[];
+};
+
+export type ProviderCredential = {
+  kind: "api-key" | "oauth" | "well-known";
+  source: string;
+  location: string;
+  expiresAt?: number;
+  expired?: boolean;
+  refreshable?: boolean;
+};
+
+export type ProviderAuthStatus = {
+  provider: string;
+  configured: boolean;
+  inUse: boolean;
+  credentials: ProviderCredential[];
+  notes: string[];
+};
+
+export type ProviderAuthReport = {
+  authPath?: string;
+  authError?: string;
+  providers: ProviderAuthStatus[];
+};
+
+export type NodeRuntimeInstall = {
+  version: string;
+  archive: string;
+  sha256: string;
+  origin: "network" | "cache";
+  installedAtMs: number;
+};
+
+export type NodeRuntimeStatus = {
+  active: "managed" | "system" | "missing";
+  enabled: boolean;
+  configuredVersion: string;
+  managed: NodeRuntimeInstall | null;
+  managedBinDir: string | null;
+  systemPath: string | null;
+  systemVersion: string | null;
+};
+
+/** Payload of the `openwork://node-runtime-progress` event. */
+export type NodeRuntimeProgress = {
+  phase: "downloading" | "done" | "failed";
+  origin: "network" | "cache" | null;
+  downloadedBytes: number;
+  totalBytes: number | null;
+  error: string | null;
+};
+
+export async function nodeRuntimeStatus(): Promise<NodeRuntimeStatus> {
+  return invoke<NodeRuntimeStatus>("node_runtime_status");
+}
+
+export async function nodeRuntimeInstall(): Promise<NodeRuntimeInstall> {
+  return invoke<NodeRuntimeInstall>("node_runtime_install");
+}
+
+export async function nodeRuntimeRemove(): Promise<NodeRuntimeStatus> {
+  return invoke<NodeRuntimeStatus>("node_runtime_remove");
+}
+
+export type McpRuntimeCheck = {
+  server: string;
+  source: string | null;
+  command: string | null;
+  enabled: boolean;
+  resolvedPath: string | null;
+  runtimeVersion: string | null;
+  ok: boolean;
+  note: string | null;
+};
+
+export type RuntimePreflight = {
+  projectDir: string | null;
+  mcp: McpRuntimeCheck[];
+  plugins: PluginEntry[];
+  notes: string[];
 };
 
 export type WorkspaceInfo = {
@@ -52,6 +165,11 @@ export type WorkspaceInfo = {
   openworkHostUrl?: string | null;
   openworkWorkspaceId?: string | null;
   openworkWorkspaceName?: string | null;
+  tokenSecret?: string;
+  opencodeProfile?: string;
+  engineLauncher?: string;
+  engineSandbox?: boolean;
+  resourceLimits?: ResourceLimits;
 };
 
 export type WorkspaceList = {
@@ -63,6 +181,16 @@ export type WorkspaceExportSummary = {
   outputPath: string;
   included: number;
   excluded: string[];
+  encrypted: boolean;
+  redactions: SecretRedaction[];
+  warnings: string[];
+};
+
+export type SecretRedaction = {
+  path: string;
+  location: string;
+  kind: "known-prefix" | "sensitive-key" | "authorization" | "env-assignment" | "high-entropy";
+  placeholder: string;
 };
 
 export async function engineStart(
@@ -79,6 +207,77 @@ export async function workspaceBootstrap(): Promise<WorkspaceList> {
   return invoke<WorkspaceList>("workspace_bootstrap");
 }
 
+export type WorkspacePreset = {
+  id: string;
+  name?: string | null;
+  description?: string | null;
+  skills: string[];
+  commands: OpencodeCommandDraft[];
+  plugins: string[];
+  mcp: Record<string, unknown>;
+  openwork?: Record<string, unknown> | null;
+  enterpriseCreatorSkills: boolean;
+  source: "builtin" | "bundled" | "user";
+};
+
+export async function workspacePresetsList(): Promise<WorkspacePreset[]> {
+  return invoke<WorkspacePreset[]>("workspace_presets_list");
+}
+
+export type WorkspaceTemplateSummary = {
+  preset: WorkspacePreset;
+  path: string;
+  excluded: string[];
+  envReferences: string[];
+};
+
+export async function workspaceSaveAsTemplate(input: {
+  workspaceId: string;
+  name: string;
+  description?: string | null;
+  overwrite?: boolean;
+}): Promise<WorkspaceTemplateSummary> {
+  return invoke<WorkspaceTemplateSummary>("workspace_save_as_template", {
+    workspaceId: input.workspaceId,
+    name: input.name,
+    description: input.description ?? null,
+    overwrite: input.overwrite ?? false,
+  });
+}
+
+export type SeedFileStatus =
+  | "current"
+  | "update"
+  | "new"
+  | "customized"
+  | "modified"
+  | "removed";
+
+export type WorkspaceUpgradePlan = {
+  workspaceId: string;
+  preset: string;
+  files: Array<{ path: string; status: SeedFileStatus; diff?: string | null }>;
+};
+
+export type WorkspaceUpgradeResult = {
+  workspaceId: string;
+  updated: string[];
+  skipped: string[];
+};
+
+export async function workspacePresetUpgradePlan(
+  workspaceId: string,
+): Promise<WorkspaceUpgradePlan> {
+  return invoke<WorkspaceUpgradePlan>("workspace_preset_upgrade_plan", { workspaceId });
+}
+
+export async function workspacePresetUpgradeApply(
+  workspaceId: string,
+  accepted: string[],
+): Promise<WorkspaceUpgradeResult> {
+  return invoke<WorkspaceUpgradeResult>("workspace_preset_upgrade_apply", { workspaceId, accepted });
+}
+
 export async function workspaceSetActive(workspaceId: string): Promise<WorkspaceList> {
   return invoke<WorkspaceList>("workspace_set_active", { workspaceId });
 }
@@ -141,6 +340,167 @@ export async function workspaceForget(workspaceId: string): Promise<WorkspaceLis
   return invoke<WorkspaceList>("workspace_forget", { workspaceId });
 }
 
+export async function workspaceRemoteTokenSet(input: {
+  workspaceId: string;
+  token?: string | null;
+  secretName?: string | null;
+}): Promise<WorkspaceList> {
+  return invoke<WorkspaceList>("workspace_remote_token_set", {
+    workspaceId: input.workspaceId,
+    token: input.token ?? null,
+    secretName: input.secretName ?? null,
+  });
+}
+
+export async function workspaceRemoteTokenGet(workspaceId: string): Promise<string | null> {
+  return invoke<string | null>("workspace_remote_token_get", { workspaceId });
+}
+
+export async function workspaceLauncherSet(input: {
+  workspaceId: string;
+  launcher?: string | null;
+}): Promise<WorkspaceList> {
+  return invoke<WorkspaceList>("workspace_launcher_set", {
+    workspaceId: input.workspaceId,
+    launcher: input.launcher ?? null,
+  });
+}
+
+export async function workspaceSandboxSet(input: {
+  workspaceId: string;
+  enabled: boolean;
+}): Promise<WorkspaceList> {
+  return invoke<WorkspaceList>("workspace_sandbox_set", {
+    workspaceId: input.workspaceId,
+    enabled: input.enabled,
+  });
+}
+
+export async function workspaceLimitsSet(input: {
+  workspaceId: string;
+  limits?: ResourceLimits | null;
+}): Promise<WorkspaceList> {
+  return invoke<WorkspaceList>("workspace_limits_set", {
+    workspaceId: input.workspaceId,
+    limits: input.limits ?? null,
+  });
+}
+
+export type OpencodeProfile = {
+  name: string;
+  path: string;
+  createdAtMs: number;
+  clonedFrom: string | null;
+  providers: string[];
+  workspaces: string[];
+};
+
+export async function opencodeProfileList(): Promise<OpencodeProfile[]> {
+  return invoke<OpencodeProfile[]>("opencode_profile_list");
+}
+
+export async function opencodeProfileCreate(input: {
+  name: string;
+  cloneFrom?: string | null;
+}): Promise<OpencodeProfile[]> {
+  return invoke<OpencodeProfile[]>("opencode_profile_create", {
+    name: input.name,
+    cloneFrom: input.cloneFrom ?? null,
+  });
+}
+
+/** `null` on either side means the user's shared OpenCode login. */
+export async function opencodeProfileCopyCredentials(input: {
+  from?: string | null;
+  to?: string | null;
+  providers?: string[] | null;
+}): Promise<string[]> {
+  return invoke<string[]>("opencode_profile_copy_credentials", {
+    from: input.from ?? null,
+    to: input.to ?? null,
+    providers: input.providers ?? null,
+  });
+}
+
+export async function opencodeProfileDelete(name: string): Promise<OpencodeProfile[]> {
+  return invoke<OpencodeProfile[]>("opencode_profile_delete", { name });
+}
+
+export async function workspaceProfileSet(input: {
+  workspaceId: string;
+  profile?: string | null;
+}): Promise<WorkspaceList> {
+  return invoke<WorkspaceList>("workspace_profile_set", {
+    workspaceId: input.workspaceId,
+    profile: input.profile ?? null,
+  });
+}
+
+export type AppSettings = {
+  enterpriseSkills: {
+    repository: string;
+    ref: string;
+    archiveUrl?: string | null;
+    sha256?: string | null;
+  };
+  nodeRuntime?: {
+    enabled: boolean;
+    version: string;
+    mirror: string;
+    sha256?: string | null;
+  };
+  engineLaunchers?: EngineLauncher[];
+};
+
+/**
+ * A wrapper OpenCode starts through. `command` is argv; `{opencode}`, `{hostname}`, `{port}`,
+ * `{projectDir}` and `{args}` are filled in, and the serve args are appended without `{args}`.
+ */
+export type EngineLauncher = {
+  id: string;
+  name: string;
+  command: string[];
+};
+
+export async function appSettingsGet(): Promise<AppSettings> {
+  return invoke<AppSettings>("app_settings_get");
+}
+
+export async function appSettingsSet(settings: AppSettings): Promise<AppSettings> {
+  return invoke<AppSettings>("app_settings_set", { settings });
+}
+
+/** Payload of the `openwork://enterprise-skills-progress` event. */
+export type EnterpriseSeedProgress = {
+  workspacePath: string;
+  phase: "downloading" | "extracting" | "done" | "failed";
+  origin: "network" | "cache" | null;
+  downloadedBytes: number;
+  totalBytes: number | null;
+  seededSkills: number;
+  error: string | null;
+};
+
+export type WorkspaceMoveProgress = {
+  workspaceId: string;
+  phase: "moving" | "renamed" | "copying" | "copied" | "rolling-back" | "done";
+  copiedBytes: number;
+  totalBytes: number;
+  copiedFiles: number;
+  totalFiles: number;
+  currentPath: string | null;
+};
+
+export async function workspaceMove(input: {
+  workspaceId: string;
+  targetDir: string;
+}): Promise<WorkspaceList> {
+  return invoke<WorkspaceList>("workspace_move", {
+    workspaceId: input.workspaceId,
+    targetDir: input.targetDir,
+  });
+}
+
 export async function workspaceAddAuthorizedRoot(input: {
   workspacePath: string;
   folderPath: string;
@@ -154,10 +514,61 @@ export async function workspaceAddAuthorizedRoot(input: {
 export async function workspaceExportConfig(input: {
   workspaceId: string;
   outputPath: string;
+  passphrase?: string | null;
+  includeSecrets?: boolean;
+  include?: string[];
+  exclude?: string[];
 }): Promise<WorkspaceExportSummary> {
   return invoke<WorkspaceExportSummary>("workspace_export_config", {
     workspaceId: input.workspaceId,
     outputPath: input.outputPath,
+    options: {
+      passphrase: input.passphrase ?? null,
+      includeSecrets: input.includeSecrets ?? false,
+      include: input.include ?? [],
+      exclude: input.exclude ?? [],
+    },
+  });
+}
+
+export type ConnectionExportSummary = {
+  outputPath: string;
+  exported: number;
+  withTokens: number;
+  encrypted: boolean;
+};
+
+export async function workspaceExportConnections(input: {
+  workspaceIds?: string[];
+  outputPath: string;
+  tokens?: Record<string, string>;
+  includeVaultTokens?: boolean;
+  passphrase?: string | null;
+}): Promise<ConnectionExportSummary> {
+  return invoke<ConnectionExportSummary>("workspace_export_connections", {
+    workspaceIds: input.workspaceIds ?? [],
+    outputPath: input.outputPath,
+    options: {
+      tokens: input.tokens ?? {},
+      includeVaultTokens: input.includeVaultTokens ?? false,
+      passphrase: input.passphrase ?? null,
+    },
+  });
+}
+
+export type ConnectionImportResult = WorkspaceList & {
+  added: string[];
+  skipped: string[];
+  tokens: Record<string, string>;
+};
+
+export async function workspaceImportConnections(input: {
+  bundlePath: string;
+  passphrase?: string | null;
+}): Promise<ConnectionImportResult> {
+  return invoke<ConnectionImportResult>("workspace_import_connections", {
+    bundlePath: input.bundlePath,
+    passphrase: input.passphrase ?? null,
   });
 }
 
@@ -165,11 +576,67 @@ export async function workspaceImportConfig(input: {
   archivePath: string;
   targetDir: string;
   name?: string | null;
+  passphrase?: string | null;
+  secrets?: Record<string, string>;
+  merge?: boolean;
+  resolutions?: Record<string, ImportResolution>;
 }): Promise<WorkspaceList> {
   return invoke<WorkspaceList>("workspace_import_config", {
     archivePath: input.archivePath,
     targetDir: input.targetDir,
     name: input.name ?? null,
+    options: {
+      passphrase: input.passphrase ?? null,
+      secrets: input.secrets ?? {},
+      merge: input.merge ?? false,
+      resolutions: input.resolutions ?? {},
+    },
+  });
+}
+
+export type ImportResolution = "keep" | "overwrite" | "rename" | "merge";
+
+export type ImportFilePlan = {
+  path: string;
+  status: "new" | "identical" | "conflict";
+  mergeable: boolean;
+  action: "create" | "skip" | "overwrite" | "rename" | "merge";
+  writePath: string | null;
+};
+
+export type WorkspaceImportPreview = {
+  encrypted: boolean;
+  manifest: Record<string, unknown> | null;
+  targetExists: boolean;
+  files: ImportFilePlan[];
+  skippedSecrets: string[];
+  redactions: SecretRedaction[];
+  warnings: string[];
+};
+
+export async function workspaceImportPreview(input: {
+  archivePath: string;
+  targetDir?: string | null;
+  passphrase?: string | null;
+  resolutions?: Record<string, ImportResolution>;
+}): Promise<WorkspaceImportPreview> {
+  return invoke<WorkspaceImportPreview>("workspace_import_preview", {
+    archivePath: input.archivePath,
+    targetDir: input.targetDir ?? null,
+    options: {
+      passphrase: input.passphrase ?? null,
+      resolutions: input.resolutions ?? {},
+    },
+  });
+}
+
+export async function workspaceArchiveRedactions(
+  archivePath: string,
+  passphrase?: string | null,
+): Promise<SecretRedaction[]> {
+  return invoke<SecretRedaction[]>("workspace_archive_redactions", {
+    archivePath,
+    passphrase: passphrase ?? null,
   });
 }
 
@@ -190,6 +657,8 @@ export type WorkspaceOpenworkConfig = {
     preset?: string | null;
   } | null;
   authorizedRoots: string[];
+  env?: Record<string, string>;
+  envFiles?: string[];
 };
 
 export async function workspaceOpenworkRead(input: {
@@ -210,6 +679,124 @@ export async function workspaceOpenworkWrite(input: {
   });
 }
 
+export type VaultStatus = {
+  path: string;
+  exists: boolean;
+  mode: "machine-key" | "passphrase";
+  locked: boolean;
+  secretCount?: number;
+  error?: string;
+};
+
+export type VaultSecretInfo = {
+  name: string;
+  redacted: string;
+  description?: string;
+  createdAt: number;
+  updatedAt: number;
+};
+
+export async function vaultStatus(): Promise<VaultStatus> {
+  return invoke<VaultStatus>("vault_status");
+}
+
+export async function vaultUnlock(passphrase: string): Promise<VaultStatus> {
+  return invoke<VaultStatus>("vault_unlock", { passphrase });
+}
+
+export async function vaultLock(): Promise<VaultStatus> {
+  return invoke<VaultStatus>("vault_lock");
+}
+
+export async function vaultSetPassphrase(input: {
+  current?: string | null;
+  passphrase: string | null;
+}): Promise<VaultStatus> {
+  return invoke<VaultStatus>("vault_set_passphrase", {
+    current: input.current ?? null,
+    passphrase: input.passphrase,
+  });
+}
+
+export async function vaultSecretList(): Promise<VaultSecretInfo[]> {
+  return invoke<VaultSecretInfo[]>("vault_secret_list");
+}
+
+export async function vaultSecretGet(name: string): Promise<VaultSecretInfo> {
+  return invoke<VaultSecretInfo>("vault_secret_get", { name });
+}
+
+export async function vaultSecretSet(input: {
+  name: string;
+  value: string;
+  description?: string | null;
+}): Promise<VaultSecretInfo> {
+  return invoke<VaultSecretInfo>("vault_secret_set", {
+    name: input.name,
+    value: input.value,
+    description: input.description ?? null,
+  });
+}
+
+export async function vaultSecretDelete(name: string): Promise<VaultSecretInfo[]> {
+  return invoke<VaultSecretInfo[]>("vault_secret_delete", { name });
+}
+
+export type WorkspaceEnvSource = "env-file" | "openwork" | "vault";
+
+export type WorkspaceEnvVar = {
+  name: string;
+  source: WorkspaceEnvSource;
+  origin?: string;
+  value?: string;
+};
+
+export type EnvFileStatus = {
+  path: string;
+  resolved?: string;
+  loaded: boolean;
+  keys: string[];
+  error?: string;
+};
+
+export type WorkspaceEnvReport = {
+  vars: WorkspaceEnvVar[];
+  files: EnvFileStatus[];
+  warnings: string[];
+};
+
+export async function workspaceEnvList(workspacePath: string): Promise<WorkspaceEnvReport> {
+  return invoke<WorkspaceEnvReport>("workspace_env_list", { workspacePath });
+}
+
+export async function workspaceEnvSet(input: {
+  workspacePath: string;
+  name: string;
+  value: string;
+  secret: boolean;
+}): Promise<WorkspaceEnvReport> {
+  return invoke<WorkspaceEnvReport>("workspace_env_set", {
+    workspacePath: input.workspacePath,
+    name: input.name,
+    value: input.value,
+    secret: input.secret,
+  });
+}
+
+export async function workspaceEnvRemove(
+  workspacePath: string,
+  name: string,
+): Promise<WorkspaceEnvReport> {
+  return invoke<WorkspaceEnvReport>("workspace_env_remove", { workspacePath, name });
+}
+
+export async function workspaceEnvFilesSet(
+  workspacePath: string,
+  files: string[],
+): Promise<WorkspaceEnvReport> {
+  return invoke<WorkspaceEnvReport>("workspace_env_files_set", { workspacePath, files });
+}
+
 export async function opencodeCommandList(input: {
   scope: "workspace" | "global";
   projectDir: string;
@@ -258,9 +845,11 @@ export async function engineInfo(): Promise<EngineInfo> {
 
 export async function engineDoctor(options?: {
   preferSidecar?: boolean;
+  projectDir?: string;
 }): Promise<EngineDoctorResult> {
   return invoke<EngineDoctorResult>("engine_doctor", {
     preferSidecar: options?.preferSidecar ?? false,
+    projectDir: options?.projectDir ?? null,
   });
 }
 
@@ -386,12 +975,75 @@ export async function readOpencodeConfig(
   return invoke<OpencodeConfigFile>("read_opencode_config", { scope, projectDir });
 }
 
+export type ConfigDiagnostic = {
+  line: number;
+  column: number;
+  pointer: string;
+  message: string;
+};
+
+export type ConfigValidation = {
+  valid: boolean;
+  diagnostics: ConfigDiagnostic[];
+};
+
+export class OpencodeConfigError extends Error {
+  diagnostics: ConfigDiagnostic[];
+
+  constructor(message: string, diagnostics: ConfigDiagnostic[]) {
+    super(message);
+    this.name = "OpencodeConfigError";
+    this.diagnostics = diagnostics;
+  }
+}
+
 export async function writeOpencodeConfig(
   scope: "project" | "global",
   projectDir: string,
   content: string,
+  options?: { force?: boolean },
 ): Promise<ExecResult> {
-  return invoke<ExecResult>("write_opencode_config", { scope, projectDir, content });
+  try {
+    return await invoke<ExecResult>("write_opencode_config", {
+      scope,
+      projectDir,
+      content,
+      force: options?.force ?? false,
+    });
+  } catch (error) {
+    if (error && typeof error === "object" && "message" in error) {
+      const { message, diagnostics } = error as {
+        message: string;
+        diagnostics?: ConfigDiagnostic[];
+      };
+      throw new OpencodeConfigError(message, diagnostics ?? []);
+    }
+    throw error;
+  }
+}
+
+export async function validateOpencodeConfig(content: string): Promise<ConfigValidation> {
+  return invoke<ConfigValidation>("validate_opencode_config", { content });
+}
+
+export type EffectiveConfigLayer = {
+  scope: "global" | "custom" | "project" | "inline";
+  source: string;
+  error?: string;
+};
+
+export type EffectiveOpencodeConfig = {
+  config: Record<string, unknown>;
+  layers: EffectiveConfigLayer[];
+  provenance: Record<string, string>;
+};
+
+export async function resolveEffectiveOpencodeConfig(projectDir: string): Promise<EffectiveOpencodeConfig> {
+  return invoke<EffectiveOpencodeConfig>("resolve_effective_opencode_config", { projectDir });
+}
+
+export async function providerAuthStatus(projectDir?: string | null): Promise<ProviderAuthReport> {
+  return invoke<ProviderAuthReport>("provider_auth_status", { projectDir: projectDir ?? null });
 }
 
 export async function resetOpenworkState(mode: "onboarding" | "all"): Promise<void> {
@@ -570,3 +1222,134 @@ export async function opencodeMcpAuth(
     serverName: safeServerName,
   });
 }
+
+export type McpServerConfig =
+  | {
+      type: "local";
+      command: string[];
+      environment?: Record<string, string>;
+      enabled?: boolean;
+      timeout?: number;
+    }
+  | {
+      type: "remote";
+      url: string;
+      headers?: Record<string, string>;
+      oauth?: unknown;
+      enabled?: boolean;
+      timeout?: number;
+    };
+
+export type McpServerEntry = {
+  name: string;
+  server: McpServerConfig | null;
+  error?: string;
+};
+
+export type McpTool = {
+  name: string;
+  description?: string;
+};
+
+export type McpTestResult = {
+  ok: boolean;
+  stage?: "spawn" | "initialize" | "tools/list";
+  error?: string;
+  serverName?: string;
+  serverVersion?: string;
+  protocolVersion?: string;
+  tools: McpTool[];
+  stderr: string;
+  elapsedMs: number;
+};
+
+export async function mcpList(
+  scope: "project" | "global",
+  projectDir: string,
+): Promise<McpServerEntry[]> {
+  return invoke<McpServerEntry[]>("mcp_list", { scope, projectDir });
+}
+
+export async function mcpAdd(
+  scope: "project" | "global",
+  projectDir: string,
+  name: string,
+  server: McpServerConfig,
+): Promise<McpServerEntry[]> {
+  return invoke<McpServerEntry[]>("mcp_add", {
+    scope,
+    projectDir,
+    name: validateMcpServerName(name),
+    server,
+  });
+}
+
+export async function mcpUpdate(
+  scope: "project" | "global",
+  projectDir: string,
+  name: string,
+  server: McpServerConfig,
+): Promise<McpServerEntry[]> {
+  return invoke<McpServerEntry[]>("mcp_update", { scope, projectDir, name, server });
+}
+
+export async function mcpRemove(
+  scope: "project" | "global",
+  projectDir: string,
+  name: string,
+): Promise<McpServerEntry[]> {
+  return invoke<McpServerEntry[]>("mcp_remove", { scope, projectDir, name });
+}
+
+export async function mcpTest(
+  server: McpServerConfig,
+  options?: { projectDir?: string; timeoutMs?: number },
+): Promise<McpTestResult> {
+  return invoke<McpTestResult>("mcp_test", {
+    projectDir: options?.projectDir ?? null,
+    server,
+    timeoutMs: options?.timeoutMs ?? null,
+  });
+}
+
+export type PluginEntry = {
+  spec: string;
+  name: string;
+  version?: string;
+  source: "npm" | "file";
+  cached: boolean;
+  cachedVersion?: string;
+  cachePath?: string;
+};
+
+export async function pluginList(
+  scope: "project" | "global",
+  projectDir: string,
+): Promise<PluginEntry[]> {
+  return invoke<PluginEntry[]>("plugin_list", { scope, projectDir });
+}
+
+export async function pluginAdd(
+  scope: "project" | "global",
+  projectDir: string,
+  spec: string,
+): Promise<PluginEntry[]> {
+  return invoke<PluginEntry[]>("plugin_add", { scope, projectDir, spec });
+}
+
+export async function pluginRemove(
+  scope: "project" | "global",
+  projectDir: string,
+  name: string,
+): Promise<PluginEntry[]> {
+  return invoke<PluginEntry[]>("plugin_remove", { scope, projectDir, name });
+}
+
+export async function pluginPin(
+  scope: "project" | "global",
+  projectDir: string,
+  name: string,
+  version: string | null,
+): Promise<PluginEntry[]> {
+  return invoke<PluginEntry[]>("plugin_pin", { scope, projectDir, name, version });
+}
@@ -1,6 +1,33 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::config::effective::{resolve_effective_config, EffectiveOpencodeConfig};
+use crate::config::validate::{
+    validate_opencode_config as validate_inner, ConfigDiagnostic, ConfigValidation,
+};
 use crate::config::{read_opencode_config as read_inner, write_opencode_config as write_inner};
+use crate::engine::auth::{app_provider_auth_report, ProviderAuthReport};
+use crate::engine::profiles::workspace_config_inputs;
 use crate::types::{ExecResult, OpencodeConfigFile};
 
+/// Error returned by `write_opencode_config`; `diagnostics` is set when validation failed.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigWriteError {
+    pub message: String,
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl From<String> for ConfigWriteError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            diagnostics: Vec::new(),
+        }
+    }
+}
+
 #[tauri::command]
 pub fn read_opencode_config(
     scope: String,
@@ -9,11 +36,59 @@ pub fn read_opencode_config(
     read_inner(scope.trim(), &project_dir)
 }
 
+/// Writes the config after checking it against the OpenCode schema. `force` skips the check.
 #[tauri::command]
 pub fn write_opencode_config(
     scope: String,
     project_dir: String,
     content: String,
-) -> Result<ExecResult, String> {
-    write_inner(scope.trim(), &project_dir, &content)
+    force: Option<bool>,
+) -> Result<ExecResult, ConfigWriteError> {
+    if !force.unwrap_or(false) {
+        let validation = validate_inner(&content)?;
+        if !validation.valid {
+            let first = &validation.diagnostics[0];
+            return Err(ConfigWriteError {
+                message: format!(
+                    "opencode config is invalid at line {}, column {}: {}",
+                    first.line, first.column, first.message
+                ),
+                diagnostics: validation.diagnostics,
+            });
+        }
+    }
+    Ok(write_inner(scope.trim(), &project_dir, &content)?)
+}
+
+#[tauri::command]
+pub fn validate_opencode_config(content: String) -> Result<ConfigValidation, String> {
+    validate_inner(&content)
+}
+
+/// The config OpenCode would run with in `project_dir`, with the file each value came from.
+#[tauri::command]
+pub fn resolve_effective_opencode_config(
+    app: tauri::AppHandle,
+    project_dir: String,
+) -> Result<EffectiveOpencodeConfig, String> {
+    if project_dir.trim().is_empty() {
+        return Err("projectDir is required".to_string());
+    }
+    let project_dir = Path::new(project_dir.trim());
+    Ok(resolve_effective_config(
+        &workspace_config_inputs(&app, Some(project_dir)),
+        Some(project_dir),
+    ))
+}
+
+/// Which model providers have credentials, without returning any of them.
+#[tauri::command]
+pub fn provider_auth_status(
+    app: tauri::AppHandle,
+    project_dir: Option<String>,
+) -> ProviderAuthReport {
+    let project_dir = project_dir
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty());
+    app_provider_auth_report(&app, project_dir.as_deref().map(Path::new))
 }
@@ -1,17 +1,28 @@
+use std::path::PathBuf;
+
 use tauri::{AppHandle, Manager, State};
 
-use crate::config::{read_opencode_config, write_opencode_config};
+use crate::config::{read_opencode_config, write_opencode_config, DEFAULT_OPENCODE_CONFIG};
+use crate::engine::auth::app_provider_auth_report;
 use crate::engine::doctor::{
     opencode_serve_help, opencode_version, resolve_engine_path, resolve_sidecar_candidate,
 };
+use crate::engine::launcher::{uses_local_opencode, workspace_launcher};
 use crate::engine::manager::EngineManager;
-use crate::engine::spawn::{find_free_port, spawn_engine};
+use crate::engine::node_runtime::{app_node_runtime_status, managed_node_bin_dir};
+use crate::engine::preflight::runtime_preflight;
+use crate::engine::profiles::workspace_config_inputs;
+use crate::engine::sandbox::{
+    is_sandbox_denial, record_denial, sandbox_status, workspace_sandbox_enabled,
+    workspace_sandbox_plan,
+};
+use crate::engine::spawn::{find_free_port, spawn_engine, EngineCommand};
 use crate::commands::owpenbot::owpenbot_start;
 use crate::openwork_server::{manager::OpenworkServerManager, resolve_connect_url, start_openwork_server};
 use crate::owpenbot::manager::OwpenbotManager;
-use crate::types::{EngineDoctorResult, EngineInfo, ExecResult};
+use crate::types::{EngineDoctorResult, EngineInfo, ExecResult, WorkspaceType};
 use crate::utils::truncate_output;
-use serde_json::json;
+use crate::workspace::state::load_workspace_state;
 use tauri_plugin_shell::process::CommandEvent;
 
 #[derive(Default)]
@@ -20,6 +31,22 @@ struct OutputState {
     stderr: String,
     exited: bool,
     exit_code: Option<i32>,
+    exit_reason: Option<String>,
+}
+
+/// Logs an engine output line that looks like a write the sandbox refused.
+fn note_sandbox_denial(denials: Option<&std::sync::Mutex<Vec<String>>>, line: &str) {
+    let Some(denials) = denials else {
+        return;
+    };
+    if !is_sandbox_denial(line) {
+        return;
+    }
+    if let Ok(mut denials) = denials.lock() {
+        if record_denial(&mut denials, line) {
+            println!("[engine] sandbox denied: {}", line.trim());
+        }
+    }
 }
 
 #[tauri::command]
@@ -45,9 +72,42 @@ pub fn engine_stop(
     EngineManager::snapshot_locked(&mut state)
 }
 
+/// The directory doctor preflights: `project_dir`, else the active local workspace.
+fn doctor_project_dir(app: &AppHandle, project_dir: Option<String>) -> Option<PathBuf> {
+    if let Some(dir) = project_dir.filter(|dir| !dir.trim().is_empty()) {
+        return Some(PathBuf::from(dir.trim()));
+    }
+    let state = load_workspace_state(app).ok()?;
+    state
+        .workspaces
+        .iter()
+        .find(|workspace| {
+            workspace.id == state.active_id && workspace.workspace_type == WorkspaceType::Local
+        })
+        .map(|workspace| PathBuf::from(&workspace.path))
+        .filter(|path| path.is_dir())
+}
+
+/// Runs off the main thread: it starts OpenCode and MCP runtimes to read their versions.
 #[tauri::command]
-pub fn engine_doctor(app: AppHandle, prefer_sidecar: Option<bool>) -> EngineDoctorResult {
+pub async fn engine_doctor(
+    app: AppHandle,
+    prefer_sidecar: Option<bool>,
+    project_dir: Option<String>,
+) -> Result<EngineDoctorResult, String> {
     let prefer_sidecar = prefer_sidecar.unwrap_or(false);
+    tauri::async_runtime::spawn_blocking(move || {
+        run_engine_doctor(&app, prefer_sidecar, project_dir)
+    })
+    .await
+    .map_err(|e| format!("Engine doctor failed: {e}"))
+}
+
+fn run_engine_doctor(
+    app: &AppHandle,
+    prefer_sidecar: bool,
+    project_dir: Option<String>,
+) -> EngineDoctorResult {
     let resource_dir = app.path().resource_dir().ok();
 
     let current_bin_dir = tauri::process::current_binary(&app.env())
@@ -75,6 +135,31 @@ pub fn engine_doctor(app: AppHandle, prefer_sidecar: Option<bool>) -> EngineDoct
             None => (None, false, None, None, None),
         };
 
+    let node_runtime = app_node_runtime_status(app).ok();
+    let managed_path: Vec<PathBuf> = managed_node_bin_dir(app).into_iter().collect();
+    let project_dir = doctor_project_dir(app, project_dir);
+    let preflight = runtime_preflight(
+        &workspace_config_inputs(app, project_dir.as_deref()),
+        project_dir.as_deref(),
+        &managed_path,
+    );
+    let providers = app_provider_auth_report(app, project_dir.as_deref());
+    let denials = app
+        .state::<EngineManager>()
+        .inner
+        .lock()
+        .ok()
+        .filter(|state| state.sandboxed)
+        .and_then(|state| {
+            state
+                .sandbox_denials
+                .lock()
+                .ok()
+                .map(|denials| denials.clone())
+        })
+        .unwrap_or_default();
+    let sandbox = sandbox_status(app, project_dir.as_deref(), denials);
+
     EngineDoctorResult {
         found: resolved.is_some(),
         in_path,
@@ -85,6 +170,10 @@ pub fn engine_doctor(app: AppHandle, prefer_sidecar: Option<bool>) -> EngineDoct
         serve_help_status,
         serve_help_stdout,
         serve_help_stderr,
+        preflight,
+        node_runtime,
+        providers,
+        sandbox,
     }
 }
 
@@ -146,16 +235,13 @@ pub fn engine_start(
 
     let config = read_opencode_config("project", &project_dir)?;
     if !config.exists {
-        let content = serde_json::to_string_pretty(&json!({
-            "$schema": "https://opencode.ai/config.json",
-        }))
-        .map_err(|e| format!("Failed to serialize opencode config: {e}"))?;
-        let write_result = write_opencode_config("project", &project_dir, &format!("{content}\n"))?;
+        let write_result = write_opencode_config("project", &project_dir, DEFAULT_OPENCODE_CONFIG)?;
         if !write_result.ok {
             return Err(write_result.stderr);
         }
     }
 
+    let launcher = workspace_launcher(&app, &project_dir)?;
     let bind_host = "0.0.0.0".to_string();
     let client_host = "127.0.0.1".to_string();
     let port = find_free_port()?;
@@ -170,22 +256,42 @@ pub fn engine_start(
     let prefer_sidecar = prefer_sidecar.unwrap_or(false);
     let (program, _in_path, notes) =
         resolve_engine_path(prefer_sidecar, resource_dir.as_deref(), current_bin_dir.as_deref());
-    let Some(program) = program else {
+    // A launcher that names its own OpenCode (in a container, over ssh) doesn't need ours.
+    let needs_local = launcher.as_ref().is_none_or(uses_local_opencode);
+    if program.is_none() && needs_local {
         let notes_text = notes.join("\n");
         return Err(format!(
       "OpenCode CLI not found.\n\nInstall with:\n- brew install anomalyco/tap/opencode\n- curl -fsSL https://opencode.ai/install | bash\n\nNotes:\n{notes_text}"
     ));
-    };
+    }
 
     let (sidecar_candidate, _sidecar_notes) =
         resolve_sidecar_candidate(prefer_sidecar, resource_dir.as_deref(), current_bin_dir.as_deref());
     let use_sidecar = prefer_sidecar
         && sidecar_candidate
             .as_ref()
-            .is_some_and(|candidate| candidate == &program);
+            .is_some_and(|candidate| program.as_ref() == Some(candidate));
+
+    let sandbox = if workspace_sandbox_enabled(&app, &project_dir) {
+        if !cfg!(target_os = "linux") {
+            return Err("The engine sandbox is only available on Linux".to_string());
+        }
+        Some(workspace_sandbox_plan(
+            &app,
+            &project_dir,
+            program.as_deref(),
+        )?)
+    } else {
+        None
+    };
 
-    let (mut rx, child) =
-        spawn_engine(&app, &program, &bind_host, port, &project_dir, use_sidecar)?;
+    let engine = EngineCommand {
+        program: program.as_deref(),
+        use_sidecar,
+        launcher: launcher.as_ref(),
+        sandbox: sandbox.as_ref(),
+    };
+    let (mut rx, child, limits) = spawn_engine(&app, &engine, &bind_host, port, &project_dir)?;
 
     state.last_stdout = None;
     state.last_stderr = None;
@@ -194,12 +300,15 @@ pub fn engine_start(
     let output_state = std::sync::Arc::new(std::sync::Mutex::new(OutputState::default()));
     let output_state_handle = output_state.clone();
     let state_handle = manager.inner.clone();
+    let denials = sandbox.is_some().then(|| state.sandbox_denials.clone());
+    let exit_limits = limits.clone();
 
     tauri::async_runtime::spawn(async move {
         while let Some(event) = rx.recv().await {
             match event {
                 CommandEvent::Stdout(line_bytes) => {
                     let line = String::from_utf8_lossy(&line_bytes).to_string();
+                    note_sandbox_denial(denials.as_deref(), &line);
                     if let Ok(mut output) = output_state_handle.lock() {
                         output.stdout.push_str(&line);
                     }
@@ -215,6 +324,7 @@ pub fn engine_start(
                 }
                 CommandEvent::Stderr(line_bytes) => {
                     let line = String::from_utf8_lossy(&line_bytes).to_string();
+                    note_sandbox_denial(denials.as_deref(), &line);
                     if let Ok(mut output) = output_state_handle.lock() {
                         output.stderr.push_str(&line);
                     }
@@ -229,12 +339,22 @@ pub fn engine_start(
                     }
                 }
                 CommandEvent::Terminated(payload) => {
+                    let mut exit_reason = None;
                     if let Ok(mut output) = output_state_handle.lock() {
                         output.exited = true;
                         output.exit_code = payload.code;
+                        let combined = format!("{}\n{}", output.stdout, output.stderr);
+                        exit_reason = exit_limits
+                            .as_ref()
+                            .and_then(|limits| limits.kill_reason(&payload, &combined));
+                        output.exit_reason = exit_reason.clone();
+                    }
+                    if let Some(reason) = exit_reason.as_deref() {
+                        println!("[engine] OpenCode {reason}");
                     }
                     if let Ok(mut state) = state_handle.try_lock() {
                         state.child_exited = true;
+                        state.exit_reason = exit_reason;
                     }
                 }
                 CommandEvent::Error(message) => {
@@ -284,9 +404,15 @@ pub fn engine_start(
                     format!("\n\n{}", parts.join("\n\n"))
                 };
 
+                let reason = output
+                    .exit_reason
+                    .as_deref()
+                    .map(|reason| format!(" ({reason})"))
+                    .unwrap_or_default();
                 return Err(format!(
-                    "OpenCode exited immediately with status {}.{}",
+                    "OpenCode exited immediately with status {}{}.{}",
                     output.exit_code.unwrap_or(-1),
+                    reason,
                     suffix
                 ));
             }
@@ -300,6 +426,10 @@ pub fn engine_start(
     }
 
     state.child = Some(child);
+    state.prefer_sidecar = prefer_sidecar;
+    state.launcher = launcher.map(|launcher| launcher.name);
+    state.sandboxed = sandbox.is_some();
+    state.limits = limits;
     state.project_dir = Some(project_dir.clone());
     state.hostname = Some(client_host.clone());
     state.port = Some(port);
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use crate::config::mcp::{
+    add_mcp_server, list_mcp_servers, remove_mcp_server, resolve_server_environment,
+    update_mcp_server, McpServerConfig, McpServerEntry,
+};
+use crate::config::{edit_opencode_document, load_opencode_document};
+use crate::mcp_probe::{test_local_mcp_server, McpTestResult};
+use crate::vault::Vault;
+use crate::workspace::env::workspace_spawn_env;
+
+const DEFAULT_MCP_TEST_TIMEOUT_MS: u64 = 30_000;
+
+#[tauri::command]
+pub fn mcp_list(scope: String, project_dir: String) -> Result<Vec<McpServerEntry>, String> {
+    let doc = load_opencode_document(&scope, &project_dir)?;
+    Ok(list_mcp_servers(&doc))
+}
+
+#[tauri::command]
+pub fn mcp_add(
+    scope: String,
+    project_dir: String,
+    name: String,
+    server: McpServerConfig,
+) -> Result<Vec<McpServerEntry>, String> {
+    let doc = edit_opencode_document(&scope, &project_dir, |doc| {
+        add_mcp_server(doc, &name, &server)
+    })?;
+    Ok(list_mcp_servers(&doc))
+}
+
+#[tauri::command]
+pub fn mcp_update(
+    scope: String,
+    project_dir: String,
+    name: String,
+    server: McpServerConfig,
+) -> Result<Vec<McpServerEntry>, String> {
+    let doc = edit_opencode_document(&scope, &project_dir, |doc| {
+        update_mcp_server(doc, &name, &server)
+    })?;
+    Ok(list_mcp_servers(&doc))
+}
+
+#[tauri::command]
+pub fn mcp_remove(
+    scope: String,
+    project_dir: String,
+    name: String,
+) -> Result<Vec<McpServerEntry>, String> {
+    let doc = edit_opencode_document(&scope, &project_dir, |doc| remove_mcp_server(doc, &name))?;
+    Ok(list_mcp_servers(&doc))
+}
+
+/// Starts a local server the way OpenCode would and lists its tools, with the workspace env
+/// and vault references the engine would pass it. Remote servers are rejected; OpenCode
+/// reaches them itself, often behind OAuth.
+#[tauri::command]
+pub async fn mcp_test(
+    app: AppHandle,
+    project_dir: Option<String>,
+    server: McpServerConfig,
+    timeout_ms: Option<u64>,
+) -> Result<McpTestResult, String> {
+    let McpServerConfig::Local {
+        command,
+        environment,
+        ..
+    } = server
+    else {
+        return Err("Only local MCP servers can be tested".to_string());
+    };
+    let cwd = project_dir
+        .map(|dir| PathBuf::from(dir.trim()))
+        .filter(|dir| !dir.as_os_str().is_empty());
+    if let Some(cwd) = &cwd {
+        if !cwd.is_dir() {
+            return Err(format!("{} is not a directory", cwd.display()));
+        }
+    }
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_MCP_TEST_TIMEOUT_MS));
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut env: BTreeMap<String, String> = cwd
+            .as_ref()
+            .map(|dir| workspace_spawn_env(&app, &dir.to_string_lossy(), "MCP test"))
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let resolved = resolve_server_environment(
+            &environment.unwrap_or_default(),
+            |name| env.get(name).cloned().or_else(|| std::env::var(name).ok()),
+            |name| Vault::for_app(&app)?.secret_value(name),
+        )?;
+        env.extend(resolved);
+        Ok(test_local_mcp_server(
+            &command,
+            &env,
+            cwd.as_deref(),
+            timeout,
+        ))
+    })
+    .await
+    .map_err(|e| format!("MCP test failed: {e}"))?
+}
@@ -1,11 +1,12 @@
-use std::collections::HashSet;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
+use crate::config::mcp::validate_mcp_server_name;
 use crate::engine::doctor::resolve_engine_path;
-use crate::paths::home_dir;
+use crate::paths::opencode_cache_candidates;
 use crate::platform::command_for_program;
-use crate::types::{ExecResult, WorkspaceOpenworkConfig};
+use crate::types::ExecResult;
+use crate::workspace::files::read_workspace_openwork_config;
 use crate::workspace::state::load_workspace_state;
 use tauri::{AppHandle, Manager};
 
@@ -16,88 +17,6 @@ pub struct CacheResetResult {
     pub errors: Vec<String>,
 }
 
-fn opencode_cache_candidates() -> Vec<PathBuf> {
-    let mut candidates: Vec<PathBuf> = Vec::new();
-
-    if let Ok(value) = std::env::var("XDG_CACHE_HOME") {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            candidates.push(PathBuf::from(trimmed).join("opencode"));
-        }
-    }
-
-    if let Some(home) = home_dir() {
-        candidates.push(home.join(".cache").join("opencode"));
-
-        #[cfg(target_os = "macos")]
-        {
-            candidates.push(home.join("Library").join("Caches").join("opencode"));
-        }
-    }
-
-    #[cfg(windows)]
-    {
-        if let Ok(value) = std::env::var("LOCALAPPDATA") {
-            let trimmed = value.trim();
-            if !trimmed.is_empty() {
-                candidates.push(PathBuf::from(trimmed).join("opencode"));
-            }
-        }
-        if let Ok(value) = std::env::var("APPDATA") {
-            let trimmed = value.trim();
-            if !trimmed.is_empty() {
-                candidates.push(PathBuf::from(trimmed).join("opencode"));
-            }
-        }
-    }
-
-    let mut seen = HashSet::new();
-    candidates
-        .into_iter()
-        .filter(|path| seen.insert(path.to_string_lossy().to_string()))
-        .collect()
-}
-
-fn validate_server_name(name: &str) -> Result<String, String> {
-    let trimmed = name.trim();
-    if trimmed.is_empty() {
-        return Err("server_name is required".to_string());
-    }
-
-    if trimmed.starts_with('-') {
-        return Err("server_name must not start with '-'".to_string());
-    }
-
-    if !trimmed
-        .chars()
-        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
-    {
-        return Err("server_name must be alphanumeric with '-' or '_'".to_string());
-    }
-
-    Ok(trimmed.to_string())
-}
-
-fn read_workspace_openwork_config(
-    workspace_path: &Path,
-) -> Result<WorkspaceOpenworkConfig, String> {
-    let openwork_path = workspace_path.join(".opencode").join("openwork.json");
-    if !openwork_path.exists() {
-        let mut cfg = WorkspaceOpenworkConfig::default();
-        let workspace_value = workspace_path.to_string_lossy().to_string();
-        if !workspace_value.trim().is_empty() {
-            cfg.authorized_roots.push(workspace_value);
-        }
-        return Ok(cfg);
-    }
-
-    let raw = fs::read_to_string(&openwork_path)
-        .map_err(|e| format!("Failed to read {}: {e}", openwork_path.display()))?;
-
-    serde_json::from_str::<WorkspaceOpenworkConfig>(&raw)
-        .map_err(|e| format!("Failed to parse {}: {e}", openwork_path.display()))
-}
-
 fn load_authorized_roots(app: &AppHandle) -> Result<Vec<PathBuf>, String> {
     let state = load_workspace_state(app)?;
     let mut roots = Vec::new();
@@ -229,7 +148,7 @@ pub fn opencode_mcp_auth(
     server_name: String,
 ) -> Result<ExecResult, String> {
     let project_dir = validate_project_dir(&app, &project_dir)?;
-    let server_name = validate_server_name(&server_name)?;
+    let server_name = validate_mcp_server_name(&server_name)?;
 
     let resource_dir = app.path().resource_dir().ok();
     let current_bin_dir = tauri::process::current_binary(&app.env())
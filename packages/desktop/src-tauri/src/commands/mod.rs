@@ -1,10 +1,17 @@
 pub mod command_files;
 pub mod config;
 pub mod engine;
+pub mod mcp;
 pub mod misc;
 pub mod openwork_server;
 pub mod opkg;
 pub mod owpenbot;
+pub mod plugins;
+pub mod profiles;
+pub mod runtime;
+pub mod settings;
 pub mod skills;
 pub mod updater;
+pub mod vault;
 pub mod workspace;
+pub mod workspace_env;
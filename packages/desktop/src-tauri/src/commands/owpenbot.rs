@@ -71,7 +71,7 @@ pub fn owpenbot_start(
         .map_err(|_| "owpenbot mutex poisoned".to_string())?;
     OwpenbotManager::stop_locked(&mut state);
 
-    let (mut rx, child) = spawn_owpenbot(&app, &workspace_path, opencode_url.as_deref())?;
+    let (mut rx, child, limits) = spawn_owpenbot(&app, &workspace_path, opencode_url.as_deref())?;
 
     state.child = Some(child);
     state.child_exited = false;
@@ -117,7 +117,14 @@ pub fn owpenbot_start(
                 CommandEvent::Terminated(payload) => {
                     if let Ok(mut state) = state_handle.try_lock() {
                         state.child_exited = true;
-                        if let Some(code) = payload.code {
+                        let stderr = state.last_stderr.as_deref().unwrap_or_default();
+                        let reason = limits
+                            .as_ref()
+                            .and_then(|limits| limits.kill_reason(&payload, stderr));
+                        if let Some(reason) = reason {
+                            println!("[owpenbot] Owpenbot {reason}");
+                            state.last_stderr = Some(format!("Owpenbot {reason}."));
+                        } else if let Some(code) = payload.code {
                             let next = format!("Owpenbot exited (code {code}).");
                             state.last_stderr = Some(truncate_output(&next, 8000));
                         }
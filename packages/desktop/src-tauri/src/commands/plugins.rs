@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use crate::config::plugins::{
+    add_plugin, describe_plugin, normalize_plugins, pin_plugin, read_plugins, remove_plugin,
+    PluginEntry,
+};
+use crate::config::{edit_opencode_document, load_opencode_document, resolve_opencode_config_path};
+use crate::jsonc::JsoncDocument;
+use crate::paths::opencode_cache_candidates;
+
+fn describe_all(
+    doc: &JsoncDocument,
+    scope: &str,
+    project_dir: &str,
+) -> Result<Vec<PluginEntry>, String> {
+    let config_path = resolve_opencode_config_path(scope.trim(), project_dir)?;
+    let base_dir = config_path.parent().map(PathBuf::from);
+    let cache_dirs = opencode_cache_candidates();
+    Ok(read_plugins(doc)
+        .iter()
+        .map(|spec| describe_plugin(spec, &cache_dirs, base_dir.as_deref()))
+        .collect())
+}
+
+fn edit_plugins(
+    scope: &str,
+    project_dir: &str,
+    edit: impl FnOnce(&mut JsoncDocument) -> Result<(), String>,
+) -> Result<Vec<PluginEntry>, String> {
+    let doc = edit_opencode_document(scope, project_dir, |doc| {
+        normalize_plugins(doc)?;
+        edit(doc)
+    })?;
+    describe_all(&doc, scope, project_dir)
+}
+
+#[tauri::command]
+pub fn plugin_list(scope: String, project_dir: String) -> Result<Vec<PluginEntry>, String> {
+    let doc = load_opencode_document(&scope, &project_dir)?;
+    describe_all(&doc, &scope, &project_dir)
+}
+
+#[tauri::command]
+pub fn plugin_add(
+    scope: String,
+    project_dir: String,
+    spec: String,
+) -> Result<Vec<PluginEntry>, String> {
+    edit_plugins(&scope, &project_dir, |doc| add_plugin(doc, &spec))
+}
+
+#[tauri::command]
+pub fn plugin_remove(
+    scope: String,
+    project_dir: String,
+    name: String,
+) -> Result<Vec<PluginEntry>, String> {
+    edit_plugins(&scope, &project_dir, |doc| remove_plugin(doc, &name))
+}
+
+/// Pins a plugin to a version, range or dist-tag; an empty `version` unpins it.
+#[tauri::command]
+pub fn plugin_pin(
+    scope: String,
+    project_dir: String,
+    name: String,
+    version: Option<String>,
+) -> Result<Vec<PluginEntry>, String> {
+    edit_plugins(&scope, &project_dir, |doc| {
+        pin_plugin(doc, &name, version.as_deref())
+    })
+}
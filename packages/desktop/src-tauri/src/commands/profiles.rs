@@ -0,0 +1,97 @@
+use tauri::AppHandle;
+
+use crate::engine::profiles::{
+    app_auth_path, app_profiles, clone_profile, copy_credentials, create_profile, delete_profile,
+    profiles_root, OpencodeProfile,
+};
+use crate::workspace::state::load_workspace_state;
+
+fn clean(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[tauri::command]
+pub fn opencode_profile_list(app: AppHandle) -> Result<Vec<OpencodeProfile>, String> {
+    app_profiles(&app)
+}
+
+/// Creates an empty profile, or a copy of `clone_from` without its cache.
+#[tauri::command]
+pub fn opencode_profile_create(
+    app: AppHandle,
+    name: String,
+    clone_from: Option<String>,
+) -> Result<Vec<OpencodeProfile>, String> {
+    let root = profiles_root(&app)?;
+    let name = name.trim();
+    match clean(clone_from) {
+        Some(source) => {
+            clone_profile(&root, &source, name)?;
+            println!("[workspace] OpenCode profile {name} cloned from {source}");
+        }
+        None => {
+            create_profile(&root, name)?;
+            println!("[workspace] OpenCode profile {name} created");
+        }
+    }
+    app_profiles(&app)
+}
+
+/// Copies `auth.json` entries between profiles; `None` on either side is the user's shared
+/// OpenCode login. An empty `providers` copies every provider. Returns the providers copied.
+#[tauri::command]
+pub fn opencode_profile_copy_credentials(
+    app: AppHandle,
+    from: Option<String>,
+    to: Option<String>,
+    providers: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    let from = clean(from);
+    let to = clean(to);
+    if from == to {
+        return Err("Source and target profile are the same".to_string());
+    }
+    let providers: Vec<String> = providers
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|id| clean(Some(id)))
+        .collect();
+    let copied = copy_credentials(
+        &app_auth_path(&app, from.as_deref())?,
+        &app_auth_path(&app, to.as_deref())?,
+        &providers,
+    )?;
+    println!(
+        "[workspace] Copied OpenCode credentials {} -> {}: {}",
+        from.as_deref().unwrap_or("shared"),
+        to.as_deref().unwrap_or("shared"),
+        copied.join(", ")
+    );
+    Ok(copied)
+}
+
+/// Deletes a profile with its credentials and sessions. Refused while a workspace uses it.
+#[tauri::command]
+pub fn opencode_profile_delete(
+    app: AppHandle,
+    name: String,
+) -> Result<Vec<OpencodeProfile>, String> {
+    let name = name.trim();
+    let users: Vec<String> = load_workspace_state(&app)?
+        .workspaces
+        .into_iter()
+        .filter(|w| w.opencode_profile.as_deref() == Some(name))
+        .map(|w| w.name)
+        .collect();
+    if !users.is_empty() {
+        return Err(format!(
+            "OpenCode profile {name} is used by: {}",
+            users.join(", ")
+        ));
+    }
+    delete_profile(&profiles_root(&app)?, name)?;
+    println!("[workspace] OpenCode profile {name} deleted");
+    app_profiles(&app)
+}
@@ -0,0 +1,64 @@
+use std::fs;
+
+use tauri::{AppHandle, Emitter};
+
+use crate::engine::node_runtime::{
+    app_node_runtime_status, install_node_runtime, install_request, node_runtime_root,
+    NodeRuntimeInstall, NodeRuntimeProgress, NodeRuntimeStatus, NODE_RUNTIME_EVENT,
+};
+
+#[tauri::command]
+pub fn node_runtime_status(app: AppHandle) -> Result<NodeRuntimeStatus, String> {
+    app_node_runtime_status(&app)
+}
+
+/// Installs the configured Node runtime. Emits `openwork://node-runtime-progress`; the engine
+/// picks the runtime up on its next start.
+#[tauri::command]
+pub async fn node_runtime_install(app: AppHandle) -> Result<NodeRuntimeInstall, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let emit = |progress: NodeRuntimeProgress| {
+            let _ = app.emit(NODE_RUNTIME_EVENT, progress);
+        };
+        emit(NodeRuntimeProgress::new("downloading"));
+        let result = install_request(&app).and_then(|request| {
+            install_node_runtime(&request, &mut |downloaded, total| {
+                let mut progress = NodeRuntimeProgress::new("downloading");
+                progress.downloaded_bytes = downloaded;
+                progress.total_bytes = total;
+                emit(progress);
+            })
+        });
+        match &result {
+            Ok(install) => {
+                println!(
+                    "[runtime] Installed Node v{} ({:?})",
+                    install.version, install.origin
+                );
+                let mut progress = NodeRuntimeProgress::new("done");
+                progress.origin = Some(install.origin);
+                emit(progress);
+            }
+            Err(error) => {
+                println!("[runtime] Failed to install Node: {error}");
+                let mut progress = NodeRuntimeProgress::new("failed");
+                progress.error = Some(error.clone());
+                emit(progress);
+            }
+        }
+        result
+    })
+    .await
+    .map_err(|e| format!("Node runtime install failed: {e}"))?
+}
+
+/// Deletes every managed runtime. OpenCode falls back to the system PATH on its next start.
+#[tauri::command]
+pub fn node_runtime_remove(app: AppHandle) -> Result<NodeRuntimeStatus, String> {
+    let root = node_runtime_root(&app)?;
+    if root.exists() {
+        fs::remove_dir_all(&root)
+            .map_err(|e| format!("Failed to remove {}: {e}", root.display()))?;
+    }
+    app_node_runtime_status(&app)
+}
@@ -0,0 +1,51 @@
+use crate::engine::launcher::normalize_launchers;
+use crate::settings::{load_app_settings, save_app_settings};
+use crate::types::AppSettings;
+
+#[tauri::command]
+pub fn app_settings_get(app: tauri::AppHandle) -> Result<AppSettings, String> {
+    load_app_settings(&app)
+}
+
+#[tauri::command]
+pub fn app_settings_set(
+    app: tauri::AppHandle,
+    mut settings: AppSettings,
+) -> Result<AppSettings, String> {
+    let sha = settings
+        .enterprise_skills
+        .sha256
+        .take()
+        .map(|sha| sha.trim().to_ascii_lowercase())
+        .filter(|sha| !sha.is_empty());
+    if let Some(sha) = sha.as_deref() {
+        if sha.len() != 64 || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err("enterpriseSkills.sha256 must be a 64-character hex digest".to_string());
+        }
+    }
+    settings.enterprise_skills.sha256 = sha;
+
+    let runtime = &mut settings.node_runtime;
+    let sha = runtime
+        .sha256
+        .take()
+        .map(|sha| sha.trim().to_ascii_lowercase())
+        .filter(|sha| !sha.is_empty());
+    if let Some(sha) = sha.as_deref() {
+        if sha.len() != 64 || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err("nodeRuntime.sha256 must be a 64-character hex digest".to_string());
+        }
+    }
+    runtime.sha256 = sha;
+    runtime.version = runtime.version.trim().trim_start_matches('v').to_string();
+    if semver::Version::parse(&runtime.version).is_err() {
+        return Err("nodeRuntime.version must be a version like 22.12.0".to_string());
+    }
+    runtime.mirror = runtime.mirror.trim().trim_end_matches('/').to_string();
+    if !runtime.mirror.starts_with("https://") && !runtime.mirror.starts_with("http://") {
+        return Err("nodeRuntime.mirror must start with http:// or https://".to_string());
+    }
+    normalize_launchers(&mut settings)?;
+    save_app_settings(&app, &settings)?;
+    Ok(settings)
+}
@@ -103,7 +103,8 @@ fn collect_skill_roots(project_dir: &str) -> Result<Vec<PathBuf>, String> {
     Ok(unique)
 }
 
-fn validate_skill_name(name: &str) -> Result<String, String> {
+/// Skill names are kebab-case, so a valid one is always a single plain path component.
+pub(crate) fn validate_skill_name(name: &str) -> Result<String, String> {
     let trimmed = name.trim();
     if trimmed.is_empty() {
         return Err("skill name is required".to_string());
@@ -0,0 +1,84 @@
+use tauri::{AppHandle, State};
+
+use crate::vault::{Vault, VaultSecretInfo, VaultSession, VaultStatus};
+
+#[tauri::command]
+pub fn vault_status(app: AppHandle) -> Result<VaultStatus, String> {
+    Ok(Vault::for_app(&app)?.status())
+}
+
+/// Keeps the passphrase in memory until the app quits or `vault_lock` is called.
+#[tauri::command]
+pub fn vault_unlock(
+    app: AppHandle,
+    session: State<VaultSession>,
+    passphrase: String,
+) -> Result<VaultStatus, String> {
+    Vault::for_app(&app)?.verify_passphrase(&passphrase)?;
+    session.set(Some(passphrase));
+    println!("[vault] unlocked");
+    Ok(Vault::for_app(&app)?.status())
+}
+
+#[tauri::command]
+pub fn vault_lock(app: AppHandle, session: State<VaultSession>) -> Result<VaultStatus, String> {
+    session.set(None);
+    println!("[vault] locked");
+    Ok(Vault::for_app(&app)?.status())
+}
+
+/// Switches to passphrase mode, changes the passphrase, or with `passphrase: null` goes back
+/// to a machine key. `current` is needed when the vault is locked.
+#[tauri::command]
+pub fn vault_set_passphrase(
+    app: AppHandle,
+    session: State<VaultSession>,
+    current: Option<String>,
+    passphrase: Option<String>,
+) -> Result<VaultStatus, String> {
+    let current = current.filter(|value| !value.is_empty()).or(session.get());
+    let passphrase = passphrase.filter(|value| !value.is_empty());
+    let vault = Vault::for_app(&app)?.with_passphrase(current);
+    vault.change_passphrase(passphrase.as_deref())?;
+    let mode = if passphrase.is_some() {
+        "passphrase"
+    } else {
+        "machine key"
+    };
+    session.set(passphrase);
+    println!("[vault] now sealed with a {mode}");
+    Ok(Vault::for_app(&app)?.status())
+}
+
+#[tauri::command]
+pub fn vault_secret_list(app: AppHandle) -> Result<Vec<VaultSecretInfo>, String> {
+    Vault::for_app(&app)?.list_secrets()
+}
+
+/// Metadata and a redacted preview; the value itself never leaves the backend here.
+#[tauri::command]
+pub fn vault_secret_get(app: AppHandle, name: String) -> Result<VaultSecretInfo, String> {
+    Vault::for_app(&app)?.secret_info(name.trim())
+}
+
+#[tauri::command]
+pub fn vault_secret_set(
+    app: AppHandle,
+    name: String,
+    value: String,
+    description: Option<String>,
+) -> Result<VaultSecretInfo, String> {
+    let name = name.trim();
+    let info = Vault::for_app(&app)?.set_secret(name, &value, description.as_deref())?;
+    println!("[vault] secret set: {name}");
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn vault_secret_delete(app: AppHandle, name: String) -> Result<Vec<VaultSecretInfo>, String> {
+    let name = name.trim();
+    let vault = Vault::for_app(&app)?;
+    vault.delete_secret(name)?;
+    println!("[vault] secret deleted: {name}");
+    vault.list_secrets()
+}
@@ -1,22 +1,70 @@
+use std::collections::BTreeMap;
 use std::fs;
-use std::io::{Read, Write};
+use std::io::{Cursor, Read, Write};
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::commands::engine::{engine_start, engine_stop};
+use crate::crypto::{decrypt_with_passphrase, encrypt_with_passphrase, is_encrypted};
+use crate::engine::manager::EngineManager;
+use crate::engine::profiles::{create_profile, profiles_root, read_profile};
+use crate::limits::validate_limits;
+use crate::settings::load_app_settings;
 use crate::types::{
-    ExecResult, RemoteType, WorkspaceInfo, WorkspaceList, WorkspaceOpenworkConfig, WorkspaceType,
+    ExecResult, RemoteType, ResourceLimits, WorkspaceInfo, WorkspaceList, WorkspaceOpenworkConfig,
+    WorkspaceType,
 };
+use crate::vault::{validate_secret_name, Vault};
+use crate::workspace::archive::{
+    apply_import, archive_digest, collect_export_entries, compatibility_warnings, plan_import,
+    read_workspace_archive, ArchiveFileRecord, ImportFilePlan, ImportResolution, WorkspaceArchive,
+    ARCHIVE_VERSION, MANIFEST_NAME,
+};
+use crate::workspace::connections::{
+    import_connection_profiles, profile_from_workspace, remote_token_secret_name, ConnectionBundle,
+    ConnectionImportSummary,
+};
+use crate::workspace::enterprise::schedule_enterprise_seed;
 use crate::workspace::files::ensure_workspace_files;
+use crate::workspace::presets::{
+    builtin_presets, load_workspace_presets, resolve_workspace_preset, user_presets_dir,
+    WorkspacePreset,
+};
+use crate::workspace::relocate::{
+    move_workspace_dir, remove_moved_source, rewrite_openwork_roots, rollback_move,
+    validate_move_target, WorkspaceMoveProgress, MOVE_PROGRESS_EVENT,
+};
+use crate::workspace::secrets::{SecretRedaction, SecretRedactor};
+use crate::workspace::seed::{
+    apply_seed_upgrade, plan_seed_upgrade, sha256_hex, WorkspaceUpgradePlan, WorkspaceUpgradeResult,
+};
 use crate::workspace::state::{
     ensure_starter_workspace, load_workspace_state, save_workspace_state, stable_workspace_id,
-    stable_workspace_id_for_openwork, stable_workspace_id_for_remote,
+    stable_workspace_id_for_openwork, stable_workspace_id_for_remote, starter_workspace_info,
+};
+use crate::workspace::templates::{
+    save_workspace_template, template_id_from_name, WorkspaceTemplateSummary,
 };
 use crate::workspace::watch::{update_workspace_watch, WorkspaceWatchState};
-use serde::Serialize;
-use tauri::State;
-use walkdir::WalkDir;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
 use zip::write::FileOptions;
-use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use zip::{CompressionMethod, ZipWriter};
+
+/// Seeds preset files synchronously and leaves the network-bound enterprise skills to a
+/// background job.
+fn prepare_workspace_files(
+    app: &tauri::AppHandle,
+    workspace_path: &str,
+    preset_id: &str,
+) -> Result<(), String> {
+    let preset = resolve_workspace_preset(app, preset_id);
+    ensure_workspace_files(workspace_path, &preset)?;
+    if preset.enterprise_creator_skills {
+        schedule_enterprise_seed(app, workspace_path);
+    }
+    Ok(())
+}
 
 #[tauri::command]
 pub fn workspace_bootstrap(
@@ -26,8 +74,13 @@ pub fn workspace_bootstrap(
     println!("[workspace] bootstrap");
     let mut state = load_workspace_state(&app)?;
 
-    let starter = ensure_starter_workspace(&app)?;
-    ensure_workspace_files(&starter.path, &starter.preset)?;
+    // A moved starter keeps its id, so don't recreate the original app-data folder for it.
+    let starter = starter_workspace_info(&app)?;
+    let starter = match state.workspaces.iter().find(|w| w.id == starter.id) {
+        Some(existing) => existing.clone(),
+        None => ensure_starter_workspace(&app)?,
+    };
+    prepare_workspace_files(&app, &starter.path, &starter.preset)?;
 
     if !state.workspaces.iter().any(|w| w.id == starter.id) {
         state.workspaces.push(starter.clone());
@@ -51,6 +104,30 @@ pub fn workspace_bootstrap(
     })
 }
 
+/// Drops vault entries owned by a forgotten workspace. Shared secrets it only referenced
+/// stay; a locked vault is logged and left alone.
+fn forget_workspace_secrets(app: &tauri::AppHandle, workspace: &WorkspaceInfo) {
+    let owned_token = remote_token_secret_name(&workspace.id);
+    let owns_token = workspace.token_secret.as_deref() == Some(owned_token.as_str());
+    let result = Vault::for_app(app).and_then(|vault| {
+        let data = vault.load()?;
+        let has_token = owns_token && data.secrets.contains_key(&owned_token);
+        if !(has_token || data.workspace_env.contains_key(&workspace.id)) {
+            return Ok(());
+        }
+        vault.update(|data| {
+            data.workspace_env.remove(&workspace.id);
+            if owns_token {
+                data.secrets.remove(&owned_token);
+            }
+            Ok(())
+        })
+    });
+    if let Err(error) = result {
+        println!("[workspace] forget: vault entries kept: {error}");
+    }
+}
+
 #[tauri::command]
 pub fn workspace_forget(
     app: tauri::AppHandle,
@@ -65,11 +142,10 @@ pub fn workspace_forget(
         return Err("workspaceId is required".to_string());
     }
 
-    let before = state.workspaces.len();
-    state.workspaces.retain(|w| w.id != id);
-    if before == state.workspaces.len() {
+    let Some(forgotten) = state.workspaces.iter().find(|w| w.id == id).cloned() else {
         return Err("Unknown workspaceId".to_string());
-    }
+    };
+    state.workspaces.retain(|w| w.id != id);
 
     if state.active_id == id {
         state.active_id = state
@@ -81,12 +157,13 @@ pub fn workspace_forget(
 
     if state.workspaces.is_empty() {
         let starter = ensure_starter_workspace(&app)?;
-        ensure_workspace_files(&starter.path, &starter.preset)?;
+        prepare_workspace_files(&app, &starter.path, &starter.preset)?;
         state.active_id = starter.id.clone();
         state.workspaces.push(starter);
     }
 
     save_workspace_state(&app, &state)?;
+    forget_workspace_secrets(&app, &forgotten);
     let active_workspace = state.workspaces.iter().find(|w| w.id == state.active_id);
     update_workspace_watch(&app, watch_state, active_workspace)?;
     println!("[workspace] forget complete");
@@ -97,6 +174,106 @@ pub fn workspace_forget(
     })
 }
 
+#[tauri::command]
+pub fn workspace_presets_list(app: tauri::AppHandle) -> Vec<WorkspacePreset> {
+    load_workspace_presets(&app)
+}
+
+#[tauri::command]
+pub fn workspace_save_as_template(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    name: String,
+    description: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<WorkspaceTemplateSummary, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("name is required".to_string());
+    }
+    let id = template_id_from_name(&name);
+    if id.is_empty() {
+        return Err("Template name must contain letters or numbers".to_string());
+    }
+    if builtin_presets().iter().any(|preset| preset.id == id) {
+        return Err(format!("{name} is reserved by a built-in preset"));
+    }
+
+    let state = load_workspace_state(&app)?;
+    let workspace = state
+        .workspaces
+        .iter()
+        .find(|w| w.id == workspace_id.trim())
+        .ok_or_else(|| "Unknown workspaceId".to_string())?;
+    if workspace.workspace_type != WorkspaceType::Local {
+        return Err("Templates can only be saved from local workspaces".to_string());
+    }
+
+    let description = description
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    let summary = save_workspace_template(
+        Path::new(&workspace.path),
+        &user_presets_dir(&app)?,
+        &id,
+        &name,
+        description,
+        overwrite.unwrap_or(false),
+    )?;
+    println!(
+        "[workspace] saved template {id} from {} (excluded {} secret files)",
+        workspace.path,
+        summary.excluded.len()
+    );
+    Ok(summary)
+}
+
+fn local_workspace_preset(
+    app: &tauri::AppHandle,
+    workspace_id: &str,
+) -> Result<(PathBuf, WorkspacePreset), String> {
+    let state = load_workspace_state(app)?;
+    let workspace = state
+        .workspaces
+        .iter()
+        .find(|w| w.id == workspace_id.trim())
+        .ok_or_else(|| "Unknown workspaceId".to_string())?;
+    if workspace.workspace_type != WorkspaceType::Local {
+        return Err("Preset upgrades are only supported for local workspaces".to_string());
+    }
+    let root = PathBuf::from(&workspace.path);
+    if !root.is_dir() {
+        return Err(format!("Workspace path not found: {}", root.display()));
+    }
+    Ok((root, resolve_workspace_preset(app, &workspace.preset)))
+}
+
+#[tauri::command]
+pub fn workspace_preset_upgrade_plan(
+    app: tauri::AppHandle,
+    workspace_id: String,
+) -> Result<WorkspaceUpgradePlan, String> {
+    let (root, preset) = local_workspace_preset(&app, &workspace_id)?;
+    plan_seed_upgrade(workspace_id.trim(), &root, &preset)
+}
+
+#[tauri::command]
+pub fn workspace_preset_upgrade_apply(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    accepted: Vec<String>,
+) -> Result<WorkspaceUpgradeResult, String> {
+    let (root, preset) = local_workspace_preset(&app, &workspace_id)?;
+    let result = apply_seed_upgrade(workspace_id.trim(), &root, &preset, &accepted)?;
+    println!(
+        "[workspace] preset upgrade {}: updated {}, skipped {}",
+        root.display(),
+        result.updated.len(),
+        result.skipped.len()
+    );
+    Ok(result)
+}
+
 #[tauri::command]
 pub fn workspace_set_active(
     app: tauri::AppHandle,
@@ -157,7 +334,7 @@ pub fn workspace_create(
 
     let id = stable_workspace_id(&folder);
 
-    ensure_workspace_files(&folder, &preset)?;
+    prepare_workspace_files(&app, &folder, &preset)?;
 
     let mut state = load_workspace_state(&app)?;
 
@@ -175,6 +352,11 @@ pub fn workspace_create(
         openwork_host_url: None,
         openwork_workspace_id: None,
         openwork_workspace_name: None,
+        token_secret: None,
+        opencode_profile: None,
+        engine_launcher: None,
+        engine_sandbox: false,
+        resource_limits: None,
     });
 
     state.active_id = id.clone();
@@ -268,6 +450,11 @@ pub fn workspace_create_remote(
         openwork_host_url,
         openwork_workspace_id,
         openwork_workspace_name,
+        token_secret: None,
+        opencode_profile: None,
+        engine_launcher: None,
+        engine_sandbox: false,
+        resource_limits: None,
     });
     state.active_id = id.clone();
     save_workspace_state(&app, &state)?;
@@ -375,6 +562,372 @@ pub fn workspace_update_remote(
     })
 }
 
+/// Stops the engine (and its sidecars) when it is serving a folder inside `root`.
+/// Returns the sidecar preference so the caller can restart it the same way.
+fn stop_engine_serving(app: &AppHandle, root: &Path) -> Option<bool> {
+    let manager = app.state::<EngineManager>();
+    let prefer_sidecar = {
+        let mut state = manager.inner.lock().expect("engine mutex poisoned");
+        let info = EngineManager::snapshot_locked(&mut state);
+        let serving = info
+            .project_dir
+            .as_deref()
+            .is_some_and(|dir| Path::new(dir).starts_with(root));
+        if !info.running || !serving {
+            return None;
+        }
+        state.prefer_sidecar
+    };
+
+    engine_stop(manager, app.state(), app.state());
+    Some(prefer_sidecar)
+}
+
+fn restart_engine(app: &AppHandle, project_dir: &Path, prefer_sidecar: bool) {
+    if let Err(error) = engine_start(
+        app.clone(),
+        app.state(),
+        app.state(),
+        app.state(),
+        project_dir.to_string_lossy().to_string(),
+        Some(prefer_sidecar),
+    ) {
+        println!("[workspace] move: failed to restart engine: {error}");
+    }
+}
+
+fn move_workspace(
+    app: &AppHandle,
+    workspace_id: &str,
+    target_dir: &str,
+) -> Result<WorkspaceList, String> {
+    let id = workspace_id.trim();
+    if id.is_empty() {
+        return Err("workspaceId is required".to_string());
+    }
+    let target_dir = target_dir.trim();
+    if target_dir.is_empty() {
+        return Err("targetDir is required".to_string());
+    }
+
+    let mut state = load_workspace_state(app)?;
+    let workspace = state
+        .workspaces
+        .iter()
+        .find(|w| w.id == id)
+        .cloned()
+        .ok_or_else(|| "Unknown workspaceId".to_string())?;
+    if workspace.workspace_type != WorkspaceType::Local {
+        return Err("Only local workspaces can be moved".to_string());
+    }
+
+    let source = PathBuf::from(&workspace.path);
+    let target = PathBuf::from(target_dir);
+    validate_move_target(&source, &target)?;
+
+    let is_active = state.active_id == id;
+    let engine_restart = stop_engine_serving(app, &source);
+    if is_active {
+        update_workspace_watch(app, app.state::<WorkspaceWatchState>(), None)?;
+    }
+
+    let emit = |progress: &WorkspaceMoveProgress| {
+        let _ = app.emit(MOVE_PROGRESS_EVENT, progress);
+    };
+    let restore = |root: &Path| {
+        if is_active {
+            let _ =
+                update_workspace_watch(app, app.state::<WorkspaceWatchState>(), Some(&workspace));
+        }
+        if let Some(prefer_sidecar) = engine_restart {
+            restart_engine(app, root, prefer_sidecar);
+        }
+    };
+
+    let strategy = match move_workspace_dir(id, &source, &target, emit) {
+        Ok(strategy) => strategy,
+        Err(error) => {
+            restore(&source);
+            return Err(error);
+        }
+    };
+
+    let mut previous_openwork = None;
+    let commit = rewrite_openwork_roots(&target, &source).and_then(|previous| {
+        previous_openwork = previous;
+        if let Some(entry) = state.workspaces.iter_mut().find(|w| w.id == id) {
+            entry.path = target.to_string_lossy().to_string();
+        }
+        save_workspace_state(app, &state)
+    });
+    if let Err(error) = commit {
+        let rollback = rollback_move(strategy, &source, &target, previous_openwork.as_deref());
+        if let Err(rollback_error) = rollback {
+            println!("[workspace] move rollback failed: {rollback_error}");
+        }
+        restore(&source);
+        return Err(error);
+    }
+
+    if let Err(error) = remove_moved_source(strategy, &source) {
+        println!("[workspace] move: {error}");
+    }
+
+    let moved = state.workspaces.iter().find(|w| w.id == id);
+    if is_active {
+        update_workspace_watch(app, app.state::<WorkspaceWatchState>(), moved)?;
+    }
+    if let Some(prefer_sidecar) = engine_restart {
+        restart_engine(app, &target, prefer_sidecar);
+    }
+    emit(&WorkspaceMoveProgress::new(id, "done"));
+    println!("[workspace] move complete: {id}");
+
+    Ok(WorkspaceList {
+        active_id: state.active_id,
+        workspaces: state.workspaces,
+    })
+}
+
+fn find_remote_workspace<'a>(
+    workspaces: &'a mut [WorkspaceInfo],
+    workspace_id: &str,
+) -> Result<&'a mut WorkspaceInfo, String> {
+    let id = workspace_id.trim();
+    let entry = workspaces
+        .iter_mut()
+        .find(|w| w.id == id)
+        .ok_or_else(|| "Unknown workspaceId".to_string())?;
+    if entry.workspace_type != WorkspaceType::Remote {
+        return Err("workspaceId is not remote".to_string());
+    }
+    Ok(entry)
+}
+
+/// Stores a remote workspace's token in the vault, or points it at an existing secret with
+/// `secretName`. Passing neither clears the token.
+#[tauri::command]
+pub fn workspace_remote_token_set(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    token: Option<String>,
+    secret_name: Option<String>,
+) -> Result<WorkspaceList, String> {
+    let mut state = load_workspace_state(&app)?;
+    let entry = find_remote_workspace(&mut state.workspaces, &workspace_id)?;
+    let vault = Vault::for_app(&app)?;
+    let owned_token = remote_token_secret_name(&entry.id);
+    let token = token.filter(|value| !value.trim().is_empty());
+    let secret_name = secret_name
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    match (token, secret_name) {
+        (Some(token), name) => {
+            let name = name.unwrap_or(owned_token);
+            vault.set_secret(&name, token.trim(), Some("OpenWork remote token"))?;
+            entry.token_secret = Some(name);
+        }
+        (None, Some(name)) => {
+            validate_secret_name(&name)?;
+            vault.secret_info(&name)?;
+            entry.token_secret = Some(name);
+        }
+        (None, None) => {
+            if entry.token_secret.as_deref() == Some(owned_token.as_str()) {
+                let _ = vault.delete_secret(&owned_token);
+            }
+            entry.token_secret = None;
+        }
+    }
+    println!("[workspace] remote token updated: {}", entry.id);
+
+    save_workspace_state(&app, &state)?;
+    Ok(WorkspaceList {
+        active_id: state.active_id,
+        workspaces: state.workspaces,
+    })
+}
+
+/// The token of a remote workspace, read from the vault so the UI can authenticate.
+#[tauri::command]
+pub fn workspace_remote_token_get(
+    app: tauri::AppHandle,
+    workspace_id: String,
+) -> Result<Option<String>, String> {
+    let mut state = load_workspace_state(&app)?;
+    let entry = find_remote_workspace(&mut state.workspaces, &workspace_id)?;
+    let Some(name) = &entry.token_secret else {
+        return Ok(None);
+    };
+    Vault::for_app(&app)?.secret_value(name)
+}
+
+/// Runs a local workspace's engine with its own OpenCode profile, creating the profile if it
+/// doesn't exist yet; `None` goes back to the shared setup. Applies on the next engine start.
+#[tauri::command]
+pub fn workspace_profile_set(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    profile: Option<String>,
+) -> Result<WorkspaceList, String> {
+    let mut state = load_workspace_state(&app)?;
+    let id = workspace_id.trim();
+    let entry = state
+        .workspaces
+        .iter_mut()
+        .find(|w| w.id == id)
+        .ok_or_else(|| "Unknown workspaceId".to_string())?;
+    if entry.workspace_type != WorkspaceType::Local {
+        return Err("OpenCode profiles only apply to local workspaces".to_string());
+    }
+
+    let profile = profile
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    if let Some(name) = &profile {
+        let root = profiles_root(&app)?;
+        if read_profile(&root, name).is_err() {
+            create_profile(&root, name)?;
+        }
+    }
+    println!(
+        "[workspace] OpenCode profile for {}: {}",
+        entry.id,
+        profile.as_deref().unwrap_or("shared")
+    );
+    entry.opencode_profile = profile;
+
+    save_workspace_state(&app, &state)?;
+    Ok(WorkspaceList {
+        active_id: state.active_id,
+        workspaces: state.workspaces,
+    })
+}
+
+/// Starts a local workspace's engine through one of the launchers in app settings; `None`
+/// runs OpenCode directly. Applies on the next engine start.
+#[tauri::command]
+pub fn workspace_launcher_set(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    launcher: Option<String>,
+) -> Result<WorkspaceList, String> {
+    let mut state = load_workspace_state(&app)?;
+    let id = workspace_id.trim();
+    let entry = state
+        .workspaces
+        .iter_mut()
+        .find(|w| w.id == id)
+        .ok_or_else(|| "Unknown workspaceId".to_string())?;
+    if entry.workspace_type != WorkspaceType::Local {
+        return Err("Engine launchers only apply to local workspaces".to_string());
+    }
+
+    let launcher = launcher
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    if let Some(launcher_id) = &launcher {
+        let settings = load_app_settings(&app)?;
+        if !settings
+            .engine_launchers
+            .iter()
+            .any(|l| &l.id == launcher_id)
+        {
+            return Err(format!("Unknown engine launcher: {launcher_id}"));
+        }
+    }
+    println!(
+        "[workspace] engine launcher for {}: {}",
+        entry.id,
+        launcher.as_deref().unwrap_or("direct")
+    );
+    entry.engine_launcher = launcher;
+
+    save_workspace_state(&app, &state)?;
+    Ok(WorkspaceList {
+        active_id: state.active_id,
+        workspaces: state.workspaces,
+    })
+}
+
+/// Turns the Linux engine sandbox on or off for a local workspace. With it on, OpenCode can
+/// only write to the authorized roots and its own data dirs. Applies on the next engine start.
+#[tauri::command]
+pub fn workspace_sandbox_set(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    enabled: bool,
+) -> Result<WorkspaceList, String> {
+    if enabled && !cfg!(target_os = "linux") {
+        return Err("The engine sandbox is only available on Linux".to_string());
+    }
+    let mut state = load_workspace_state(&app)?;
+    let id = workspace_id.trim();
+    let entry = state
+        .workspaces
+        .iter_mut()
+        .find(|w| w.id == id)
+        .ok_or_else(|| "Unknown workspaceId".to_string())?;
+    if entry.workspace_type != WorkspaceType::Local {
+        return Err("The engine sandbox only applies to local workspaces".to_string());
+    }
+    entry.engine_sandbox = enabled;
+    println!("[workspace] engine sandbox for {}: {enabled}", entry.id);
+
+    save_workspace_state(&app, &state)?;
+    Ok(WorkspaceList {
+        active_id: state.active_id,
+        workspaces: state.workspaces,
+    })
+}
+
+/// Sets memory, CPU priority, open-file and process caps for a local workspace's engine and
+/// sidecars; `None` removes them. Applies the next time they start.
+#[tauri::command]
+pub fn workspace_limits_set(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    limits: Option<ResourceLimits>,
+) -> Result<WorkspaceList, String> {
+    let limits = limits.filter(|limits| !limits.is_empty());
+    if let Some(limits) = limits.as_ref() {
+        validate_limits(limits)?;
+    }
+    let mut state = load_workspace_state(&app)?;
+    let id = workspace_id.trim();
+    let entry = state
+        .workspaces
+        .iter_mut()
+        .find(|w| w.id == id)
+        .ok_or_else(|| "Unknown workspaceId".to_string())?;
+    if entry.workspace_type != WorkspaceType::Local {
+        return Err("Resource limits only apply to local workspaces".to_string());
+    }
+    println!("[workspace] resource limits for {}: {limits:?}", entry.id);
+    entry.resource_limits = limits;
+
+    save_workspace_state(&app, &state)?;
+    Ok(WorkspaceList {
+        active_id: state.active_id,
+        workspaces: state.workspaces,
+    })
+}
+
+/// Moves a local workspace folder, e.g. out of the app-data starter folder into Documents.
+/// Emits `openwork://workspace-move-progress` while copying across filesystems.
+#[tauri::command]
+pub async fn workspace_move(
+    app: AppHandle,
+    workspace_id: String,
+    target_dir: String,
+) -> Result<WorkspaceList, String> {
+    println!("[workspace] move request: {workspace_id}");
+    tauri::async_runtime::spawn_blocking(move || move_workspace(&app, &workspace_id, &target_dir))
+        .await
+        .map_err(|e| format!("Workspace move failed: {e}"))?
+}
+
 #[tauri::command]
 pub fn workspace_add_authorized_root(
     _app: tauri::AppHandle,
@@ -497,86 +1050,77 @@ pub struct WorkspaceExportSummary {
     pub output_path: String,
     pub included: usize,
     pub excluded: Vec<String>,
+    pub encrypted: bool,
+    /// Values replaced by placeholders in the exported copy.
+    pub redactions: Vec<SecretRedaction>,
+    /// Files left out because their secrets couldn't be redacted.
+    pub warnings: Vec<String>,
 }
 
-fn now_ms() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0)
-}
-
-fn normalize_zip_path(path: &Path) -> String {
-    path.to_string_lossy().replace('\\', "/")
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceExportOptions {
+    /// Encrypts the archive with a key derived from this passphrase.
+    #[serde(default)]
+    pub passphrase: Option<String>,
+    /// Skips secret redaction. Only allowed together with a passphrase.
+    #[serde(default)]
+    pub include_secrets: bool,
+    /// Extra workspace files to export, as globs relative to the workspace root.
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Globs for files to leave out, including ones exported by default.
+    #[serde(default)]
+    pub exclude: Vec<String>,
 }
 
-fn is_secret_name(name: &str) -> bool {
-    let lower = name.to_lowercase();
-    if lower == ".env" || lower.starts_with(".env.") {
-        return true;
-    }
-    if lower == "credentials.json"
-        || lower == "credentials.yml"
-        || lower == "credentials.yaml"
-    {
-        return true;
-    }
-    if lower.ends_with(".key")
-        || lower.ends_with(".pem")
-        || lower.ends_with(".p12")
-        || lower.ends_with(".pfx")
-    {
-        return true;
-    }
-    false
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceImportOptions {
+    /// Required when the archive was exported with a passphrase.
+    #[serde(default)]
+    pub passphrase: Option<String>,
+    /// Values for the secret placeholders listed in the archive manifest.
+    #[serde(default)]
+    pub secrets: BTreeMap<String, String>,
+    /// Allows importing into a folder that already has files.
+    #[serde(default)]
+    pub merge: bool,
+    /// Per-file choice for conflicting archive paths.
+    #[serde(default)]
+    pub resolutions: BTreeMap<String, ImportResolution>,
 }
 
-fn should_exclude(path: &Path) -> bool {
-    let name = path
-        .file_name()
-        .and_then(|entry| entry.to_str())
-        .unwrap_or("");
-    is_secret_name(name)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceImportPreview {
+    pub encrypted: bool,
+    pub manifest: Option<serde_json::Value>,
+    pub target_exists: bool,
+    pub files: Vec<ImportFilePlan>,
+    pub skipped_secrets: Vec<String>,
+    pub redactions: Vec<SecretRedaction>,
+    /// Version or preset mismatches that don't block the import.
+    pub warnings: Vec<String>,
 }
 
-fn collect_workspace_entries(
-    workspace_root: &Path,
-) -> Result<(Vec<(PathBuf, String)>, Vec<String>), String> {
-    let mut entries: Vec<(PathBuf, String)> = Vec::new();
-    let mut excluded: Vec<String> = Vec::new();
-
-    let config_path = workspace_root.join("opencode.json");
-    if config_path.exists() && config_path.is_file() {
-        if should_exclude(&config_path) {
-            excluded.push("opencode.json".to_string());
-        } else {
-            entries.push((config_path, "opencode.json".to_string()));
-        }
-    }
-
-    let opencode_dir = workspace_root.join(".opencode");
-    if opencode_dir.exists() {
-        for entry in WalkDir::new(&opencode_dir) {
-            let entry = entry.map_err(|e| e.to_string())?;
-            if !entry.file_type().is_file() {
-                continue;
-            }
-            let absolute = entry.path().to_path_buf();
-            let rel = absolute
-                .strip_prefix(workspace_root)
-                .map_err(|e| format!("Failed to compute relative path: {e}"))?;
-            let rel_str = normalize_zip_path(rel);
-            if should_exclude(&absolute) {
-                if !excluded.contains(&rel_str) {
-                    excluded.push(rel_str);
-                }
-                continue;
-            }
-            entries.push((absolute, rel_str));
-        }
+fn read_archive_bytes(archive_path: &str, passphrase: Option<&str>) -> Result<Vec<u8>, String> {
+    let bytes =
+        fs::read(archive_path).map_err(|e| format!("Failed to open {}: {e}", archive_path))?;
+    if !is_encrypted(&bytes) {
+        return Ok(bytes);
     }
+    let passphrase = passphrase
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| "Archive is encrypted; a passphrase is required".to_string())?;
+    decrypt_with_passphrase(&bytes, passphrase)
+}
 
-    Ok((entries, excluded))
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
 }
 
 #[tauri::command]
@@ -584,7 +1128,14 @@ pub fn workspace_export_config(
     app: tauri::AppHandle,
     workspace_id: String,
     output_path: String,
+    options: Option<WorkspaceExportOptions>,
 ) -> Result<WorkspaceExportSummary, String> {
+    let export_options = options.unwrap_or_default();
+    let include_secrets = export_options.include_secrets;
+    let passphrase = export_options.passphrase.filter(|value| !value.is_empty());
+    if include_secrets && passphrase.is_none() {
+        return Err("Exporting secrets requires a passphrase".to_string());
+    }
     let workspace_id = workspace_id.trim().to_string();
     if workspace_id.is_empty() {
         return Err("workspaceId is required".to_string());
@@ -616,57 +1167,335 @@ pub fn workspace_export_config(
             .map_err(|e| format!("Failed to create export folder {}: {e}", parent.display()))?;
     }
 
-    let (entries, excluded_paths) = collect_workspace_entries(&workspace_root)?;
+    let collected = collect_export_entries(
+        &workspace_root,
+        &export_options.include,
+        &export_options.exclude,
+    )?;
+    let entries = collected.files;
+    let mut excluded_paths = collected.excluded;
     if entries.is_empty() {
         return Err("No workspace config files found to export".to_string());
     }
 
-    let file = fs::File::create(&output_path)
-        .map_err(|e| format!("Failed to create {}: {e}", output_path.display()))?;
-    let mut zip = ZipWriter::new(file);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
     let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
     let mut included_paths: Vec<String> = Vec::new();
+    let mut files: BTreeMap<String, ArchiveFileRecord> = BTreeMap::new();
+    let mut redactor = SecretRedactor::new();
+    let mut warnings = Vec::new();
 
-    for (src, rel) in entries {
+    for (rel, src) in entries {
         let mut input = fs::File::open(&src)
             .map_err(|e| format!("Failed to read {}: {e}", src.display()))?;
-        zip.start_file(rel.clone(), options)
-            .map_err(|e| format!("Failed to add {}: {e}", rel))?;
         let mut buffer = Vec::new();
         input
             .read_to_end(&mut buffer)
             .map_err(|e| format!("Failed to read {}: {e}", src.display()))?;
+        if !include_secrets {
+            if let Ok(text) = std::str::from_utf8(&buffer) {
+                match redactor.redact_file(&rel, text) {
+                    Ok(Some(redacted)) => buffer = redacted.into_bytes(),
+                    Ok(None) => {}
+                    // Leave the file out rather than export its secrets or fail the export.
+                    Err(error) => {
+                        println!("[workspace] export: {error}; skipping {rel}");
+                        warnings.push(format!("{error}; {rel} was left out"));
+                        excluded_paths.push(rel);
+                        continue;
+                    }
+                }
+            }
+        }
+        zip.start_file(rel.clone(), options)
+            .map_err(|e| format!("Failed to add {}: {e}", rel))?;
         zip.write_all(&buffer)
             .map_err(|e| format!("Failed to write {}: {e}", src.display()))?;
+        files.insert(
+            rel.clone(),
+            ArchiveFileRecord {
+                sha256: sha256_hex(&buffer),
+                size: buffer.len() as u64,
+            },
+        );
         included_paths.push(rel);
     }
 
     let included_count = included_paths.len();
     let excluded_summary = excluded_paths.clone();
     let manifest = serde_json::json!({
-        "version": 1,
+        "version": ARCHIVE_VERSION,
+        "appVersion": app.package_info().version.to_string(),
         "createdAtMs": now_ms(),
         "workspace": {
             "id": workspace.id.clone(),
             "name": workspace.name.clone(),
-            "path": workspace.path.clone()
+            "path": workspace.path.clone(),
+            "preset": workspace.preset.clone()
         },
         "included": included_paths,
         "excluded": excluded_paths,
+        "redactions": redactor.redactions,
+        "digest": archive_digest(&files),
+        "files": files,
     });
     let manifest_json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
-    zip.start_file("manifest.json", options)
+    zip.start_file(MANIFEST_NAME, options)
         .map_err(|e| format!("Failed to add manifest: {e}"))?;
     zip.write_all(manifest_json.as_bytes())
         .map_err(|e| format!("Failed to write manifest: {e}"))?;
 
-    zip.finish()
-        .map_err(|e| format!("Failed to finalize export: {e}"))?;
+    let archive = zip
+        .finish()
+        .map_err(|e| format!("Failed to finalize export: {e}"))?
+        .into_inner();
+    let encrypted = passphrase.is_some();
+    let archive = match passphrase.as_deref() {
+        Some(passphrase) => encrypt_with_passphrase(&archive, passphrase)?,
+        None => archive,
+    };
+    fs::write(&output_path, archive)
+        .map_err(|e| format!("Failed to write {}: {e}", output_path.display()))?;
 
     Ok(WorkspaceExportSummary {
         output_path: output_path.to_string_lossy().to_string(),
         included: included_count,
         excluded: excluded_summary,
+        encrypted,
+        redactions: redactor.redactions,
+        warnings,
+    })
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionExportOptions {
+    /// Tokens to ship with the profiles, keyed by workspace id. Left out unless supplied.
+    #[serde(default)]
+    pub tokens: BTreeMap<String, String>,
+    /// Also ship tokens stored in the vault for workspaces not listed in `tokens`.
+    #[serde(default)]
+    pub include_vault_tokens: bool,
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionExportSummary {
+    pub output_path: String,
+    pub exported: usize,
+    pub with_tokens: usize,
+    pub encrypted: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionImportResult {
+    #[serde(flatten)]
+    pub list: WorkspaceList,
+    #[serde(flatten)]
+    pub summary: ConnectionImportSummary,
+}
+
+/// Writes remote workspace connection details to a bundle teammates can import. With no ids,
+/// every remote workspace is exported.
+#[tauri::command]
+pub fn workspace_export_connections(
+    app: tauri::AppHandle,
+    workspace_ids: Vec<String>,
+    output_path: String,
+    options: Option<ConnectionExportOptions>,
+) -> Result<ConnectionExportSummary, String> {
+    let options = options.unwrap_or_default();
+    let passphrase = options.passphrase.filter(|value| !value.is_empty());
+    let output_path = output_path.trim().to_string();
+    if output_path.is_empty() {
+        return Err("outputPath is required".to_string());
+    }
+    let mut tokens: BTreeMap<String, String> = options
+        .tokens
+        .into_iter()
+        .filter(|(_, token)| !token.trim().is_empty())
+        .collect();
+
+    let state = load_workspace_state(&app)?;
+    if options.include_vault_tokens {
+        let vault = Vault::for_app(&app)?;
+        for workspace in &state.workspaces {
+            let Some(secret) = &workspace.token_secret else {
+                continue;
+            };
+            if tokens.contains_key(&workspace.id) {
+                continue;
+            }
+            if let Some(token) = vault.secret_value(secret)? {
+                tokens.insert(workspace.id.clone(), token);
+            }
+        }
+    }
+    if !tokens.is_empty() && passphrase.is_none() {
+        return Err("Exporting tokens requires a passphrase".to_string());
+    }
+
+    let mut profiles = Vec::new();
+    for workspace in &state.workspaces {
+        if !workspace_ids.is_empty() && !workspace_ids.contains(&workspace.id) {
+            continue;
+        }
+        if workspace.workspace_type != WorkspaceType::Remote {
+            if workspace_ids.contains(&workspace.id) {
+                return Err(format!("Workspace {} is not remote", workspace.id));
+            }
+            continue;
+        }
+        if let Some(profile) = profile_from_workspace(workspace, tokens.get(&workspace.id).cloned())
+        {
+            profiles.push(profile);
+        }
+    }
+    if profiles.is_empty() {
+        return Err("No remote workspaces to export".to_string());
+    }
+
+    let exported = profiles.len();
+    let with_tokens = profiles
+        .iter()
+        .filter(|profile| profile.token.is_some())
+        .count();
+    let bundle = ConnectionBundle::new(profiles, now_ms());
+    let raw = serde_json::to_vec_pretty(&bundle).map_err(|e| e.to_string())?;
+    let encrypted = passphrase.is_some();
+    let raw = match passphrase.as_deref() {
+        Some(passphrase) => encrypt_with_passphrase(&raw, passphrase)?,
+        None => raw,
+    };
+
+    let output_path = PathBuf::from(&output_path);
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export folder {}: {e}", parent.display()))?;
+    }
+    fs::write(&output_path, raw)
+        .map_err(|e| format!("Failed to write {}: {e}", output_path.display()))?;
+    println!("[workspace] exported {exported} connection profiles");
+
+    Ok(ConnectionExportSummary {
+        output_path: output_path.to_string_lossy().to_string(),
+        exported,
+        with_tokens,
+        encrypted,
+    })
+}
+
+/// Adds the remote workspaces from a connection bundle. Workspaces that already exist are
+/// skipped; the active workspace is left alone.
+#[tauri::command]
+pub fn workspace_import_connections(
+    app: tauri::AppHandle,
+    bundle_path: String,
+    passphrase: Option<String>,
+) -> Result<ConnectionImportResult, String> {
+    let bundle_path = bundle_path.trim();
+    if bundle_path.is_empty() {
+        return Err("bundlePath is required".to_string());
+    }
+    let raw = read_archive_bytes(bundle_path, passphrase.as_deref())?;
+    let bundle = ConnectionBundle::parse(&raw)?;
+
+    let mut state = load_workspace_state(&app)?;
+    let mut summary = import_connection_profiles(&mut state, &bundle)?;
+    if !summary.tokens.is_empty() {
+        let vault = Vault::for_app(&app)?;
+        summary.tokens.retain(|id, token| {
+            let name = remote_token_secret_name(id);
+            if let Err(error) = vault.set_secret(&name, token, Some("OpenWork remote token")) {
+                println!("[workspace] token for {id} not stored in the vault: {error}");
+                return true;
+            }
+            if let Some(workspace) = state.workspaces.iter_mut().find(|w| &w.id == id) {
+                workspace.token_secret = Some(name);
+            }
+            false
+        });
+    }
+    if !summary.added.is_empty() {
+        save_workspace_state(&app, &state)?;
+    }
+    println!(
+        "[workspace] imported {} connection profiles ({} skipped)",
+        summary.added.len(),
+        summary.skipped.len()
+    );
+
+    Ok(ConnectionImportResult {
+        list: WorkspaceList {
+            active_id: state.active_id,
+            workspaces: state.workspaces,
+        },
+        summary,
+    })
+}
+
+/// Lists the secret placeholders an archive expects, so the UI can ask for them before
+/// importing.
+#[tauri::command]
+pub fn workspace_archive_redactions(
+    archive_path: String,
+    passphrase: Option<String>,
+) -> Result<Vec<SecretRedaction>, String> {
+    let bytes = read_archive_bytes(archive_path.trim(), passphrase.as_deref())?;
+    Ok(read_workspace_archive(bytes)?.redactions())
+}
+
+fn archive_warnings(app: &tauri::AppHandle, archive: &WorkspaceArchive) -> Vec<String> {
+    let Some(manifest) = &archive.manifest else {
+        return Vec::new();
+    };
+    let presets: Vec<String> = load_workspace_presets(app)
+        .into_iter()
+        .map(|preset| preset.id)
+        .collect();
+    compatibility_warnings(manifest, &app.package_info().version.to_string(), &presets)
+}
+
+/// Dry run of `workspace_import_config`: what the archive holds and what importing it into
+/// `target_dir` with the given resolutions would do. Nothing is written.
+#[tauri::command]
+pub fn workspace_import_preview(
+    app: tauri::AppHandle,
+    archive_path: String,
+    target_dir: Option<String>,
+    options: Option<WorkspaceImportOptions>,
+) -> Result<WorkspaceImportPreview, String> {
+    let options = options.unwrap_or_default();
+    let archive_path = archive_path.trim();
+    if archive_path.is_empty() {
+        return Err("archivePath is required".to_string());
+    }
+    let encrypted = fs::read(archive_path)
+        .map(|bytes| is_encrypted(&bytes))
+        .unwrap_or(false);
+    let bytes = read_archive_bytes(archive_path, options.passphrase.as_deref())?;
+    let archive = read_workspace_archive(bytes)?;
+
+    let target_path = target_dir
+        .map(|dir| PathBuf::from(dir.trim()))
+        .filter(|dir| !dir.as_os_str().is_empty());
+    let target_exists = target_path.as_ref().is_some_and(|dir| dir.exists());
+    // Without a target everything is new; plan against a path that can't exist.
+    let plan_root = target_path.unwrap_or_else(|| {
+        std::env::temp_dir().join(format!("openwork-import-preview-{}", now_ms()))
+    });
+
+    Ok(WorkspaceImportPreview {
+        encrypted,
+        target_exists,
+        files: plan_import(&archive, &plan_root, &options.resolutions),
+        warnings: archive_warnings(&app, &archive),
+        redactions: archive.redactions(),
+        skipped_secrets: archive.skipped_secrets,
+        manifest: archive.manifest,
     })
 }
 
@@ -676,19 +1505,21 @@ pub fn workspace_import_config(
     archive_path: String,
     target_dir: String,
     name: Option<String>,
+    options: Option<WorkspaceImportOptions>,
     watch_state: State<WorkspaceWatchState>,
 ) -> Result<WorkspaceList, String> {
     let archive_path = archive_path.trim().to_string();
     if archive_path.is_empty() {
         return Err("archivePath is required".to_string());
     }
+    let options = options.unwrap_or_default();
     let target_dir = target_dir.trim().to_string();
     if target_dir.is_empty() {
         return Err("targetDir is required".to_string());
     }
 
     let target_path = PathBuf::from(&target_dir);
-    if target_path.exists() {
+    if target_path.exists() && !options.merge {
         let mut entries = fs::read_dir(&target_path)
             .map_err(|e| format!("Failed to read {}: {e}", target_path.display()))?;
         if entries.next().is_some() {
@@ -696,60 +1527,34 @@ pub fn workspace_import_config(
         }
     }
 
-    fs::create_dir_all(&target_path)
-        .map_err(|e| format!("Failed to create {}: {e}", target_path.display()))?;
-
-    let file = fs::File::open(&archive_path)
-        .map_err(|e| format!("Failed to open {}: {e}", archive_path))?;
-    let mut archive = ZipArchive::new(file)
-        .map_err(|e| format!("Failed to read archive: {e}"))?;
-
-    for i in 0..archive.len() {
-        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
-        let name = entry.name().to_string();
-        if name == "manifest.json" {
-            continue;
-        }
-        let entry_path = Path::new(&name);
-        if entry_path.components().any(|component| match component {
-            std::path::Component::ParentDir
-            | std::path::Component::RootDir
-            | std::path::Component::Prefix(_) => true,
-            _ => false,
-        }) {
-            return Err("Archive contains an unsafe path".to_string());
-        }
-        if !(name == "opencode.json" || name.starts_with(".opencode/")) {
-            continue;
-        }
-        if let Some(file_name) = entry_path.file_name().and_then(|entry| entry.to_str()) {
-            if is_secret_name(file_name) {
-                continue;
-            }
-        }
-        let out_path = target_path.join(Path::new(&name));
-        if let Some(parent) = out_path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
-        }
-        if entry.name().ends_with('/') {
-            fs::create_dir_all(&out_path)
-                .map_err(|e| format!("Failed to create {}: {e}", out_path.display()))?;
-            continue;
-        }
-        let mut buffer = Vec::new();
-        entry.read_to_end(&mut buffer)
-            .map_err(|e| format!("Failed to read archive entry: {e}"))?;
-        fs::write(&out_path, buffer)
-            .map_err(|e| format!("Failed to write {}: {e}", out_path.display()))?;
+    let bytes = read_archive_bytes(&archive_path, options.passphrase.as_deref())?;
+    let archive = read_workspace_archive(bytes)?;
+    if !archive
+        .entries
+        .iter()
+        .any(|entry| entry.path.starts_with(".opencode/"))
+    {
+        return Err("Archive is missing .opencode config".to_string());
     }
 
-    let opencode_dir = target_path.join(".opencode");
-    if !opencode_dir.exists() {
-        return Err("Archive is missing .opencode config".to_string());
+    for warning in archive_warnings(&app, &archive) {
+        println!("[workspace] import: {warning}");
     }
 
+    fs::create_dir_all(&target_path)
+        .map_err(|e| format!("Failed to create {}: {e}", target_path.display()))?;
+
     let openwork_path = target_path.join(".opencode").join("openwork.json");
+    let had_openwork = openwork_path.exists();
+    let plans = plan_import(&archive, &target_path, &options.resolutions);
+    let missing = apply_import(&archive, &target_path, &plans, &options.secrets)?;
+    if !missing.is_empty() {
+        println!(
+            "[workspace] import left {} secret placeholders unfilled",
+            missing.len()
+        );
+    }
+
     let mut preset = "starter".to_string();
     let mut workspace_name = name.clone().filter(|value| !value.trim().is_empty());
 
@@ -757,7 +1562,10 @@ pub fn workspace_import_config(
         let raw = fs::read_to_string(&openwork_path)
             .map_err(|e| format!("Failed to read {}: {e}", openwork_path.display()))?;
         if let Ok(mut config) = serde_json::from_str::<WorkspaceOpenworkConfig>(&raw) {
-            config.authorized_roots = vec![target_dir.clone()];
+            // A merge keeps the workspace's own roots; a fresh import drops the exporter's.
+            if !had_openwork {
+                config.authorized_roots = vec![target_dir.clone()];
+            }
             if let Some(workspace) = &config.workspace {
                 if workspace_name.is_none() {
                     workspace_name = workspace.name.clone().filter(|value| !value.trim().is_empty());
@@ -798,24 +1606,40 @@ pub fn workspace_import_config(
         .trim()
         .to_string();
 
-    let id = stable_workspace_id(&target_dir);
-
     let mut state = load_workspace_state(&app)?;
-    state.workspaces.retain(|w| w.id != id);
-    state.workspaces.push(WorkspaceInfo {
-        id: id.clone(),
-        name,
-        path: target_dir.clone(),
-        preset,
-        workspace_type: WorkspaceType::Local,
-        remote_type: None,
-        base_url: None,
-        directory: None,
-        display_name: None,
-        openwork_host_url: None,
-        openwork_workspace_id: None,
-        openwork_workspace_name: None,
-    });
+    // Merging into a known workspace keeps its entry (and id, which may predate a move).
+    let existing_id = state
+        .workspaces
+        .iter()
+        .find(|w| w.workspace_type == WorkspaceType::Local && w.path == target_dir)
+        .map(|w| w.id.clone());
+    let id = match existing_id {
+        Some(id) => id,
+        None => {
+            let id = stable_workspace_id(&target_dir);
+            state.workspaces.retain(|w| w.id != id);
+            state.workspaces.push(WorkspaceInfo {
+                id: id.clone(),
+                name,
+                path: target_dir.clone(),
+                preset,
+                workspace_type: WorkspaceType::Local,
+                remote_type: None,
+                base_url: None,
+                directory: None,
+                display_name: None,
+                openwork_host_url: None,
+                openwork_workspace_id: None,
+                openwork_workspace_name: None,
+                token_secret: None,
+                opencode_profile: None,
+                engine_launcher: None,
+                engine_sandbox: false,
+                resource_limits: None,
+            });
+            id
+        }
+    };
     state.active_id = id.clone();
     save_workspace_state(&app, &state)?;
 
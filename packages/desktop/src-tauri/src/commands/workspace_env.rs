@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use tauri::AppHandle;
+
+use crate::vault::Vault;
+use crate::workspace::env::{
+    load_workspace_env, remove_workspace_env, set_workspace_env, set_workspace_env_files,
+    workspace_id_for_path, WorkspaceEnvReport,
+};
+
+fn workspace_root(workspace_path: &str) -> Result<PathBuf, String> {
+    let trimmed = workspace_path.trim();
+    if trimmed.is_empty() {
+        return Err("workspacePath is required".to_string());
+    }
+    let root = PathBuf::from(trimmed);
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+    Ok(root)
+}
+
+fn report(app: &AppHandle, workspace_path: &str) -> Result<WorkspaceEnvReport, String> {
+    Ok(load_workspace_env(app, workspace_path)?.report)
+}
+
+/// Effective variables for a workspace. Secret and env-file values are never returned.
+#[tauri::command]
+pub fn workspace_env_list(
+    app: AppHandle,
+    workspace_path: String,
+) -> Result<WorkspaceEnvReport, String> {
+    workspace_root(&workspace_path)?;
+    report(&app, &workspace_path)
+}
+
+/// Sets a variable in `openwork.json`, or in the vault when `secret` is true. Running
+/// processes pick it up on their next start.
+#[tauri::command]
+pub fn workspace_env_set(
+    app: AppHandle,
+    workspace_path: String,
+    name: String,
+    value: String,
+    secret: bool,
+) -> Result<WorkspaceEnvReport, String> {
+    let root = workspace_root(&workspace_path)?;
+    let name = name.trim();
+    set_workspace_env(
+        &root,
+        &Vault::for_app(&app)?,
+        &workspace_id_for_path(&app, &workspace_path),
+        name,
+        &value,
+        secret,
+    )?;
+    println!("[workspace] env set: {name} (secret: {secret})");
+    report(&app, &workspace_path)
+}
+
+#[tauri::command]
+pub fn workspace_env_remove(
+    app: AppHandle,
+    workspace_path: String,
+    name: String,
+) -> Result<WorkspaceEnvReport, String> {
+    let root = workspace_root(&workspace_path)?;
+    let name = name.trim();
+    remove_workspace_env(
+        &root,
+        &Vault::for_app(&app)?,
+        &workspace_id_for_path(&app, &workspace_path),
+        name,
+    )?;
+    println!("[workspace] env removed: {name}");
+    report(&app, &workspace_path)
+}
+
+/// Replaces `envFiles`. Every entry must resolve inside an authorized root.
+#[tauri::command]
+pub fn workspace_env_files_set(
+    app: AppHandle,
+    workspace_path: String,
+    files: Vec<String>,
+) -> Result<WorkspaceEnvReport, String> {
+    let root = workspace_root(&workspace_path)?;
+    set_workspace_env_files(&root, &files)?;
+    report(&app, &workspace_path)
+}
@@ -0,0 +1,363 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::jsonc::{escape_pointer, JsoncDocument};
+use crate::paths::{home_dir, infer_opencode_config_home};
+
+/// Arrays OpenCode concatenates across config files instead of replacing.
+const CONCATENATED_KEYS: [&str; 2] = ["plugin", "instructions"];
+
+const GLOBAL_CONFIG_FILES: [&str; 3] = ["config.json", "opencode.json", "opencode.jsonc"];
+const PROJECT_CONFIG_FILES: [&str; 2] = ["opencode.jsonc", "opencode.json"];
+
+/// Where `source` came from for an inline config passed through the environment.
+pub const INLINE_CONFIG_SOURCE: &str = "OPENCODE_CONFIG_CONTENT";
+
+/// One config file that contributed (or failed to contribute) to the merged config.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigLayer {
+    /// `global`, `custom`, `project` or `inline`.
+    pub scope: String,
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveOpencodeConfig {
+    pub config: Value,
+    /// In merge order; later layers win.
+    pub layers: Vec<ConfigLayer>,
+    /// JSON pointer of every leaf value (and of each `plugin`/`instructions` entry) to the
+    /// layer source it came from.
+    pub provenance: BTreeMap<String, String>,
+}
+
+/// Inputs OpenCode reads from outside the project.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigInputs {
+    pub global_dir: Option<PathBuf>,
+    pub custom_path: Option<PathBuf>,
+    pub inline: Option<String>,
+}
+
+impl ConfigInputs {
+    /// What a sidecar spawned by OpenWork would see, including the inferred XDG config home.
+    pub fn from_env() -> Self {
+        let non_empty = |name: &str| env::var(name).ok().filter(|value| !value.trim().is_empty());
+        let config_home = non_empty("XDG_CONFIG_HOME")
+            .or_else(infer_opencode_config_home)
+            .map(PathBuf::from)
+            .or_else(|| home_dir().map(|home| home.join(".config")));
+
+        Self {
+            global_dir: config_home.map(|dir| dir.join("opencode")),
+            custom_path: non_empty("OPENCODE_CONFIG").map(PathBuf::from),
+            inline: non_empty(INLINE_CONFIG_SOURCE),
+        }
+    }
+}
+
+/// Project directories OpenCode searches, from the git root (or filesystem root outside a
+/// repository) down to `project_dir`.
+fn project_search_dirs(project_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for dir in project_dir.ancestors() {
+        dirs.push(dir.to_path_buf());
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    dirs.reverse();
+    dirs
+}
+
+fn layer_files(inputs: &ConfigInputs, project_dir: Option<&Path>) -> Vec<(&'static str, PathBuf)> {
+    let mut files = Vec::new();
+    if let Some(dir) = &inputs.global_dir {
+        for name in GLOBAL_CONFIG_FILES {
+            files.push(("global", dir.join(name)));
+        }
+    }
+    if let Some(path) = &inputs.custom_path {
+        files.push(("custom", path.clone()));
+    }
+
+    let dirs = project_dir.map(project_search_dirs).unwrap_or_default();
+    for name in PROJECT_CONFIG_FILES {
+        for dir in &dirs {
+            files.push(("project", dir.join(name)));
+        }
+    }
+    for dir in &dirs {
+        for name in PROJECT_CONFIG_FILES {
+            files.push(("project", dir.join(".opencode").join(name)));
+        }
+    }
+
+    files
+}
+
+fn forget(provenance: &mut BTreeMap<String, String>, pointer: &str) {
+    let nested = format!("{pointer}/");
+    provenance.retain(|key, _| key != pointer && !key.starts_with(&nested));
+}
+
+fn record(
+    provenance: &mut BTreeMap<String, String>,
+    value: &Value,
+    pointer: &str,
+    source: &str,
+    per_item: bool,
+) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let child_pointer = format!("{pointer}/{}", escape_pointer(key));
+                record(provenance, child, &child_pointer, source, false);
+            }
+        }
+        Value::Array(items) if per_item && !items.is_empty() => {
+            for index in 0..items.len() {
+                provenance.insert(format!("{pointer}/{index}"), source.to_string());
+            }
+        }
+        _ => {
+            provenance.insert(pointer.to_string(), source.to_string());
+        }
+    }
+}
+
+fn merge_object(
+    target: &mut Map<String, Value>,
+    layer: &Map<String, Value>,
+    pointer: &str,
+    source: &str,
+    provenance: &mut BTreeMap<String, String>,
+) {
+    for (key, value) in layer {
+        let child_pointer = format!("{pointer}/{}", escape_pointer(key));
+        let concatenated = pointer.is_empty() && CONCATENATED_KEYS.contains(&key.as_str());
+
+        match (target.get_mut(key), value) {
+            (Some(Value::Object(existing)), Value::Object(incoming)) => {
+                merge_object(existing, incoming, &child_pointer, source, provenance);
+            }
+            (Some(Value::Array(existing)), Value::Array(incoming)) if concatenated => {
+                for item in incoming {
+                    if !existing.contains(item) {
+                        provenance.insert(
+                            format!("{child_pointer}/{}", existing.len()),
+                            source.to_string(),
+                        );
+                        existing.push(item.clone());
+                    }
+                }
+            }
+            _ => {
+                forget(provenance, &child_pointer);
+                record(provenance, value, &child_pointer, source, concatenated);
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Merges every config OpenCode would load for `project_dir`, in OpenCode's precedence order:
+/// global files, `OPENCODE_CONFIG`, project files from the git root down (every
+/// `opencode.jsonc` before any `opencode.json`), `.opencode/` directories, then
+/// `OPENCODE_CONFIG_CONTENT`. Without a project only the user-level layers apply.
+pub fn resolve_effective_config(
+    inputs: &ConfigInputs,
+    project_dir: Option<&Path>,
+) -> EffectiveOpencodeConfig {
+    let mut parsed: Vec<(ConfigLayer, Option<Value>)> = Vec::new();
+
+    for (scope, path) in layer_files(inputs, project_dir) {
+        if !path.is_file() {
+            continue;
+        }
+        let source = path.to_string_lossy().to_string();
+        let value = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))
+            .and_then(|content| parse_layer(&content));
+        parsed.push(layer_result(scope, source, value));
+    }
+
+    if let Some(content) = &inputs.inline {
+        let value = parse_layer(content);
+        parsed.push(layer_result(
+            "inline",
+            INLINE_CONFIG_SOURCE.to_string(),
+            value,
+        ));
+    }
+
+    let mut config = Map::new();
+    let mut provenance = BTreeMap::new();
+    let mut layers = Vec::new();
+    for (layer, value) in parsed {
+        if let Some(Value::Object(map)) = &value {
+            merge_object(&mut config, map, "", &layer.source, &mut provenance);
+        }
+        layers.push(layer);
+    }
+
+    EffectiveOpencodeConfig {
+        config: Value::Object(config),
+        layers,
+        provenance,
+    }
+}
+
+fn parse_layer(content: &str) -> Result<Value, String> {
+    let value = JsoncDocument::parse(content)?.value()?;
+    if !value.is_object() {
+        return Err("config must be a JSON object".to_string());
+    }
+    Ok(value)
+}
+
+fn layer_result(
+    scope: &str,
+    source: String,
+    value: Result<Value, String>,
+) -> (ConfigLayer, Option<Value>) {
+    let (value, error) = match value {
+        Ok(value) => (Some(value), None),
+        Err(error) => (None, Some(error)),
+    };
+    let layer = ConfigLayer {
+        scope: scope.to_string(),
+        source,
+        error,
+    };
+    (layer, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::TempDir;
+
+    struct Layers {
+        _root: TempDir,
+        global: String,
+        repo: String,
+        project: String,
+        broken: String,
+        effective: EffectiveOpencodeConfig,
+    }
+
+    fn resolve_layers() -> Layers {
+        let root = TempDir::new("effective-config");
+        let global = root.join("xdg").join("opencode");
+        let repo = root.join("repo");
+        let project = repo.join("app");
+        fs::create_dir_all(&global).expect("global dir");
+        fs::create_dir_all(repo.join(".git")).expect("git dir");
+        fs::create_dir_all(project.join(".opencode")).expect("project dir");
+
+        let global_file = global.join("opencode.jsonc");
+        fs::write(
+            &global_file,
+            r#"{
+  // user defaults
+  "model": "a/base",
+  "plugin": ["shared", "global-only"],
+  "mcp": { "search": { "type": "remote", "url": "https://g.example", "enabled": true } },
+}"#,
+        )
+        .expect("global config");
+        let repo_file = repo.join("opencode.jsonc");
+        fs::write(
+            &repo_file,
+            r#"{ "theme": "dark", "plugin": ["shared", "repo"] }"#,
+        )
+        .expect("repo config");
+        let project_file = project.join("opencode.jsonc");
+        fs::write(
+            &project_file,
+            r#"{ "mcp": { "search": { "enabled": false } }, "theme": "light" }"#,
+        )
+        .expect("project config");
+        let broken = project.join(".opencode").join("opencode.json");
+        fs::write(&broken, "{ nope").expect("broken config");
+
+        let inputs = ConfigInputs {
+            global_dir: Some(global),
+            custom_path: None,
+            inline: Some(r#"{ "model": "b/override" }"#.to_string()),
+        };
+        let effective = resolve_effective_config(&inputs, Some(&project));
+        let source = |path: &Path| path.to_string_lossy().to_string();
+        Layers {
+            global: source(&global_file),
+            repo: source(&repo_file),
+            project: source(&project_file),
+            broken: source(&broken),
+            effective,
+            _root: root,
+        }
+    }
+
+    #[test]
+    fn later_layers_override_and_objects_deep_merge() {
+        let config = resolve_layers().effective.config;
+        assert_eq!(config["model"], "b/override");
+        assert_eq!(config["theme"], "light");
+        assert_eq!(config["mcp"]["search"]["enabled"], false);
+        assert_eq!(config["mcp"]["search"]["url"], "https://g.example");
+    }
+
+    #[test]
+    fn plugin_lists_concatenate_without_duplicates() {
+        let config = resolve_layers().effective.config;
+        assert_eq!(
+            config["plugin"],
+            serde_json::json!(["shared", "global-only", "repo"])
+        );
+    }
+
+    #[test]
+    fn records_which_file_set_each_value() {
+        let layers = resolve_layers();
+        let provenance = &layers.effective.provenance;
+        assert_eq!(provenance["/model"], INLINE_CONFIG_SOURCE);
+        assert_eq!(provenance["/theme"], layers.project);
+        assert_eq!(provenance["/plugin/0"], layers.global);
+        assert_eq!(provenance["/plugin/2"], layers.repo);
+        assert_eq!(provenance["/mcp/search/url"], layers.global);
+        assert_eq!(provenance["/mcp/search/enabled"], layers.project);
+    }
+
+    #[test]
+    fn lists_layers_in_order_and_keeps_broken_ones_with_an_error() {
+        let layers = resolve_layers();
+        let scopes: Vec<&str> = layers
+            .effective
+            .layers
+            .iter()
+            .map(|l| l.scope.as_str())
+            .collect();
+        assert_eq!(
+            scopes,
+            vec!["global", "project", "project", "project", "inline"]
+        );
+        let failed: Vec<&ConfigLayer> = layers
+            .effective
+            .layers
+            .iter()
+            .filter(|layer| layer.error.is_some())
+            .collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].source, layers.broken);
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::jsonc::JsoncDocument;
+use crate::vault::{secret_reference, validate_secret_name};
+
+/// An entry of the `mcp` section of an OpenCode config.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum McpServerConfig {
+    Local {
+        command: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        environment: Option<BTreeMap<String, String>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        enabled: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout: Option<u64>,
+    },
+    Remote {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        headers: Option<BTreeMap<String, String>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        oauth: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        enabled: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout: Option<u64>,
+    },
+}
+
+impl McpServerConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            McpServerConfig::Local { command, .. } => {
+                if command
+                    .first()
+                    .is_none_or(|program| program.trim().is_empty())
+                {
+                    return Err("Local MCP servers need a command".to_string());
+                }
+            }
+            McpServerConfig::Remote { url, .. } => {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err("Remote MCP url must start with http:// or https://".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A configured server; `server` is unset when the entry doesn't match either shape.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerEntry {
+    pub name: String,
+    pub server: Option<McpServerConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub fn validate_mcp_server_name(name: &str) -> Result<String, String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("server_name is required".to_string());
+    }
+
+    if trimmed.starts_with('-') {
+        return Err("server_name must not start with '-'".to_string());
+    }
+
+    if !trimmed
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err("server_name must be alphanumeric with '-' or '_'".to_string());
+    }
+
+    Ok(trimmed.to_string())
+}
+
+pub fn list_mcp_servers(doc: &JsoncDocument) -> Vec<McpServerEntry> {
+    let Some(Value::Object(servers)) = doc.get(&["mcp"]) else {
+        return Vec::new();
+    };
+    servers
+        .into_iter()
+        .map(|(name, value)| match serde_json::from_value(value) {
+            Ok(server) => McpServerEntry {
+                name,
+                server: Some(server),
+                error: None,
+            },
+            Err(e) => McpServerEntry {
+                name,
+                server: None,
+                error: Some(e.to_string()),
+            },
+        })
+        .collect()
+}
+
+/// Rewrites `{vault:NAME}` values as `{env:NAME}`, which OpenCode substitutes itself; the
+/// engine spawn injects referenced vault secrets under the same name.
+fn vault_refs_to_env(values: &mut Option<BTreeMap<String, String>>) -> Result<(), String> {
+    for value in values.iter_mut().flat_map(|map| map.values_mut()) {
+        if let Some(name) = secret_reference(value) {
+            validate_secret_name(name)?;
+            *value = format!("{{env:{name}}}");
+        }
+    }
+    Ok(())
+}
+
+fn server_value(server: &McpServerConfig) -> Result<Value, String> {
+    server.validate()?;
+    let mut server = server.clone();
+    match &mut server {
+        McpServerConfig::Local { environment, .. } => vault_refs_to_env(environment)?,
+        McpServerConfig::Remote { headers, .. } => vault_refs_to_env(headers)?,
+    }
+    serde_json::to_value(&server).map_err(|e| format!("Failed to serialize MCP server: {e}"))
+}
+
+fn env_reference_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"\{env:([A-Za-z_][A-Za-z0-9_]*)\}").expect("valid env reference pattern")
+    })
+}
+
+/// Variables read through `{env:NAME}` in MCP headers and environments of `config`.
+pub fn env_references(config: &Value) -> BTreeSet<String> {
+    let Some(servers) = config.get("mcp").and_then(Value::as_object) else {
+        return BTreeSet::new();
+    };
+    servers
+        .values()
+        .flat_map(|server| ["headers", "environment"].map(|key| server.get(key)))
+        .flatten()
+        .filter_map(Value::as_object)
+        .flat_map(|map| map.values())
+        .filter_map(Value::as_str)
+        .flat_map(|text| env_reference_pattern().captures_iter(text))
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+/// A local server's `environment` as the server receives it once OpenCode starts it:
+/// `{vault:NAME}` values become the secret and `{env:NAME}` reads `env`, with unset variables
+/// substituted as empty the way OpenCode does.
+pub fn resolve_server_environment(
+    environment: &BTreeMap<String, String>,
+    env: impl Fn(&str) -> Option<String>,
+    secret: impl Fn(&str) -> Result<Option<String>, String>,
+) -> Result<BTreeMap<String, String>, String> {
+    let mut resolved = BTreeMap::new();
+    for (key, value) in environment {
+        let value = match secret_reference(value) {
+            Some(name) => {
+                validate_secret_name(name)?;
+                secret(name)?.ok_or_else(|| format!("Vault secret {name} is not set"))?
+            }
+            None => env_reference_pattern()
+                .replace_all(value, |captures: &regex::Captures| {
+                    env(&captures[1]).unwrap_or_default()
+                })
+                .into_owned(),
+        };
+        resolved.insert(key.clone(), value);
+    }
+    Ok(resolved)
+}
+
+pub fn add_mcp_server(
+    doc: &mut JsoncDocument,
+    name: &str,
+    server: &McpServerConfig,
+) -> Result<(), String> {
+    let name = validate_mcp_server_name(name)?;
+    if !doc.insert_key(&["mcp"], &name, &server_value(server)?)? {
+        return Err(format!("MCP server {name} already exists"));
+    }
+    Ok(())
+}
+
+pub fn update_mcp_server(
+    doc: &mut JsoncDocument,
+    name: &str,
+    server: &McpServerConfig,
+) -> Result<(), String> {
+    let name = validate_mcp_server_name(name)?;
+    if doc.get(&["mcp", &name]).is_none() {
+        return Err(format!("MCP server {name} not found"));
+    }
+    doc.set_key(&["mcp"], &name, &server_value(server)?)
+}
+
+pub fn remove_mcp_server(doc: &mut JsoncDocument, name: &str) -> Result<(), String> {
+    let name = validate_mcp_server_name(name)?;
+    if !doc.remove_key(&["mcp"], &name)? {
+        return Err(format!("MCP server {name} not found"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_typed_servers_and_keeps_comments() {
+        let mut doc = JsoncDocument::parse(
+            r#"{
+  // servers
+  "mcp": {
+    "broken": { "type": "local" }, // missing command
+  },
+}
+"#,
+        )
+        .expect("parse");
+
+        let local = McpServerConfig::Local {
+            command: vec!["npx".to_string(), "-y".to_string(), "srv".to_string()],
+            environment: None,
+            enabled: Some(true),
+            timeout: None,
+        };
+        add_mcp_server(&mut doc, "files", &local).expect("add");
+        assert!(add_mcp_server(&mut doc, "files", &local).is_err());
+
+        let remote = McpServerConfig::Remote {
+            url: "https://mcp.example".to_string(),
+            headers: None,
+            oauth: None,
+            enabled: None,
+            timeout: Some(5000),
+        };
+        update_mcp_server(&mut doc, "files", &remote).expect("update");
+        assert!(update_mcp_server(&mut doc, "missing", &remote).is_err());
+        assert!(add_mcp_server(&mut doc, "bad url", &remote).is_err());
+
+        let entries = list_mcp_servers(&doc);
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].server.is_none() && entries[0].error.is_some());
+        assert_eq!(entries[1].server.as_ref(), Some(&remote));
+
+        remove_mcp_server(&mut doc, "broken").expect("remove");
+        assert!(remove_mcp_server(&mut doc, "broken").is_err());
+        assert!(doc.text().contains("// servers"));
+        assert!(!doc.text().contains("missing command"));
+        assert_eq!(list_mcp_servers(&doc).len(), 1);
+
+        let authed = McpServerConfig::Remote {
+            url: "https://mcp.example".to_string(),
+            headers: Some(BTreeMap::from([(
+                "Authorization".to_string(),
+                "{vault:GITHUB_TOKEN}".to_string(),
+            )])),
+            oauth: None,
+            enabled: None,
+            timeout: None,
+        };
+        add_mcp_server(&mut doc, "github", &authed).expect("add with vault ref");
+        assert!(doc.text().contains("{env:GITHUB_TOKEN}"));
+        let config = doc.value().expect("value");
+        assert_eq!(
+            env_references(&config).into_iter().collect::<Vec<_>>(),
+            vec!["GITHUB_TOKEN"]
+        );
+    }
+
+    #[test]
+    fn resolves_vault_and_env_references_in_server_environment() {
+        let environment = BTreeMap::from([
+            ("TOKEN".to_string(), "{vault:GITHUB_TOKEN}".to_string()),
+            (
+                "URL".to_string(),
+                "https://{env:HOST}/{env:UNSET}".to_string(),
+            ),
+            ("PLAIN".to_string(), "value".to_string()),
+        ]);
+        let resolved = resolve_server_environment(
+            &environment,
+            |name| (name == "HOST").then(|| "api.example".to_string()),
+            |name| Ok((name == "GITHUB_TOKEN").then(|| "ghp_secret".to_string())),
+        )
+        .expect("resolve");
+        assert_eq!(resolved["TOKEN"], "ghp_secret");
+        assert_eq!(resolved["URL"], "https://api.example/");
+        assert_eq!(resolved["PLAIN"], "value");
+
+        let missing = BTreeMap::from([("TOKEN".to_string(), "{vault:MISSING}".to_string())]);
+        let error = resolve_server_environment(&missing, |_| None, |_| Ok(None)).unwrap_err();
+        assert!(error.contains("MISSING"), "{error}");
+    }
+}
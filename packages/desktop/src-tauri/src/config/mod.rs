@@ -0,0 +1,167 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::jsonc::JsoncDocument;
+use crate::types::{ExecResult, OpencodeConfigFile};
+
+pub mod effective;
+pub mod mcp;
+pub mod plugins;
+pub mod validate;
+
+/// Written when a workspace has no OpenCode config yet.
+pub const DEFAULT_OPENCODE_CONFIG: &str =
+    "{\n  \"$schema\": \"https://opencode.ai/config.json\"\n}\n";
+
+fn opencode_config_candidates(
+    scope: &str,
+    project_dir: &str,
+) -> Result<(PathBuf, PathBuf), String> {
+    match scope {
+        "project" => {
+            if project_dir.trim().is_empty() {
+                return Err("projectDir is required".to_string());
+            }
+            let root = PathBuf::from(project_dir);
+            Ok((root.join("opencode.jsonc"), root.join("opencode.json")))
+        }
+        "global" => {
+            let base = if let Ok(dir) = env::var("XDG_CONFIG_HOME") {
+                PathBuf::from(dir)
+            } else if let Ok(home) = env::var("HOME") {
+                PathBuf::from(home).join(".config")
+            } else {
+                return Err("Unable to resolve config directory".to_string());
+            };
+
+            let root = base.join("opencode");
+            Ok((root.join("opencode.jsonc"), root.join("opencode.json")))
+        }
+        _ => Err("scope must be 'project' or 'global'".to_string()),
+    }
+}
+
+pub fn resolve_opencode_config_path(scope: &str, project_dir: &str) -> Result<PathBuf, String> {
+    let (jsonc_path, json_path) = opencode_config_candidates(scope, project_dir)?;
+
+    if jsonc_path.exists() {
+        return Ok(jsonc_path);
+    }
+
+    if json_path.exists() {
+        return Ok(json_path);
+    }
+
+    Ok(jsonc_path)
+}
+
+pub fn read_opencode_config(scope: &str, project_dir: &str) -> Result<OpencodeConfigFile, String> {
+    let path = resolve_opencode_config_path(scope.trim(), project_dir)?;
+    let exists = path.exists();
+
+    let content = if exists {
+        Some(
+            fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {e}", path.display()))?,
+        )
+    } else {
+        None
+    };
+
+    Ok(OpencodeConfigFile {
+        path: path.to_string_lossy().to_string(),
+        exists,
+        content,
+    })
+}
+
+pub fn write_opencode_config(
+    scope: &str,
+    project_dir: &str,
+    content: &str,
+) -> Result<ExecResult, String> {
+    let path = resolve_opencode_config_path(scope.trim(), project_dir)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config dir {}: {e}", parent.display()))?;
+    }
+
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+
+    Ok(ExecResult {
+        ok: true,
+        status: 0,
+        stdout: format!("Wrote {}", path.display()),
+        stderr: String::new(),
+    })
+}
+
+/// Parses the config for `scope`, or the default config if the file doesn't exist yet.
+pub fn load_opencode_document(scope: &str, project_dir: &str) -> Result<JsoncDocument, String> {
+    let file = read_opencode_config(scope.trim(), project_dir)?;
+    let content = file
+        .content
+        .unwrap_or_else(|| DEFAULT_OPENCODE_CONFIG.to_string());
+    JsoncDocument::parse(&content).map_err(|e| format!("{}: {e}", file.path))
+}
+
+/// Applies `edit` to the config for `scope` and writes it back with comments intact.
+pub fn edit_opencode_document(
+    scope: &str,
+    project_dir: &str,
+    edit: impl FnOnce(&mut JsoncDocument) -> Result<(), String>,
+) -> Result<JsoncDocument, String> {
+    let mut doc = load_opencode_document(scope, project_dir)?;
+    edit(&mut doc)?;
+    write_opencode_config(scope.trim(), project_dir, doc.text())?;
+    Ok(doc)
+}
+
+/// Adds plugins and MCP servers missing from an OpenCode config. Existing MCP servers are
+/// never replaced. Returns whether anything changed.
+pub fn add_opencode_entries(
+    doc: &mut JsoncDocument,
+    plugins: &[serde_json::Value],
+    mcp: &serde_json::Map<String, serde_json::Value>,
+) -> Result<bool, String> {
+    let mut changed = false;
+
+    match doc.get(&["plugin"]) {
+        // A single plugin may be written as a bare string; widen it to a list.
+        Some(existing @ serde_json::Value::String(_)) => {
+            let mut merged = vec![existing];
+            for plugin in plugins {
+                if !merged.contains(plugin) {
+                    merged.push(plugin.clone());
+                }
+            }
+            if merged.len() > 1 {
+                doc.set_key(&[], "plugin", &serde_json::Value::Array(merged))?;
+                changed = true;
+            }
+        }
+        existing => {
+            let mut present = match existing {
+                Some(serde_json::Value::Array(items)) => items,
+                _ => Vec::new(),
+            };
+            for plugin in plugins {
+                if !present.contains(plugin) {
+                    doc.append_to_array(&["plugin"], plugin)?;
+                    present.push(plugin.clone());
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    for (name, server) in mcp {
+        if doc.insert_key(&["mcp"], name, server)? {
+            changed = true;
+        }
+    }
+
+    Ok(changed)
+}
@@ -0,0 +1,295 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use semver::{Version, VersionReq};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::jsonc::JsoncDocument;
+
+/// A `plugin` entry and whether OpenCode can load it without downloading.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginEntry {
+    /// Entry exactly as written in the config.
+    pub spec: String,
+    pub name: String,
+    /// Version, range or dist-tag the entry asks for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// `npm` or `file`.
+    pub source: String,
+    pub cached: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_path: Option<String>,
+}
+
+fn is_file_spec(spec: &str) -> bool {
+    spec.starts_with("file:")
+        || spec.starts_with('.')
+        || spec.starts_with('~')
+        || Path::new(spec).is_absolute()
+}
+
+/// Splits `name@version`, leaving the leading `@` of scoped packages alone.
+pub fn split_plugin_spec(spec: &str) -> (&str, Option<&str>) {
+    if is_file_spec(spec) {
+        return (spec, None);
+    }
+    match spec.rfind('@') {
+        Some(at) if at > 0 => (&spec[..at], Some(&spec[at + 1..])),
+        _ => (spec, None),
+    }
+}
+
+/// Entries of a `plugin` value; a bare string counts as a one-entry list.
+pub fn read_plugin_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(spec)) => vec![spec.clone()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+pub fn read_plugins(doc: &JsoncDocument) -> Vec<String> {
+    read_plugin_list(doc.get(&["plugin"]).as_ref())
+}
+
+/// Array indexes of the string entries naming `name`. Other entries, including ones that
+/// aren't strings, are never touched.
+fn plugin_indexes(doc: &JsoncDocument, name: &str) -> Vec<usize> {
+    let Some(Value::Array(items)) = doc.get(&["plugin"]) else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let spec = item.as_str()?;
+            (spec == name || split_plugin_spec(spec).0 == name).then_some(index)
+        })
+        .collect()
+}
+
+fn validate_spec(spec: &str) -> Result<(), String> {
+    let (name, version) = split_plugin_spec(spec);
+    if name.is_empty() || spec.chars().any(char::is_whitespace) {
+        return Err(format!("Invalid plugin: {spec:?}"));
+    }
+    if version.is_some_and(str::is_empty) {
+        return Err(format!("Plugin {name} has an empty version"));
+    }
+    Ok(())
+}
+
+pub fn add_plugin(doc: &mut JsoncDocument, spec: &str) -> Result<(), String> {
+    let spec = spec.trim();
+    validate_spec(spec)?;
+    normalize_plugins(doc)?;
+    let (name, _) = split_plugin_spec(spec);
+    if !plugin_indexes(doc, name).is_empty() {
+        return Err(format!("Plugin {name} is already listed"));
+    }
+    doc.append_to_array(&["plugin"], &Value::String(spec.to_string()))
+}
+
+pub fn remove_plugin(doc: &mut JsoncDocument, name: &str) -> Result<(), String> {
+    let name = name.trim();
+    normalize_plugins(doc)?;
+    let indexes = plugin_indexes(doc, name);
+    if indexes.is_empty() {
+        return Err(format!("Plugin {name} not found"));
+    }
+    for index in indexes.into_iter().rev() {
+        doc.remove_array_item(&["plugin"], index)?;
+    }
+    Ok(())
+}
+
+/// Pins `name` to `version`, or unpins it when `version` is `None`.
+pub fn pin_plugin(
+    doc: &mut JsoncDocument,
+    name: &str,
+    version: Option<&str>,
+) -> Result<(), String> {
+    let name = name.trim();
+    if is_file_spec(name) {
+        return Err("Local plugins can't be pinned".to_string());
+    }
+    let spec = match version.map(str::trim).filter(|version| !version.is_empty()) {
+        Some(version) => format!("{name}@{version}"),
+        None => name.to_string(),
+    };
+    validate_spec(&spec)?;
+    normalize_plugins(doc)?;
+    let Some(index) = plugin_indexes(doc, name).into_iter().next() else {
+        return Err(format!("Plugin {name} not found"));
+    };
+    doc.replace_at(&format!("/plugin/{index}"), &Value::String(spec))?;
+    Ok(())
+}
+
+/// Rewrites a bare-string `plugin` value as a list. Returns whether anything changed.
+pub fn normalize_plugins(doc: &mut JsoncDocument) -> Result<bool, String> {
+    let Some(Value::String(spec)) = doc.get(&["plugin"]) else {
+        return Ok(false);
+    };
+    doc.set_key(&[], "plugin", &Value::Array(vec![Value::String(spec)]))?;
+    Ok(true)
+}
+
+fn satisfies(installed: &str, wanted: &str) -> bool {
+    let Ok(installed) = Version::parse(installed) else {
+        return false;
+    };
+    if let Ok(exact) = Version::parse(wanted) {
+        return installed == exact;
+    }
+    match VersionReq::parse(wanted) {
+        Ok(req) => req.matches(&installed),
+        // Dist-tags like `latest` can't be checked offline; any installed copy will do.
+        Err(_) => true,
+    }
+}
+
+fn resolve_file_plugin(spec: &str, base_dir: Option<&Path>) -> PathBuf {
+    let raw = spec.strip_prefix("file://").unwrap_or(spec);
+    let raw = raw.strip_prefix("file:").unwrap_or(raw);
+    let path = Path::new(raw);
+    match base_dir {
+        Some(base) if path.is_relative() => base.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Describes `spec`, looking for npm plugins under `<cache>/node_modules` in each cache dir.
+/// Relative file plugins are resolved against `base_dir`.
+pub fn describe_plugin(spec: &str, cache_dirs: &[PathBuf], base_dir: Option<&Path>) -> PluginEntry {
+    let (name, version) = split_plugin_spec(spec);
+    let mut entry = PluginEntry {
+        spec: spec.to_string(),
+        name: name.to_string(),
+        version: version.map(str::to_string),
+        source: "npm".to_string(),
+        cached: false,
+        cached_version: None,
+        cache_path: None,
+    };
+
+    if is_file_spec(spec) {
+        let path = resolve_file_plugin(spec, base_dir);
+        entry.source = "file".to_string();
+        entry.cached = path.exists();
+        entry.cache_path = Some(path.to_string_lossy().to_string());
+        return entry;
+    }
+
+    for dir in cache_dirs {
+        let package_dir = dir.join("node_modules").join(name);
+        let Ok(raw) = fs::read_to_string(package_dir.join("package.json")) else {
+            continue;
+        };
+        let installed = serde_json::from_str::<Value>(&raw)
+            .ok()
+            .and_then(|manifest| manifest.get("version")?.as_str().map(str::to_string));
+        let matches = match (version, installed.as_deref()) {
+            (None, _) => true,
+            (Some(wanted), Some(installed)) => satisfies(installed, wanted),
+            (Some(_), None) => false,
+        };
+        entry.cached_version = installed;
+        entry.cache_path = Some(package_dir.to_string_lossy().to_string());
+        if matches {
+            entry.cached = true;
+            break;
+        }
+    }
+
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::TempDir;
+
+    #[test]
+    fn normalizes_a_single_plugin_string_into_a_list() {
+        let mut doc =
+            JsoncDocument::parse("{\n  // scheduling\n  \"plugin\": \"opencode-scheduler\"\n}\n")
+                .expect("parse");
+        assert!(normalize_plugins(&mut doc).expect("normalize"));
+        assert_eq!(read_plugins(&doc), vec!["opencode-scheduler"]);
+        assert!(doc.text().contains("// scheduling"));
+    }
+
+    #[test]
+    fn adds_pins_and_removes_plugins() {
+        let mut doc =
+            JsoncDocument::parse(r#"{ "plugin": ["opencode-scheduler"] }"#).expect("parse");
+        add_plugin(&mut doc, "@acme/notes@^1.2.0").expect("add");
+        assert!(add_plugin(&mut doc, "@acme/notes").is_err());
+        pin_plugin(&mut doc, "opencode-scheduler", Some("0.3.1")).expect("pin");
+        pin_plugin(&mut doc, "@acme/notes", None).expect("unpin");
+        assert_eq!(
+            read_plugins(&doc),
+            vec!["opencode-scheduler@0.3.1", "@acme/notes"]
+        );
+
+        remove_plugin(&mut doc, "opencode-scheduler").expect("remove");
+        assert!(remove_plugin(&mut doc, "opencode-scheduler").is_err());
+        assert_eq!(read_plugins(&doc), vec!["@acme/notes"]);
+    }
+
+    #[test]
+    fn compares_requested_versions_with_the_npm_cache() {
+        let cache = TempDir::new("plugin-cache");
+        let package = cache.join("node_modules").join("opencode-scheduler");
+        fs::create_dir_all(&package).expect("package dir");
+        fs::write(package.join("package.json"), r#"{ "version": "0.3.0" }"#).expect("manifest");
+        let dirs = vec![cache.to_path_buf()];
+
+        let stale = describe_plugin("opencode-scheduler@0.3.1", &dirs, None);
+        assert!(!stale.cached);
+        assert_eq!(stale.cached_version.as_deref(), Some("0.3.0"));
+        assert!(describe_plugin("opencode-scheduler@^0.3.0", &dirs, None).cached);
+        assert!(describe_plugin("opencode-scheduler@latest", &dirs, None).cached);
+        assert!(!describe_plugin("@acme/notes", &dirs, None).cached);
+        let local = describe_plugin("./plugins/mine.ts", &dirs, Some(&cache));
+        assert_eq!((local.source.as_str(), local.cached), ("file", false));
+    }
+
+    #[test]
+    fn keeps_comments_and_other_entries_inside_the_plugin_array() {
+        let config = r#"{
+  "plugin": [
+    // team plugins
+    "opencode-scheduler", // nightly jobs
+    ["opencode-notify", { "channel": "ops" }],
+    "@acme/notes@1.0.0" /* pinned for the demo */
+  ]
+}
+"#;
+        let mut doc = JsoncDocument::parse(config).expect("parse");
+        pin_plugin(&mut doc, "@acme/notes", Some("1.1.0")).expect("pin");
+        add_plugin(&mut doc, "opencode-git").expect("add");
+        remove_plugin(&mut doc, "opencode-scheduler").expect("remove");
+        assert_eq!(
+            doc.text(),
+            r#"{
+  "plugin": [
+    // team plugins
+    ["opencode-notify", { "channel": "ops" }],
+    "@acme/notes@1.1.0", /* pinned for the demo */
+    "opencode-git"
+  ]
+}
+"#
+        );
+    }
+}
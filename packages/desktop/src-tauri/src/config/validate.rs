@@ -0,0 +1,137 @@
+use std::sync::OnceLock;
+
+use jsonschema::Validator;
+use serde::Serialize;
+
+use crate::jsonc::JsoncDocument;
+
+/// Hand-maintained subset of the schema behind the `$schema` URL written into every config.
+/// It only checks the keys it lists, so settings added by newer OpenCode releases pass.
+const OPENCODE_CONFIG_SCHEMA: &str = include_str!("../../schemas/opencode-config.schema.json");
+
+/// One problem found in an OpenCode config, located for an editor.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiagnostic {
+    /// 1-based.
+    pub line: usize,
+    /// 1-based, in characters.
+    pub column: usize,
+    /// JSON pointer to the offending value; empty for syntax errors and the root.
+    pub pointer: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigValidation {
+    pub valid: bool,
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+fn schema_validator() -> Result<&'static Validator, String> {
+    static VALIDATOR: OnceLock<Result<Validator, String>> = OnceLock::new();
+    VALIDATOR
+        .get_or_init(|| {
+            let schema: serde_json::Value = serde_json::from_str(OPENCODE_CONFIG_SCHEMA)
+                .map_err(|e| format!("Failed to parse bundled config schema: {e}"))?;
+            jsonschema::validator_for(&schema)
+                .map_err(|e| format!("Failed to compile bundled config schema: {e}"))
+        })
+        .as_ref()
+        .map_err(|e| e.clone())
+}
+
+/// Parses `content` as JSONC and checks it against the bundled OpenCode schema.
+pub fn validate_opencode_config(content: &str) -> Result<ConfigValidation, String> {
+    let mut diagnostics = Vec::new();
+
+    match JsoncDocument::parse(content) {
+        Err(error) => diagnostics.push(ConfigDiagnostic {
+            line: error.line,
+            column: error.column,
+            pointer: String::new(),
+            message: error.message,
+        }),
+        Ok(doc) => {
+            let value = doc.value()?;
+            for error in schema_validator()?.iter_errors(&value) {
+                let pointer = error.instance_path().as_str().to_string();
+                let (line, column) = doc.locate(&pointer);
+                diagnostics.push(ConfigDiagnostic {
+                    line,
+                    column,
+                    pointer,
+                    message: error.to_string(),
+                });
+            }
+        }
+    }
+
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    Ok(ConfigValidation {
+        valid: diagnostics.is_empty(),
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_schema_errors_on_their_line() {
+        let config = r#"{
+  // team defaults
+  "$schema": "https://opencode.ai/config.json",
+  "share": "always",
+  "mcp": {
+    "docs": { "type": "local", "command": "npx docs-mcp" },
+  },
+}
+"#;
+        let report = validate_opencode_config(config).expect("validate");
+        assert!(!report.valid);
+        let located: Vec<(usize, &str)> = report
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.pointer.as_str()))
+            .collect();
+        assert!(located.contains(&(4, "/share")), "{located:?}");
+        assert!(located
+            .iter()
+            .any(|(line, pointer)| *line == 6 && pointer.starts_with("/mcp/docs")));
+    }
+
+    #[test]
+    fn reports_syntax_errors_at_the_offending_token() {
+        let broken = validate_opencode_config("{\n  \"model\": \"x\"\n  \"theme\": \"y\"\n}")
+            .expect("validate");
+        assert_eq!(
+            broken.diagnostics,
+            vec![ConfigDiagnostic {
+                line: 3,
+                column: 3,
+                pointer: String::new(),
+                message: "expected ','".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn accepts_plugin_options_and_keys_outside_the_subset() {
+        let valid = validate_opencode_config(
+            r#"{
+  "$schema": "https://opencode.ai/config.json",
+  "plugin": ["opencode-scheduler", ["opencode-notify", { "sound": true }]],
+  "compaction": { "auto": true },
+  "mcp": {
+    "chrome-devtools": { "type": "local", "command": ["npx", "-y", "chrome-devtools-mcp@latest"] },
+    "notion": { "type": "remote", "url": "https://mcp.notion.com/mcp", "enabled": true }
+  }
+}"#,
+        )
+        .expect("validate");
+        assert!(valid.valid, "{:?}", valid.diagnostics);
+    }
+}
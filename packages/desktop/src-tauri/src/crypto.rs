@@ -0,0 +1,189 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+/// Prefix of every passphrase-encrypted blob.
+const MAGIC: &[u8; 8] = b"OWCRYPT1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+/// magic + m_cost + t_cost + p_cost + salt + nonce
+const HEADER_LEN: usize = MAGIC.len() + 12 + SALT_LEN + NONCE_LEN;
+
+// Argon2id parameters recommended by OWASP for interactive use (19 MiB, 2 passes).
+const DEFAULT_M_COST: u32 = 19 * 1024;
+const DEFAULT_T_COST: u32 = 2;
+const DEFAULT_P_COST: u32 = 1;
+/// The header is read before anything is authenticated, so a crafted archive could otherwise
+/// make us allocate and hash for as long as it likes. Stay close to what we write.
+const MAX_M_COST: u32 = 256 * 1024;
+const MAX_T_COST: u32 = 4;
+const MAX_P_COST: u32 = 4;
+
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: (u32, u32, u32)) -> Result<[u8; 32], String> {
+    let (m_cost, t_cost, p_cost) = params;
+    if m_cost > MAX_M_COST || t_cost > MAX_T_COST || p_cost > MAX_P_COST {
+        return Err("Encrypted data uses unsupported key derivation parameters".to_string());
+    }
+    let params = Params::new(m_cost, t_cost, p_cost, Some(32))
+        .map_err(|e| format!("Invalid key derivation parameters: {e}"))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive key: {e}"))?;
+    Ok(key)
+}
+
+/// Encrypts `plaintext` with a key derived from `passphrase` (Argon2id + XChaCha20-Poly1305).
+/// The header, including the KDF parameters, is authenticated as associated data.
+pub fn encrypt_with_passphrase(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    if passphrase.is_empty() {
+        return Err("Passphrase must not be empty".to_string());
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&DEFAULT_M_COST.to_le_bytes());
+    header.extend_from_slice(&DEFAULT_T_COST.to_le_bytes());
+    header.extend_from_slice(&DEFAULT_P_COST.to_le_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce);
+
+    let key = derive_key(
+        passphrase,
+        &salt,
+        (DEFAULT_M_COST, DEFAULT_T_COST, DEFAULT_P_COST),
+    )?;
+    let cipher = XChaCha20Poly1305::new(&key.into());
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: &header,
+            },
+        )
+        .map_err(|_| "Failed to encrypt data".to_string())?;
+
+    header.extend_from_slice(&ciphertext);
+    Ok(header)
+}
+
+pub fn decrypt_with_passphrase(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    if !is_encrypted(data) || data.len() < HEADER_LEN {
+        return Err("Data is not an OpenWork encrypted blob".to_string());
+    }
+
+    let (header, ciphertext) = data.split_at(HEADER_LEN);
+    let read_u32 = |offset: usize| {
+        let mut raw = [0u8; 4];
+        raw.copy_from_slice(&header[offset..offset + 4]);
+        u32::from_le_bytes(raw)
+    };
+    let params_at = MAGIC.len();
+    let params = (
+        read_u32(params_at),
+        read_u32(params_at + 4),
+        read_u32(params_at + 8),
+    );
+    let salt_at = params_at + 12;
+    let salt = &header[salt_at..salt_at + SALT_LEN];
+    let nonce = XNonce::from_slice(&header[salt_at + SALT_LEN..]);
+
+    let key = derive_key(passphrase, salt, params)?;
+    XChaCha20Poly1305::new(&key.into())
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| "Wrong passphrase or corrupted data".to_string())
+}
+
+/// Prefix of every blob sealed with a raw 32-byte key.
+const KEYED_MAGIC: &[u8; 8] = b"OWKEYED1";
+const KEYED_HEADER_LEN: usize = KEYED_MAGIC.len() + NONCE_LEN;
+
+pub fn generate_key() -> [u8; 32] {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    key
+}
+
+/// Encrypts `plaintext` with a raw key (XChaCha20-Poly1305), for data whose key is stored
+/// next to it rather than derived from a passphrase.
+pub fn encrypt_with_key(plaintext: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, String> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let mut header = Vec::with_capacity(KEYED_HEADER_LEN);
+    header.extend_from_slice(KEYED_MAGIC);
+    header.extend_from_slice(&nonce);
+
+    let ciphertext = XChaCha20Poly1305::new(key.into())
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: &header,
+            },
+        )
+        .map_err(|_| "Failed to encrypt data".to_string())?;
+    header.extend_from_slice(&ciphertext);
+    Ok(header)
+}
+
+pub fn decrypt_with_key(data: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, String> {
+    if !data.starts_with(KEYED_MAGIC) || data.len() < KEYED_HEADER_LEN {
+        return Err("Data is not an OpenWork key-encrypted blob".to_string());
+    }
+    let (header, ciphertext) = data.split_at(KEYED_HEADER_LEN);
+    let nonce = XNonce::from_slice(&header[KEYED_MAGIC.len()..]);
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| "Wrong key or corrupted data".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_rejects_wrong_passphrase_or_tampering() {
+        let sealed = encrypt_with_passphrase(b"workspace archive", "correct horse").expect("seal");
+        assert!(is_encrypted(&sealed));
+        assert_eq!(
+            decrypt_with_passphrase(&sealed, "correct horse").expect("open"),
+            b"workspace archive"
+        );
+        assert!(decrypt_with_passphrase(&sealed, "battery staple").is_err());
+
+        let mut tampered = sealed.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(decrypt_with_passphrase(&tampered, "correct horse").is_err());
+
+        let mut expensive = sealed.clone();
+        expensive[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&(1024 * 1024u32).to_le_bytes());
+        let started = std::time::Instant::now();
+        assert_eq!(
+            decrypt_with_passphrase(&expensive, "correct horse").unwrap_err(),
+            "Encrypted data uses unsupported key derivation parameters"
+        );
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    }
+}
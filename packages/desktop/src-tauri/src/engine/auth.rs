@@ -0,0 +1,434 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config::effective::resolve_effective_config;
+use crate::engine::profiles::{workspace_config_inputs, workspace_profile_dirs};
+use crate::paths::opencode_auth_path;
+use crate::utils::now_ms;
+use crate::workspace::env::load_workspace_env;
+
+/// Variables OpenCode reads for its built-in providers.
+const PROVIDER_ENV: &[(&str, &[&str])] = &[
+    ("anthropic", &["ANTHROPIC_API_KEY"]),
+    ("openai", &["OPENAI_API_KEY"]),
+    (
+        "google",
+        &["GOOGLE_GENERATIVE_AI_API_KEY", "GEMINI_API_KEY"],
+    ),
+    ("openrouter", &["OPENROUTER_API_KEY"]),
+    ("groq", &["GROQ_API_KEY"]),
+    ("mistral", &["MISTRAL_API_KEY"]),
+    ("xai", &["XAI_API_KEY"]),
+    ("deepseek", &["DEEPSEEK_API_KEY"]),
+    ("fireworks-ai", &["FIREWORKS_API_KEY"]),
+    ("togetherai", &["TOGETHER_AI_API_KEY"]),
+    ("cerebras", &["CEREBRAS_API_KEY"]),
+    ("azure", &["AZURE_API_KEY"]),
+    (
+        "amazon-bedrock",
+        &[
+            "AWS_BEARER_TOKEN_BEDROCK",
+            "AWS_ACCESS_KEY_ID",
+            "AWS_PROFILE",
+        ],
+    ),
+    ("opencode", &["OPENCODE_API_KEY"]),
+];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CredentialKind {
+    ApiKey,
+    Oauth,
+    /// A token fetched from a `/.well-known/opencode` endpoint.
+    WellKnown,
+}
+
+/// One place a provider's credentials come from. Never carries the secret.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCredential {
+    pub kind: CredentialKind,
+    /// `auth.json`, `env`, `workspace env (<source>)` or `config`.
+    pub source: String,
+    /// File path or variable name.
+    pub location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expired: Option<bool>,
+    /// Whether an expired OAuth token has a refresh token OpenCode can use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refreshable: Option<bool>,
+}
+
+impl ProviderCredential {
+    fn usable(&self) -> bool {
+        self.expired != Some(true) || self.refreshable == Some(true)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderAuthStatus {
+    pub provider: String,
+    /// At least one credential is present and not hopelessly expired.
+    pub configured: bool,
+    /// The provider of `model` or `small_model`.
+    pub in_use: bool,
+    pub credentials: Vec<ProviderCredential>,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderAuthReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_error: Option<String>,
+    pub providers: Vec<ProviderAuthStatus>,
+}
+
+fn status_for<'a>(
+    providers: &'a mut BTreeMap<String, ProviderAuthStatus>,
+    id: &str,
+) -> &'a mut ProviderAuthStatus {
+    providers
+        .entry(id.to_string())
+        .or_insert_with(|| ProviderAuthStatus {
+            provider: id.to_string(),
+            configured: false,
+            in_use: false,
+            credentials: Vec::new(),
+            notes: Vec::new(),
+        })
+}
+
+fn non_empty_str<'a>(entry: &'a Value, key: &str) -> Option<&'a str> {
+    entry
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.trim().is_empty())
+}
+
+fn auth_json_credential(entry: &Value, path: &str, now: u64) -> Result<ProviderCredential, String> {
+    let mut credential = ProviderCredential {
+        kind: CredentialKind::ApiKey,
+        source: "auth.json".to_string(),
+        location: path.to_string(),
+        expires_at: None,
+        expired: None,
+        refreshable: None,
+    };
+    match entry.get("type").and_then(Value::as_str) {
+        Some("api") if non_empty_str(entry, "key").is_some() => {}
+        Some("oauth") => {
+            credential.kind = CredentialKind::Oauth;
+            let expires = entry.get("expires").and_then(Value::as_u64);
+            credential.expires_at = expires.filter(|expires| *expires > 0);
+            credential.expired = Some(expires.is_some_and(|expires| expires <= now));
+            credential.refreshable = Some(non_empty_str(entry, "refresh").is_some());
+            if non_empty_str(entry, "access").is_none() && credential.refreshable != Some(true) {
+                return Err("OAuth entry has neither an access nor a refresh token".to_string());
+            }
+        }
+        Some("wellknown") if non_empty_str(entry, "token").is_some() => {
+            credential.kind = CredentialKind::WellKnown;
+        }
+        Some(kind) => return Err(format!("Incomplete `{kind}` entry")),
+        None => return Err("Entry has no `type`".to_string()),
+    }
+    Ok(credential)
+}
+
+fn env_reference(value: &str) -> Option<&str> {
+    value.trim().strip_prefix("{env:")?.strip_suffix('}')
+}
+
+/// Which providers have credentials, from `auth.json`, provider env vars and `provider`
+/// entries in `config`. `env` names where a variable is set (`env`, `workspace env ...`)
+/// or `None` when it isn't.
+pub fn provider_auth_report(
+    auth_path: Option<&Path>,
+    config: &Value,
+    provenance: &BTreeMap<String, String>,
+    env: &dyn Fn(&str) -> Option<String>,
+    now: u64,
+) -> ProviderAuthReport {
+    let mut report = ProviderAuthReport {
+        auth_path: auth_path.map(|path| path.to_string_lossy().to_string()),
+        ..ProviderAuthReport::default()
+    };
+    let mut providers: BTreeMap<String, ProviderAuthStatus> = BTreeMap::new();
+
+    if let Some(path) = auth_path.filter(|path| path.exists()) {
+        let location = path.to_string_lossy().to_string();
+        let parsed = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))
+            .and_then(|raw| {
+                serde_json::from_str::<Value>(&raw)
+                    .map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+            });
+        match parsed {
+            Ok(Value::Object(entries)) => {
+                for (id, entry) in &entries {
+                    let status = status_for(&mut providers, id);
+                    match auth_json_credential(entry, &location, now) {
+                        Ok(credential) => status.credentials.push(credential),
+                        Err(note) => status.notes.push(format!("auth.json: {note}")),
+                    }
+                }
+            }
+            Ok(_) => report.auth_error = Some(format!("{} is not an object", path.display())),
+            Err(error) => report.auth_error = Some(error),
+        }
+    }
+
+    let configured = config.get("provider").and_then(Value::as_object);
+    let mut env_vars: Vec<(String, Vec<String>)> = PROVIDER_ENV
+        .iter()
+        .map(|(id, vars)| (id.to_string(), vars.iter().map(|v| v.to_string()).collect()))
+        .collect();
+    for (id, entry) in configured.into_iter().flatten() {
+        let declared: Vec<String> = entry
+            .get("env")
+            .and_then(Value::as_array)
+            .map(|vars| {
+                vars.iter()
+                    .filter_map(|var| var.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !declared.is_empty() {
+            env_vars.push((id.clone(), declared));
+        }
+    }
+    for (id, vars) in env_vars {
+        for var in vars {
+            if let Some(source) = env(&var) {
+                status_for(&mut providers, &id)
+                    .credentials
+                    .push(ProviderCredential {
+                        kind: CredentialKind::ApiKey,
+                        source,
+                        location: var,
+                        expires_at: None,
+                        expired: None,
+                        refreshable: None,
+                    });
+            }
+        }
+    }
+
+    for (id, entry) in configured.into_iter().flatten() {
+        let status = status_for(&mut providers, id);
+        let Some(api_key) = entry.pointer("/options/apiKey").and_then(Value::as_str) else {
+            continue;
+        };
+        let origin = provenance
+            .get(&format!("/provider/{id}/options/apiKey"))
+            .cloned()
+            .unwrap_or_else(|| "config".to_string());
+        match env_reference(api_key) {
+            Some(var) if env(var).is_none() => {
+                status
+                    .notes
+                    .push(format!("options.apiKey reads {var}, which isn't set"));
+            }
+            Some(var) => status.credentials.push(ProviderCredential {
+                kind: CredentialKind::ApiKey,
+                source: "config".to_string(),
+                location: format!("{origin} ({{env:{var}}})"),
+                expires_at: None,
+                expired: None,
+                refreshable: None,
+            }),
+            None if api_key.trim().is_empty() => {}
+            None => status.credentials.push(ProviderCredential {
+                kind: CredentialKind::ApiKey,
+                source: "config".to_string(),
+                location: origin,
+                expires_at: None,
+                expired: None,
+                refreshable: None,
+            }),
+        }
+    }
+
+    for key in ["model", "small_model"] {
+        let Some(provider) = config
+            .get(key)
+            .and_then(Value::as_str)
+            .and_then(|model| model.split_once('/'))
+            .map(|(provider, _)| provider)
+        else {
+            continue;
+        };
+        status_for(&mut providers, provider).in_use = true;
+    }
+
+    for status in providers.values_mut() {
+        status.configured = status.credentials.iter().any(ProviderCredential::usable);
+        if status.credentials.iter().any(|c| c.expired == Some(true)) {
+            status.notes.push(if status.configured {
+                "An OAuth token expired; OpenCode refreshes it on next use.".to_string()
+            } else {
+                "OAuth login expired; sign in again.".to_string()
+            });
+        }
+        if status.in_use && !status.configured {
+            status
+                .notes
+                .push("Selected as the default model but has no usable credentials.".to_string());
+        }
+    }
+
+    report.providers = providers.into_values().collect();
+    report
+}
+
+/// The report for what an engine started in `project_dir` would see, including variables the
+/// workspace injects and an `XDG_DATA_HOME` it overrides.
+pub fn app_provider_auth_report(
+    app: &tauri::AppHandle,
+    project_dir: Option<&Path>,
+) -> ProviderAuthReport {
+    let workspace: HashMap<String, (String, String)> = project_dir
+        .and_then(|dir| load_workspace_env(app, &dir.to_string_lossy()).ok())
+        .map(|resolved| {
+            resolved
+                .report
+                .vars
+                .iter()
+                .filter_map(|var| {
+                    let value = resolved.vars.get(&var.name)?.clone();
+                    let source = format!("workspace env ({})", var.source.label());
+                    Some((var.name.clone(), (value, source)))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let lookup = |name: &str| -> Option<String> {
+        if let Some((value, source)) = workspace.get(name) {
+            return (!value.trim().is_empty()).then(|| source.clone());
+        }
+        std::env::var(name)
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .map(|_| "env".to_string())
+    };
+
+    let profile = project_dir.and_then(|dir| workspace_profile_dirs(app, &dir.to_string_lossy()));
+    let auth_path = match (workspace.get("XDG_DATA_HOME"), &profile) {
+        (Some((home, _)), _) => Some(PathBuf::from(home).join("opencode").join("auth.json")),
+        (None, Some(profile)) => Some(profile.auth_path()),
+        (None, None) => opencode_auth_path(),
+    };
+    let effective =
+        resolve_effective_config(&workspace_config_inputs(app, project_dir), project_dir);
+    provider_auth_report(
+        auth_path.as_deref(),
+        &effective.config,
+        &effective.provenance,
+        &lookup,
+        now_ms(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::TempDir;
+
+    const AUTH_JSON: &str = r#"{
+  "anthropic": { "type": "api", "key": "sk-ant-secret" },
+  "openai": { "type": "oauth", "access": "a", "refresh": "r", "expires": 1000 },
+  "github-copilot": { "type": "oauth", "access": "a", "refresh": "", "expires": 1000 },
+  "broken": { "type": "api" }
+}"#;
+
+    fn report() -> ProviderAuthReport {
+        let dir = TempDir::new("auth");
+        let auth_path = dir.join("auth.json");
+        fs::write(&auth_path, AUTH_JSON).expect("auth.json");
+
+        let config = serde_json::json!({
+            "model": "groq/llama",
+            "provider": {
+                "local": { "options": { "apiKey": "{env:LOCAL_KEY}" } },
+                "corp": { "env": ["CORP_TOKEN"], "options": { "apiKey": "{env:MISSING_KEY}" } }
+            }
+        });
+        let provenance = BTreeMap::from([(
+            "/provider/local/options/apiKey".to_string(),
+            "/ws/opencode.jsonc".to_string(),
+        )]);
+        let env = |name: &str| match name {
+            "OPENAI_API_KEY" => Some("env".to_string()),
+            "LOCAL_KEY" | "CORP_TOKEN" => Some("workspace env (vault)".to_string()),
+            _ => None,
+        };
+        provider_auth_report(Some(&auth_path), &config, &provenance, &env, 5000)
+    }
+
+    fn status<'a>(report: &'a ProviderAuthReport, provider: &str) -> &'a ProviderAuthStatus {
+        report
+            .providers
+            .iter()
+            .find(|status| status.provider == provider)
+            .unwrap_or_else(|| panic!("no status for {provider}"))
+    }
+
+    #[test]
+    fn reads_api_keys_and_oauth_tokens_from_auth_json() {
+        let report = report();
+        let anthropic = status(&report, "anthropic");
+        assert!(anthropic.configured);
+        assert_eq!(anthropic.credentials[0].source, "auth.json");
+
+        let openai = status(&report, "openai");
+        assert!(openai.configured);
+        assert_eq!(openai.credentials.len(), 2);
+        assert_eq!(openai.credentials[0].expired, Some(true));
+        assert_eq!(openai.credentials[0].refreshable, Some(true));
+    }
+
+    #[test]
+    fn flags_expired_tokens_without_refresh_and_broken_entries() {
+        let report = report();
+        assert!(!status(&report, "github-copilot").configured);
+        let broken = status(&report, "broken");
+        assert!(!broken.configured && !broken.notes.is_empty());
+    }
+
+    #[test]
+    fn locates_env_references_in_the_config() {
+        let report = report();
+        assert_eq!(
+            status(&report, "local").credentials[0].location,
+            "/ws/opencode.jsonc ({env:LOCAL_KEY})"
+        );
+        let corp = status(&report, "corp");
+        assert!(corp.configured);
+        assert!(corp.notes.iter().any(|note| note.contains("MISSING_KEY")));
+    }
+
+    #[test]
+    fn marks_the_model_provider_in_use_even_without_credentials() {
+        let report = report();
+        let groq = status(&report, "groq");
+        assert!(groq.in_use && !groq.configured);
+    }
+
+    #[test]
+    fn never_serializes_secret_values() {
+        let serialized = serde_json::to_string(&report()).expect("serialize");
+        assert!(!serialized.contains("sk-ant-secret"));
+    }
+}
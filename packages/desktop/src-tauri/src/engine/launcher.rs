@@ -0,0 +1,351 @@
+use std::path::Path;
+
+use crate::settings::load_app_settings;
+use crate::types::{AppSettings, EngineLauncher};
+use crate::workspace::state::load_workspace_state;
+
+const ARGS_PLACEHOLDER: &str = "{args}";
+const OPENCODE_PLACEHOLDER: &str = "{opencode}";
+const HOSTNAME_PLACEHOLDER: &str = "{hostname}";
+const PORT_PLACEHOLDER: &str = "{port}";
+const PROJECT_DIR_PLACEHOLDER: &str = "{projectDir}";
+const PLACEHOLDERS: [&str; 5] = [
+    ARGS_PLACEHOLDER,
+    OPENCODE_PLACEHOLDER,
+    HOSTNAME_PLACEHOLDER,
+    PORT_PLACEHOLDER,
+    PROJECT_DIR_PLACEHOLDER,
+];
+
+/// What a launcher template is expanded with.
+pub struct LaunchContext<'a> {
+    /// The OpenCode binary OpenWork resolved, if any.
+    pub opencode: Option<&'a Path>,
+    pub hostname: &'a str,
+    pub port: u16,
+    pub project_dir: &'a str,
+    /// `serve` and its flags.
+    pub args: &'a [String],
+}
+
+/// `{name}` tokens in `value`. Braces around anything else (JSON, shell snippets) are left
+/// alone.
+fn placeholder_tokens(value: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let token = &rest[start..=start + len];
+        if token.len() > 2
+            && token[1..token.len() - 1]
+                .chars()
+                .all(|c| c.is_ascii_alphabetic())
+        {
+            tokens.push(token);
+        }
+        rest = &rest[start + 1..];
+    }
+    tokens
+}
+
+pub fn validate_launcher(launcher: &EngineLauncher) -> Result<(), String> {
+    let id = launcher.id.as_str();
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid launcher id {id:?}: use lowercase letters, digits, '-' and '_'"
+        ));
+    }
+    if launcher.name.trim().is_empty() {
+        return Err(format!("Launcher {id} needs a name"));
+    }
+    let Some(program) = launcher.command.first() else {
+        return Err(format!("Launcher {id} has no command"));
+    };
+    if program.trim().is_empty() || program == ARGS_PLACEHOLDER {
+        return Err(format!("Launcher {id} must start with a program"));
+    }
+    for arg in &launcher.command {
+        for token in placeholder_tokens(arg) {
+            if !PLACEHOLDERS.contains(&token) {
+                return Err(format!(
+                    "Launcher {id} uses unknown placeholder {token}; use {}",
+                    PLACEHOLDERS.join(", ")
+                ));
+            }
+            if token == ARGS_PLACEHOLDER && arg != ARGS_PLACEHOLDER {
+                return Err(format!(
+                    "Launcher {id}: {ARGS_PLACEHOLDER} must be an argument of its own"
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether a shell would read `value` literally: no spaces, quotes, `$`, `;` or globs.
+/// Backslashes are allowed on Windows, where every path has them.
+fn is_shell_safe(value: &str) -> bool {
+    !value.is_empty()
+        && value.chars().all(|c| {
+            c.is_ascii_alphanumeric() || "_./:@%+=,-".contains(c) || (cfg!(windows) && c == '\\')
+        })
+}
+
+/// Whether `command[index]` reaches a shell: `ssh` joins its remote command into one string
+/// for the remote shell, and the argument after `-c` is a script.
+fn reaches_a_shell(command: &[String], index: usize) -> bool {
+    let program = Path::new(&command[0])
+        .file_stem()
+        .and_then(|stem| stem.to_str());
+    program == Some("ssh") || (index > 0 && command[index - 1] == "-c")
+}
+
+/// Whether the launcher runs the OpenCode binary OpenWork found, rather than one it names.
+pub fn uses_local_opencode(launcher: &EngineLauncher) -> bool {
+    launcher
+        .command
+        .iter()
+        .any(|arg| arg.contains(OPENCODE_PLACEHOLDER))
+}
+
+/// The program and arguments to spawn. Paths and the hostname are substituted verbatim, so a
+/// value a shell would reinterpret is refused wherever a shell could see it: inside a larger
+/// argument, after `-c`, or anywhere in an `ssh` command.
+pub fn expand_launcher(
+    launcher: &EngineLauncher,
+    context: &LaunchContext,
+) -> Result<(String, Vec<String>), String> {
+    validate_launcher(launcher)?;
+    let opencode = context
+        .opencode
+        .map(|path| path.to_string_lossy().to_string());
+    if uses_local_opencode(launcher) && opencode.is_none() {
+        return Err(format!(
+            "Launcher {} needs the OpenCode CLI, which wasn't found",
+            launcher.name
+        ));
+    }
+
+    let port = context.port.to_string();
+    let values = [
+        (
+            OPENCODE_PLACEHOLDER,
+            opencode.as_deref().unwrap_or_default(),
+        ),
+        (HOSTNAME_PLACEHOLDER, context.hostname),
+        (PORT_PLACEHOLDER, port.as_str()),
+        (PROJECT_DIR_PLACEHOLDER, context.project_dir),
+    ];
+    let mut expanded = Vec::new();
+    for (index, arg) in launcher.command.iter().enumerate() {
+        if arg == ARGS_PLACEHOLDER {
+            expanded.extend(context.args.iter().cloned());
+            continue;
+        }
+        let mut value = arg.clone();
+        for (placeholder, substitute) in values {
+            if !arg.contains(placeholder) {
+                continue;
+            }
+            if (arg != placeholder || reaches_a_shell(&launcher.command, index))
+                && !is_shell_safe(substitute)
+            {
+                return Err(format!(
+                    "Launcher {} can't pass {substitute:?} as {placeholder}: a shell would \
+                     reinterpret it in {arg:?}",
+                    launcher.name
+                ));
+            }
+            value = value.replace(placeholder, substitute);
+        }
+        expanded.push(value);
+    }
+    if !launcher.command.iter().any(|arg| arg == ARGS_PLACEHOLDER) {
+        expanded.extend(context.args.iter().cloned());
+    }
+
+    let program = expanded.remove(0);
+    Ok((program, expanded))
+}
+
+/// Trims and validates launchers before they're saved.
+pub fn normalize_launchers(settings: &mut AppSettings) -> Result<(), String> {
+    let mut seen = std::collections::HashSet::new();
+    for launcher in &mut settings.engine_launchers {
+        launcher.id = launcher.id.trim().to_string();
+        launcher.name = launcher.name.trim().to_string();
+        validate_launcher(launcher)?;
+        if !seen.insert(launcher.id.clone()) {
+            return Err(format!("Duplicate launcher id: {}", launcher.id));
+        }
+    }
+    Ok(())
+}
+
+/// The launcher the workspace at `project_dir` selected. A selection that no longer exists
+/// is an error rather than a silent fallback to running OpenCode unwrapped.
+pub fn workspace_launcher(
+    app: &tauri::AppHandle,
+    project_dir: &str,
+) -> Result<Option<EngineLauncher>, String> {
+    let project_dir = project_dir.trim();
+    let Some(id) = load_workspace_state(app)?
+        .workspaces
+        .into_iter()
+        .find(|workspace| workspace.path == project_dir)
+        .and_then(|workspace| workspace.engine_launcher)
+    else {
+        return Ok(None);
+    };
+    load_app_settings(app)?
+        .engine_launchers
+        .into_iter()
+        .find(|launcher| launcher.id == id)
+        .map(Some)
+        .ok_or_else(|| format!("Unknown engine launcher: {id}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn launcher(command: &[&str]) -> EngineLauncher {
+        EngineLauncher {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            command: command.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    fn serve_args() -> Vec<String> {
+        vec![
+            "serve".to_string(),
+            "--port".to_string(),
+            "4096".to_string(),
+        ]
+    }
+
+    fn context<'a>(args: &'a [String], project_dir: &'a str) -> LaunchContext<'a> {
+        LaunchContext {
+            opencode: Some(Path::new("/opt/opencode")),
+            hostname: "0.0.0.0",
+            port: 4096,
+            project_dir,
+            args,
+        }
+    }
+
+    #[test]
+    fn expands_placeholders_where_args_says() {
+        let args = serve_args();
+        let podman = launcher(&[
+            "podman",
+            "run",
+            "-p",
+            "{port}:{port}",
+            "-v",
+            "{projectDir}:{projectDir}",
+            "img",
+            "opencode",
+            "{args}",
+            "--print-logs",
+            "HOST={hostname}",
+        ]);
+        let (program, expanded) =
+            expand_launcher(&podman, &context(&args, "/work/site")).expect("expand");
+        assert_eq!(program, "podman");
+        assert_eq!(
+            expanded,
+            [
+                "run",
+                "-p",
+                "4096:4096",
+                "-v",
+                "/work/site:/work/site",
+                "img",
+                "opencode",
+                "serve",
+                "--port",
+                "4096",
+                "--print-logs",
+                "HOST=0.0.0.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn appends_args_and_fills_in_the_local_opencode() {
+        let args = serve_args();
+        let (program, expanded) = expand_launcher(
+            &launcher(&["nice", "-n", "10", "{opencode}"]),
+            &context(&args, "/work/site"),
+        )
+        .expect("expand");
+        assert_eq!(program, "nice");
+        assert_eq!(
+            expanded,
+            ["-n", "10", "/opt/opencode", "serve", "--port", "4096"]
+        );
+
+        let missing = LaunchContext {
+            opencode: None,
+            ..context(&args, "/work/site")
+        };
+        assert!(expand_launcher(&launcher(&["{opencode}"]), &missing).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders_and_embedded_args() {
+        assert!(validate_launcher(&launcher(&["ssh", "{host}", "opencode"])).is_err());
+        assert!(validate_launcher(&launcher(&["sh", "-c", "x {args}"])).is_err());
+        assert!(validate_launcher(&launcher(&["sh", "-c", "echo '{\"a\":1}'"])).is_ok());
+    }
+
+    #[test]
+    fn refuses_hostile_project_dirs_where_a_shell_would_see_them() {
+        let args = serve_args();
+        for project_dir in ["/tmp/a; rm -rf ~", "/tmp/$(touch pwned)", "/tmp/my site"] {
+            let context = context(&args, project_dir);
+            for command in [
+                &["sh", "-c", "cd {projectDir} && opencode serve"][..],
+                &["sh", "-c", "{projectDir}"][..],
+                &["ssh", "build-box", "opencode", "--cwd", "{projectDir}"][..],
+                &["podman", "run", "-v", "{projectDir}:/work", "img"][..],
+            ] {
+                let error =
+                    expand_launcher(&launcher(command), &context).expect_err("hostile project dir");
+                assert!(error.contains("{projectDir}"), "{error}");
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn passes_a_whole_argument_placeholder_through_verbatim() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = crate::fs::TempDir::new("launcher");
+        let script = dir.join("wrap.sh");
+        std::fs::write(&script, "#!/bin/sh\nprintf '%s|' \"$@\"\n").expect("script");
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).expect("chmod");
+
+        let args = serve_args();
+        let wrapper = launcher(&[&script.to_string_lossy(), "--cwd", "{projectDir}"]);
+        let (program, expanded) =
+            expand_launcher(&wrapper, &context(&args, "/tmp/a; rm -rf ~")).expect("expand");
+        let output = std::process::Command::new(program)
+            .args(expanded)
+            .output()
+            .expect("run wrapper");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "--cwd|/tmp/a; rm -rf ~|serve|--port|4096|"
+        );
+    }
+}
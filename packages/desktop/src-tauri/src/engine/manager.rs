@@ -2,6 +2,7 @@ use std::sync::{Arc, Mutex};
 
 use tauri_plugin_shell::process::CommandChild;
 
+use crate::limits::ProcessLimits;
 use crate::types::EngineInfo;
 
 #[derive(Default)]
@@ -16,6 +17,13 @@ pub struct EngineState {
     pub project_dir: Option<String>,
     pub hostname: Option<String>,
     pub port: Option<u16>,
+    pub prefer_sidecar: bool,
+    pub launcher: Option<String>,
+    pub sandboxed: bool,
+    /// Shared with the output reader, which records denials as they're printed.
+    pub sandbox_denials: Arc<Mutex<Vec<String>>>,
+    pub limits: Option<ProcessLimits>,
+    pub exit_reason: Option<String>,
     pub base_url: Option<String>,
     pub last_stdout: Option<String>,
     pub last_stderr: Option<String>,
@@ -39,6 +47,15 @@ impl EngineManager {
             hostname: state.hostname.clone(),
             port: state.port,
             pid,
+            launcher: state.launcher.clone(),
+            sandboxed: state.sandboxed,
+            sandbox_denials: state
+                .sandbox_denials
+                .lock()
+                .map(|denials| denials.clone())
+                .unwrap_or_default(),
+            limits: state.limits.clone(),
+            exit_reason: state.exit_reason.clone(),
             last_stdout: state.last_stdout.clone(),
             last_stderr: state.last_stderr.clone(),
         }
@@ -53,6 +70,11 @@ impl EngineManager {
         state.project_dir = None;
         state.hostname = None;
         state.port = None;
+        state.launcher = None;
+        state.sandboxed = false;
+        state.sandbox_denials = Arc::default();
+        state.limits = None;
+        state.exit_reason = None;
         state.last_stdout = None;
         state.last_stderr = None;
     }
@@ -1,4 +1,10 @@
+pub mod auth;
 pub mod doctor;
+pub mod launcher;
 pub mod manager;
+pub mod node_runtime;
 pub mod paths;
+pub mod preflight;
+pub mod profiles;
+pub mod sandbox;
 pub mod spawn;
@@ -0,0 +1,685 @@
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use zip::ZipArchive;
+
+use crate::engine::preflight::runtime_version;
+use crate::paths::resolve_command;
+use crate::settings::load_app_settings;
+use crate::types::NodeRuntimeSettings;
+use crate::workspace::enterprise::{download_archive, ArchiveOrigin};
+use crate::workspace::seed::sha256_hex;
+
+pub const NODE_RUNTIME_EVENT: &str = "openwork://node-runtime-progress";
+const INSTALL_MARKER: &str = "openwork-runtime.json";
+const SHASUMS_FILE: &str = "SHASUMS256.txt";
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Written next to an installed runtime so later runs know where it came from.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeRuntimeInstall {
+    pub version: String,
+    pub archive: String,
+    pub sha256: String,
+    pub origin: ArchiveOrigin,
+    pub installed_at_ms: u64,
+}
+
+/// Which Node MCP servers and plugins will run with.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeRuntimeStatus {
+    /// `managed`, `system` or `missing`.
+    pub active: String,
+    pub enabled: bool,
+    pub configured_version: String,
+    pub managed: Option<NodeRuntimeInstall>,
+    pub managed_bin_dir: Option<String>,
+    pub system_path: Option<String>,
+    pub system_version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeRuntimeProgress {
+    pub phase: String,
+    pub origin: Option<ArchiveOrigin>,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl NodeRuntimeProgress {
+    pub fn new(phase: &str) -> Self {
+        Self {
+            phase: phase.to_string(),
+            origin: None,
+            downloaded_bytes: 0,
+            total_bytes: None,
+            error: None,
+        }
+    }
+}
+
+/// Which runtime to download from the mirror and where to install it.
+#[derive(Debug, Clone)]
+pub struct NodeInstallRequest {
+    pub version: String,
+    pub mirror: String,
+    pub sha256: Option<String>,
+    pub root: PathBuf,
+    pub os: String,
+    pub arch: String,
+    pub timeout: Duration,
+}
+
+/// Node's names for the current OS and CPU, as used in release archive names.
+pub fn node_platform() -> Option<(&'static str, &'static str)> {
+    let os = if cfg!(target_os = "macos") {
+        "darwin"
+    } else if cfg!(target_os = "linux") {
+        "linux"
+    } else if cfg!(windows) {
+        "win"
+    } else {
+        return None;
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        _ => return None,
+    };
+    Some((os, arch))
+}
+
+fn normalize_version(version: &str) -> &str {
+    version.trim().trim_start_matches('v')
+}
+
+pub fn node_archive_name(version: &str, os: &str, arch: &str) -> String {
+    let ext = if os == "win" { "zip" } else { "tar.gz" };
+    format!("node-v{}-{os}-{arch}.{ext}", normalize_version(version))
+}
+
+/// Looks up `file` in a `SHASUMS256.txt` listing.
+pub fn parse_shasums(listing: &str, file: &str) -> Option<String> {
+    listing.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let sha = parts.next()?;
+        let name = parts.next()?.trim_start_matches('*');
+        (name == file).then(|| sha.to_lowercase())
+    })
+}
+
+pub fn install_dir(root: &Path, version: &str) -> PathBuf {
+    root.join(format!("v{}", normalize_version(version)))
+}
+
+pub fn bin_dir(install_dir: &Path) -> PathBuf {
+    if cfg!(windows) {
+        install_dir.to_path_buf()
+    } else {
+        install_dir.join("bin")
+    }
+}
+
+fn node_executable(install_dir: &Path) -> PathBuf {
+    bin_dir(install_dir).join(if cfg!(windows) { "node.exe" } else { "node" })
+}
+
+/// The installed runtime for `version`, if its marker and executable are both present.
+pub fn read_install(root: &Path, version: &str) -> Option<NodeRuntimeInstall> {
+    let dir = install_dir(root, version);
+    if !node_executable(&dir).is_file() {
+        return None;
+    }
+    let raw = fs::read_to_string(dir.join(INSTALL_MARKER)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+/// Path of an archive entry below its top-level `node-v…/` directory. `None` for the top
+/// directory itself.
+fn strip_top_dir(name: &Path) -> Result<Option<PathBuf>, String> {
+    let mut components = name.components();
+    if !matches!(components.next(), Some(Component::Normal(_))) {
+        return Err(format!("Unsafe path in Node archive: {}", name.display()));
+    }
+    let mut relative = PathBuf::new();
+    for component in components {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => return Err(format!("Unsafe path in Node archive: {}", name.display())),
+        }
+    }
+    Ok((!relative.as_os_str().is_empty()).then_some(relative))
+}
+
+/// Whether a symlink at `link` (relative to the install dir) pointing at `target` stays inside.
+fn link_stays_inside(link: &Path, target: &Path) -> bool {
+    if target.is_absolute() {
+        return false;
+    }
+    let mut depth = link.components().count().saturating_sub(1);
+    for component in target.components() {
+        match component {
+            Component::ParentDir => {
+                if depth == 0 {
+                    return false;
+                }
+                depth -= 1;
+            }
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> Result<(), String> {
+    std::os::unix::fs::symlink(target, link)
+        .map_err(|e| format!("Failed to link {}: {e}", link.display()))
+}
+
+#[cfg(windows)]
+fn create_symlink(_target: &Path, link: &Path) -> Result<(), String> {
+    Err(format!(
+        "Symlinks are not supported here: {}",
+        link.display()
+    ))
+}
+
+/// Creates the archive's symlinks once every regular file is written, so no write goes
+/// through one. Links can chain, so each is checked by resolving it, not by its text.
+fn create_symlinks(links: &[(PathBuf, PathBuf)], dest: &Path) -> Result<(), String> {
+    let root =
+        fs::canonicalize(dest).map_err(|e| format!("Failed to resolve {}: {e}", dest.display()))?;
+    let escapes = |relative: &Path, target: &Path| {
+        format!(
+            "Symlink escapes the Node runtime: {} -> {}",
+            relative.display(),
+            target.display()
+        )
+    };
+    for (relative, target) in links {
+        let out = dest.join(relative);
+        let parent = out.parent().unwrap_or(dest);
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        if !fs::canonicalize(parent).is_ok_and(|parent| parent.starts_with(&root)) {
+            return Err(escapes(relative, target));
+        }
+        create_symlink(target, &out)?;
+    }
+    for (relative, target) in links {
+        if !fs::canonicalize(dest.join(relative)).is_ok_and(|resolved| resolved.starts_with(&root))
+        {
+            return Err(escapes(relative, target));
+        }
+    }
+    Ok(())
+}
+
+fn extract_tar_gz(bytes: &[u8], dest: &Path) -> Result<(), String> {
+    let mut archive = tar::Archive::new(GzDecoder::new(Cursor::new(bytes)));
+    archive.set_preserve_permissions(true);
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Failed to read Node archive: {e}"))?;
+
+    let mut links = Vec::new();
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Failed to read Node archive: {e}"))?;
+        let name = entry
+            .path()
+            .map_err(|e| format!("Failed to read Node archive entry: {e}"))?
+            .to_path_buf();
+        let Some(relative) = strip_top_dir(&name)? else {
+            continue;
+        };
+
+        let kind = entry.header().entry_type();
+        if kind.is_symlink() {
+            let target = entry
+                .link_name()
+                .map_err(|e| format!("Failed to read Node archive entry: {e}"))?
+                .ok_or_else(|| format!("Symlink without target: {}", name.display()))?;
+            if !link_stays_inside(&relative, &target) {
+                return Err(format!(
+                    "Symlink escapes the Node runtime: {} -> {}",
+                    name.display(),
+                    target.display()
+                ));
+            }
+            links.push((relative, target.into_owned()));
+            continue;
+        } else if kind.is_hard_link() {
+            return Err(format!("Hard links are not supported: {}", name.display()));
+        } else if !kind.is_file() && !kind.is_dir() {
+            continue;
+        }
+
+        let out = dest.join(&relative);
+        if let Some(parent) = out.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        entry
+            .unpack(&out)
+            .map_err(|e| format!("Failed to write {}: {e}", out.display()))?;
+    }
+    create_symlinks(&links, dest)
+}
+
+fn extract_zip(bytes: &[u8], dest: &Path) -> Result<(), String> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| format!("Failed to open Node archive: {e}"))?;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read Node archive entry: {e}"))?;
+        let Some(name) = entry.enclosed_name().map(Path::to_path_buf) else {
+            return Err(format!("Unsafe path in Node archive: {}", entry.name()));
+        };
+        let Some(relative) = strip_top_dir(&name)? else {
+            continue;
+        };
+        let out = dest.join(&relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&out)
+                .map_err(|e| format!("Failed to create {}: {e}", out.display()))?;
+            continue;
+        }
+        if let Some(parent) = out.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        let mut buffer = Vec::new();
+        entry
+            .read_to_end(&mut buffer)
+            .map_err(|e| format!("Failed to read Node archive entry: {e}"))?;
+        fs::write(&out, buffer).map_err(|e| format!("Failed to write {}: {e}", out.display()))?;
+    }
+    Ok(())
+}
+
+fn pinned_sha(request: &NodeInstallRequest) -> Option<String> {
+    request
+        .sha256
+        .as_deref()
+        .map(str::trim)
+        .filter(|sha| !sha.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn download_node_archive(
+    request: &NodeInstallRequest,
+    archive: &str,
+    on_progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<(Vec<u8>, String), String> {
+    let base = format!(
+        "{}/v{}",
+        request.mirror.trim().trim_end_matches('/'),
+        normalize_version(&request.version)
+    );
+    let expected = match pinned_sha(request) {
+        Some(sha) => sha,
+        None => {
+            let listing = download_archive(
+                &format!("{base}/{SHASUMS_FILE}"),
+                "Node checksums",
+                request.timeout,
+                &mut |_, _| {},
+            )?;
+            parse_shasums(&String::from_utf8_lossy(&listing), archive)
+                .ok_or_else(|| format!("{archive} is not listed in the mirror's {SHASUMS_FILE}"))?
+        }
+    };
+
+    let bytes = download_archive(
+        &format!("{base}/{archive}"),
+        "Node runtime",
+        request.timeout,
+        on_progress,
+    )?;
+    let actual = sha256_hex(&bytes);
+    if actual != expected {
+        return Err(format!(
+            "Node runtime checksum mismatch: expected {expected}, got {actual}"
+        ));
+    }
+    Ok((bytes, actual))
+}
+
+fn remove_other_versions(root: &Path, keep: &Path) {
+    let Ok(entries) = fs::read_dir(root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path != keep && path.is_dir() {
+            if let Err(e) = fs::remove_dir_all(&path) {
+                println!("[runtime] Failed to remove {}: {e}", path.display());
+            }
+        }
+    }
+}
+
+/// Installs the requested runtime under `request.root`, replacing any other version. The
+/// archive is verified before extraction and the installed `node` must report the version.
+pub fn install_node_runtime(
+    request: &NodeInstallRequest,
+    on_progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<NodeRuntimeInstall, String> {
+    let version = normalize_version(&request.version).to_string();
+    let archive = node_archive_name(&version, &request.os, &request.arch);
+
+    let (bytes, sha256) = download_node_archive(request, &archive, on_progress)?;
+
+    fs::create_dir_all(&request.root)
+        .map_err(|e| format!("Failed to create {}: {e}", request.root.display()))?;
+    let staging = request.root.join(format!(".v{version}.partial"));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .map_err(|e| format!("Failed to remove {}: {e}", staging.display()))?;
+    }
+    fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create {}: {e}", staging.display()))?;
+
+    let extracted = if archive.ends_with(".zip") {
+        extract_zip(&bytes, &staging)
+    } else {
+        extract_tar_gz(&bytes, &staging)
+    }
+    .and_then(|_| match runtime_version(&node_executable(&staging)) {
+        Some(reported) if normalize_version(&reported) == version => Ok(()),
+        Some(reported) => Err(format!(
+            "Installed Node reports {reported}, expected v{version}"
+        )),
+        None => Err("Installed Node runtime failed to start".to_string()),
+    });
+    if let Err(error) = extracted {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(error);
+    }
+
+    let install = NodeRuntimeInstall {
+        version: version.clone(),
+        archive,
+        sha256,
+        origin: ArchiveOrigin::Network,
+        installed_at_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+    };
+    let marker = serde_json::to_string_pretty(&install).map_err(|e| e.to_string())?;
+    fs::write(staging.join(INSTALL_MARKER), marker)
+        .map_err(|e| format!("Failed to write runtime marker: {e}"))?;
+
+    let target = install_dir(&request.root, &version);
+    if target.exists() {
+        fs::remove_dir_all(&target)
+            .map_err(|e| format!("Failed to remove {}: {e}", target.display()))?;
+    }
+    fs::rename(&staging, &target)
+        .map_err(|e| format!("Failed to move runtime into {}: {e}", target.display()))?;
+    remove_other_versions(&request.root, &target);
+    Ok(install)
+}
+
+/// Reports the managed runtime, falling back to whatever `node` is on PATH.
+pub fn node_runtime_status(settings: &NodeRuntimeSettings, root: &Path) -> NodeRuntimeStatus {
+    let managed = read_install(root, &settings.version);
+    let managed_bin_dir = managed.as_ref().map(|_| {
+        bin_dir(&install_dir(root, &settings.version))
+            .to_string_lossy()
+            .to_string()
+    });
+    let system_path = resolve_command("node");
+    let system_version = system_path.as_deref().and_then(runtime_version);
+
+    let active = if settings.enabled && managed.is_some() {
+        "managed"
+    } else if system_path.is_some() {
+        "system"
+    } else {
+        "missing"
+    };
+
+    NodeRuntimeStatus {
+        active: active.to_string(),
+        enabled: settings.enabled,
+        configured_version: normalize_version(&settings.version).to_string(),
+        managed,
+        managed_bin_dir,
+        system_path: system_path.map(|path| path.to_string_lossy().to_string()),
+        system_version,
+    }
+}
+
+pub fn node_runtime_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?;
+    Ok(data_dir.join("runtimes").join("node"))
+}
+
+/// Bin dir of the managed runtime when it is enabled and installed, for prepending to PATH.
+pub fn managed_node_bin_dir(app: &tauri::AppHandle) -> Option<PathBuf> {
+    let settings = load_app_settings(app).ok()?.node_runtime;
+    if !settings.enabled {
+        return None;
+    }
+    let root = node_runtime_root(app).ok()?;
+    read_install(&root, &settings.version)?;
+    Some(bin_dir(&install_dir(&root, &settings.version)))
+}
+
+pub fn app_node_runtime_status(app: &tauri::AppHandle) -> Result<NodeRuntimeStatus, String> {
+    let settings = load_app_settings(app)?.node_runtime;
+    Ok(node_runtime_status(&settings, &node_runtime_root(app)?))
+}
+
+pub fn install_request(app: &tauri::AppHandle) -> Result<NodeInstallRequest, String> {
+    let settings = load_app_settings(app)?.node_runtime;
+    let Some((os, arch)) = node_platform() else {
+        return Err("The managed Node runtime isn't available for this platform".to_string());
+    };
+    Ok(NodeInstallRequest {
+        version: settings.version,
+        mirror: settings.mirror,
+        sha256: settings.sha256,
+        root: node_runtime_root(app)?,
+        os: os.to_string(),
+        arch: arch.to_string(),
+        timeout: DOWNLOAD_TIMEOUT,
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::fs::TempDir;
+
+    /// A Node tarball with the given `(path, target)` symlinks, paths relative to its top dir.
+    fn node_tarball(version: &str, links: &[(&str, &str)]) -> Vec<u8> {
+        let top = format!("node-v{version}-linux-x64");
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::fast(),
+        ));
+        let files = [
+            (
+                format!("{top}/bin/node"),
+                format!("#!/bin/sh\necho v{version}\n"),
+                0o755,
+            ),
+            (
+                format!("{top}/lib/npx-cli.js"),
+                "// npx\n".to_string(),
+                0o644,
+            ),
+        ];
+        for (path, body, mode) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(body.len() as u64);
+            header.set_mode(mode);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, body.as_bytes())
+                .expect("append file");
+        }
+        for (path, target) in links {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_size(0);
+            header.set_mode(0o777);
+            builder
+                .append_link(&mut header, format!("{top}/{path}"), target)
+                .expect("append link");
+        }
+        builder
+            .into_inner()
+            .expect("finish tar")
+            .finish()
+            .expect("finish gzip")
+    }
+
+    /// Serves `files` by path over plain HTTP until the test exits and returns the base URL.
+    fn serve_mirror(files: Vec<(String, Vec<u8>)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = Vec::new();
+                let mut byte = [0u8; 1];
+                while !request.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap_or(0) == 1 {
+                    request.push(byte[0]);
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body) = match files.iter().find(|(file, _)| file == path) {
+                    Some((_, body)) => ("200 OK", body.as_slice()),
+                    None => ("404 Not Found", &b""[..]),
+                };
+                let header = format!(
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = stream.write_all(header.as_bytes());
+                let _ = stream.write_all(body);
+            }
+        });
+        format!("http://{addr}/dist")
+    }
+
+    /// A mirror serving `tarball` for 1.2.3, with `SHASUMS256.txt` listing `listed_sha` if set.
+    fn mirror(tarball: &[u8], listed_sha: Option<&str>) -> String {
+        let archive = node_archive_name("1.2.3", "linux", "x64");
+        let mut files = vec![(format!("/dist/v1.2.3/{archive}"), tarball.to_vec())];
+        if let Some(sha) = listed_sha {
+            files.push((
+                format!("/dist/v1.2.3/{SHASUMS_FILE}"),
+                format!("{sha}  {archive}\n").into_bytes(),
+            ));
+        }
+        serve_mirror(files)
+    }
+
+    fn request(root: &Path, mirror: String, version: &str) -> NodeInstallRequest {
+        NodeInstallRequest {
+            version: version.to_string(),
+            mirror,
+            sha256: None,
+            root: root.to_path_buf(),
+            os: "linux".to_string(),
+            arch: "x64".to_string(),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    fn good_tarball() -> Vec<u8> {
+        node_tarball("1.2.3", &[("bin/npx", "../lib/npx-cli.js")])
+    }
+
+    #[test]
+    fn installs_a_runtime_checked_against_the_mirror_listing() {
+        let root = TempDir::new("node-runtime");
+        let tarball = good_tarball();
+        let mirror = mirror(&tarball, Some(&sha256_hex(&tarball)));
+
+        let install = install_node_runtime(&request(&root, mirror, "v1.2.3"), &mut |_, _| {})
+            .expect("install");
+        assert_eq!(install.version, "1.2.3");
+        assert_eq!(install.origin, ArchiveOrigin::Network);
+        assert_eq!(read_install(&root, "1.2.3"), Some(install));
+        let bin = bin_dir(&install_dir(&root, "1.2.3"));
+        assert_eq!(
+            fs::read_to_string(bin.join("npx")).expect("follow npx link"),
+            "// npx\n"
+        );
+    }
+
+    #[test]
+    fn rejects_an_archive_the_listing_does_not_match() {
+        let root = TempDir::new("node-runtime");
+        let tampered = node_tarball("1.2.3", &[("bin/npx", "../lib/other.js")]);
+        let mirror = mirror(&tampered, Some(&sha256_hex(&good_tarball())));
+
+        let error = install_node_runtime(&request(&root, mirror, "1.2.3"), &mut |_, _| {})
+            .expect_err("tampered");
+        assert!(error.contains("checksum mismatch"), "{error}");
+        assert!(read_install(&root, "1.2.3").is_none());
+    }
+
+    #[test]
+    fn a_pinned_checksum_does_not_need_the_listing() {
+        let root = TempDir::new("node-runtime");
+        let tarball = good_tarball();
+        let mut pinned = request(&root, mirror(&tarball, None), "1.2.3");
+        pinned.sha256 = Some(sha256_hex(&tarball).to_uppercase());
+
+        install_node_runtime(&pinned, &mut |_, _| {}).expect("install");
+        assert!(read_install(&root, "1.2.3").is_some());
+    }
+
+    #[test]
+    fn rejects_links_that_escape_the_runtime_and_keeps_the_old_install() {
+        let root = TempDir::new("node-runtime");
+        let tarball = good_tarball();
+        let mirror_url = mirror(&tarball, Some(&sha256_hex(&tarball)));
+        install_node_runtime(&request(&root, mirror_url, "1.2.3"), &mut |_, _| {})
+            .expect("install");
+
+        // Each link looks harmless on its own; together `lib/a` resolves above the runtime.
+        for links in [
+            &[("bin/npx", "../../../../etc/passwd")][..],
+            &[
+                ("lib/b", ".."),
+                ("lib/a", "b/../.."),
+                ("lib/a/c", "../npx-cli.js"),
+            ][..],
+        ] {
+            let escaping = node_tarball("1.2.3", links);
+            let mut pinned = request(&root, mirror(&escaping, None), "1.2.3");
+            pinned.sha256 = Some(sha256_hex(&escaping));
+            let error = install_node_runtime(&pinned, &mut |_, _| {}).expect_err("escaping link");
+            assert!(error.contains("escapes"), "{error}");
+            assert!(read_install(&root, "1.2.3").is_some());
+            assert!(!root.join("c").exists());
+        }
+    }
+}
@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::config::effective::{resolve_effective_config, ConfigInputs};
+use crate::config::mcp::McpServerConfig;
+use crate::config::plugins::{describe_plugin, read_plugin_list};
+use crate::jsonc::escape_pointer;
+use crate::paths::{opencode_cache_candidates, path_entries, resolve_command_in};
+use crate::platform::{command_for_program, configure_hidden};
+use crate::types::{McpRuntimeCheck, RuntimePreflight};
+use crate::utils::truncate_output;
+
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Launchers whose `--version` only prints a version. Anything else may be the MCP server
+/// itself, which `--version` would start.
+const KNOWN_RUNTIMES: &[&str] = &[
+    "node", "npx", "npm", "uv", "uvx", "python", "python3", "docker", "bun", "bunx",
+];
+
+/// What to tell the user when a launcher is missing, keyed by its bare name.
+fn install_hint(launcher: &str) -> String {
+    match launcher {
+        "npx" | "node" | "npm" => {
+            "Install Node.js (https://nodejs.org) so `npx` and `node` are on PATH.".to_string()
+        }
+        "uvx" | "uv" => "Install uv (https://docs.astral.sh/uv/) so `uvx` is on PATH.".to_string(),
+        "python" | "python3" | "pip" => "Install Python 3 and make sure it is on PATH.".to_string(),
+        "docker" => "Install Docker Desktop (https://docker.com) and start it.".to_string(),
+        "bun" | "bunx" => "Install Bun (https://bun.sh) so it is on PATH.".to_string(),
+        other => format!("`{other}` was not found on PATH."),
+    }
+}
+
+fn launcher_name(program: &str) -> String {
+    let name = Path::new(program)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    for ext in [".exe", ".cmd", ".bat"] {
+        if let Some(stem) = name.strip_suffix(ext) {
+            return stem.to_string();
+        }
+    }
+    name
+}
+
+/// Sends the first non-empty line of `stream`, then drains the rest so the process never
+/// blocks on a full pipe.
+fn first_line(stream: impl Read + Send + 'static) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap_or(0) > 0 {
+            if !line.trim().is_empty() {
+                let _ = sender.send(truncate_output(line.trim(), 200));
+                break;
+            }
+            line.clear();
+        }
+        let _ = io::copy(&mut reader, &mut io::sink());
+    });
+    receiver
+}
+
+/// First line of `<program> --version`, or `None` if it fails or hangs.
+pub fn runtime_version(program: &Path) -> Option<String> {
+    let mut command = command_for_program(program);
+    command
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    configure_hidden(&mut command);
+    let mut child = command.spawn().ok()?;
+    let stdout = first_line(child.stdout.take()?);
+    let stderr = first_line(child.stderr.take()?);
+
+    let deadline = Instant::now() + VERSION_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    };
+    if !status.success() {
+        return None;
+    }
+    // A grandchild can keep a pipe open after the launcher exits; don't wait on it for long.
+    let grace = Duration::from_millis(500);
+    stdout
+        .recv_timeout(grace)
+        .or_else(|_| stderr.recv_timeout(grace))
+        .ok()
+}
+
+/// Resolves the launcher of every local MCP server in `config`, searching `extra_path` before
+/// `PATH` the way a spawned engine would.
+pub fn check_mcp_runtimes(
+    config: &Value,
+    provenance: &BTreeMap<String, String>,
+    project_dir: Option<&Path>,
+    extra_path: &[PathBuf],
+) -> Vec<McpRuntimeCheck> {
+    let mut search_path = extra_path.to_vec();
+    search_path.extend(path_entries());
+    let Some(servers) = config.get("mcp").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let mut versions: HashMap<PathBuf, Option<String>> = HashMap::new();
+    let mut checks = Vec::new();
+
+    for (name, value) in servers {
+        let pointer = format!("/mcp/{}", escape_pointer(name));
+        let source = provenance
+            .get(&format!("{pointer}/command"))
+            .or_else(|| provenance.get(&format!("{pointer}/type")))
+            .cloned();
+        let mut check = McpRuntimeCheck {
+            server: name.clone(),
+            source,
+            command: None,
+            enabled: true,
+            resolved_path: None,
+            runtime_version: None,
+            ok: false,
+            note: None,
+        };
+
+        let (command, enabled) = match serde_json::from_value::<McpServerConfig>(value.clone()) {
+            Ok(McpServerConfig::Local {
+                command, enabled, ..
+            }) => (command, enabled),
+            Ok(McpServerConfig::Remote { .. }) => continue,
+            Err(e) => {
+                check.note = Some(format!("Invalid MCP entry: {e}"));
+                checks.push(check);
+                continue;
+            }
+        };
+        check.command = Some(command.join(" "));
+        check.enabled = enabled.unwrap_or(true);
+        let Some(program) = command.first().map(|program| program.trim()) else {
+            check.note = Some("No command configured.".to_string());
+            checks.push(check);
+            continue;
+        };
+
+        let path = Path::new(program);
+        let resolved = if path.components().count() > 1 {
+            let path = match project_dir {
+                Some(dir) if path.is_relative() => dir.join(path),
+                _ => path.to_path_buf(),
+            };
+            path.is_file().then_some(path)
+        } else {
+            resolve_command_in(program, &search_path)
+        };
+
+        match resolved {
+            Some(resolved) => {
+                let version = if KNOWN_RUNTIMES.contains(&launcher_name(program).as_str()) {
+                    versions
+                        .entry(resolved.clone())
+                        .or_insert_with(|| runtime_version(&resolved))
+                        .clone()
+                } else {
+                    None
+                };
+                check.resolved_path = Some(resolved.to_string_lossy().to_string());
+                check.runtime_version = version;
+                check.ok = true;
+            }
+            None if !check.enabled => {
+                check.ok = true;
+                check.note = Some(format!("Disabled; `{program}` was not found."));
+            }
+            None => {
+                check.note = Some(install_hint(&launcher_name(program)));
+            }
+        }
+        checks.push(check);
+    }
+
+    checks
+}
+
+/// Checks what the effective config for `project_dir` needs at runtime: MCP launchers and
+/// npm plugins OpenCode would otherwise download on first start.
+pub fn runtime_preflight(
+    inputs: &ConfigInputs,
+    project_dir: Option<&Path>,
+    extra_path: &[PathBuf],
+) -> RuntimePreflight {
+    let effective = resolve_effective_config(inputs, project_dir);
+    let mut notes: Vec<String> = effective
+        .layers
+        .iter()
+        .filter_map(|layer| {
+            let error = layer.error.as_ref()?;
+            Some(format!("Skipped {}: {error}", layer.source))
+        })
+        .collect();
+
+    let mcp = check_mcp_runtimes(
+        &effective.config,
+        &effective.provenance,
+        project_dir,
+        extra_path,
+    );
+    if mcp.iter().any(|check| !check.ok) {
+        let dirs: Vec<String> = extra_path
+            .iter()
+            .chain(path_entries().iter())
+            .map(|dir| dir.to_string_lossy().to_string())
+            .collect();
+        notes.push(format!("Searched PATH: {}", dirs.join(", ")));
+    }
+
+    let cache_dirs = opencode_cache_candidates();
+    let plugins = read_plugin_list(effective.config.get("plugin"))
+        .iter()
+        .enumerate()
+        .map(|(index, spec)| {
+            let base_dir = effective
+                .provenance
+                .get(&format!("/plugin/{index}"))
+                .and_then(|source| Path::new(source).parent())
+                .map(Path::to_path_buf);
+            describe_plugin(spec, &cache_dirs, base_dir.as_deref())
+        })
+        .collect();
+
+    RuntimePreflight {
+        project_dir: project_dir.map(|dir| dir.to_string_lossy().to_string()),
+        mcp,
+        plugins,
+        notes,
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::fs::TempDir;
+
+    /// Checks `mcp` against a workspace holding a fake `node` and a custom launcher.
+    fn check(mcp: serde_json::Value) -> Vec<McpRuntimeCheck> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new("preflight");
+        std::fs::create_dir_all(dir.join("bin")).expect("create bin dir");
+        // Prints its version, then far more than a pipe buffer holds.
+        let node = dir.join("bin").join("node");
+        let server = dir.join("bin").join("my-mcp-server");
+        std::fs::write(
+            &node,
+            "#!/bin/sh\necho 'v20.11.0'\nhead -c 1000000 /dev/zero | tr '\\0' x\n",
+        )
+        .expect("node");
+        std::fs::write(&server, "#!/bin/sh\necho 'server started'\n").expect("server");
+        for launcher in [&node, &server] {
+            std::fs::set_permissions(launcher, std::fs::Permissions::from_mode(0o755))
+                .expect("chmod");
+        }
+
+        let provenance = BTreeMap::from([(
+            "/mcp/local/command".to_string(),
+            "/etc/opencode.json".to_string(),
+        )]);
+        check_mcp_runtimes(
+            &serde_json::json!({ "mcp": mcp }),
+            &provenance,
+            Some(&dir),
+            &[],
+        )
+    }
+
+    #[test]
+    fn reports_the_version_of_known_runtimes() {
+        let checks = check(serde_json::json!({
+            "local": { "type": "local", "command": ["./bin/node", "server.js"] }
+        }));
+        let local = &checks[0];
+        assert!(local.ok);
+        assert_eq!(local.runtime_version.as_deref(), Some("v20.11.0"));
+        assert_eq!(local.source.as_deref(), Some("/etc/opencode.json"));
+    }
+
+    #[test]
+    fn does_not_start_unknown_launchers() {
+        let checks = check(serde_json::json!({
+            "custom": { "type": "local", "command": ["./bin/my-mcp-server"] }
+        }));
+        assert!(checks[0].ok);
+        assert_eq!(checks[0].runtime_version, None);
+    }
+
+    #[test]
+    fn flags_missing_launchers_with_a_hint() {
+        let checks = check(serde_json::json!({
+            "missing": { "type": "local", "command": ["uvx-missing-for-test", "srv"] },
+            "npx-less": { "type": "local", "command": ["/nonexistent/npx", "-y", "srv"] }
+        }));
+        let notes: Vec<(&str, bool, &str)> = checks
+            .iter()
+            .map(|check| {
+                (
+                    check.server.as_str(),
+                    check.ok,
+                    check.note.as_deref().unwrap_or_default(),
+                )
+            })
+            .collect();
+        assert!(notes[0].0 == "missing" && !notes[0].1);
+        assert!(notes[0].2.contains("not found on PATH"), "{notes:?}");
+        assert!(notes[1].0 == "npx-less" && !notes[1].1);
+        assert!(notes[1].2.contains("Node.js"), "{notes:?}");
+    }
+
+    #[test]
+    fn skips_remote_servers_and_flags_broken_entries() {
+        let checks = check(serde_json::json!({
+            "off": { "type": "local", "command": ["nope-for-test"], "enabled": false },
+            "remote": { "type": "remote", "url": "https://mcp.example" },
+            "broken": { "type": "local" }
+        }));
+        let by_name: HashMap<&str, &McpRuntimeCheck> = checks
+            .iter()
+            .map(|check| (check.server.as_str(), check))
+            .collect();
+        assert_eq!(checks.len(), 2);
+        assert!(!by_name.contains_key("remote"));
+        assert!(by_name["off"].ok && !by_name["off"].enabled);
+        assert!(!by_name["broken"].ok);
+    }
+}
@@ -0,0 +1,386 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::Manager;
+
+use crate::config::effective::ConfigInputs;
+use crate::fs::{copy_dir_recursive, write_private};
+use crate::paths::opencode_auth_path;
+use crate::types::WorkspaceInfo;
+use crate::utils::now_ms;
+use crate::workspace::state::load_workspace_state;
+
+const PROFILES_DIR: &str = "opencode-profiles";
+const PROFILE_MARKER: &str = "openwork-profile.json";
+const MAX_PROFILE_NAME: usize = 64;
+
+/// Written at the top of a profile directory.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileMeta {
+    pub name: String,
+    pub created_at_ms: u64,
+    #[serde(default)]
+    pub cloned_from: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpencodeProfile {
+    pub name: String,
+    pub path: String,
+    pub created_at_ms: u64,
+    pub cloned_from: Option<String>,
+    /// Provider ids with an `auth.json` entry. Never the credentials themselves.
+    pub providers: Vec<String>,
+    /// Ids of the workspaces that run OpenCode with this profile.
+    pub workspaces: Vec<String>,
+}
+
+/// The XDG roots OpenCode uses for one profile. Each holds an `opencode` folder, just like
+/// the user's home does for the shared identity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileDirs {
+    pub data: PathBuf,
+    pub config: PathBuf,
+    pub cache: PathBuf,
+    pub state: PathBuf,
+}
+
+impl ProfileDirs {
+    pub fn new(profile_dir: &Path) -> Self {
+        Self {
+            data: profile_dir.join("data"),
+            config: profile_dir.join("config"),
+            cache: profile_dir.join("cache"),
+            state: profile_dir.join("state"),
+        }
+    }
+
+    pub fn auth_path(&self) -> PathBuf {
+        self.data.join("opencode").join("auth.json")
+    }
+
+    pub fn env(&self) -> Vec<(String, String)> {
+        [
+            ("XDG_DATA_HOME", &self.data),
+            ("XDG_CONFIG_HOME", &self.config),
+            ("XDG_CACHE_HOME", &self.cache),
+            ("XDG_STATE_HOME", &self.state),
+        ]
+        .into_iter()
+        .map(|(name, dir)| (name.to_string(), dir.to_string_lossy().to_string()))
+        .collect()
+    }
+
+    fn create(&self) -> Result<(), String> {
+        for dir in [&self.data, &self.config, &self.cache, &self.state] {
+            let dir = dir.join("opencode");
+            fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_PROFILE_NAME
+        && name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!(
+            "Invalid profile name {name:?}: use lowercase letters, digits, '-' and '_'"
+        ));
+    }
+    Ok(())
+}
+
+pub fn profile_dir(root: &Path, name: &str) -> PathBuf {
+    root.join(name)
+}
+
+pub fn read_profile(root: &Path, name: &str) -> Result<ProfileMeta, String> {
+    validate_profile_name(name)?;
+    let path = profile_dir(root, name).join(PROFILE_MARKER);
+    let raw = fs::read_to_string(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("Unknown OpenCode profile: {name}"),
+        _ => format!("Failed to read {}: {e}", path.display()),
+    })?;
+    serde_json::from_str(&raw).map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+}
+
+pub fn list_profiles(root: &Path) -> Vec<ProfileMeta> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut profiles: Vec<ProfileMeta> = entries
+        .flatten()
+        .filter_map(|entry| read_profile(root, &entry.file_name().to_string_lossy()).ok())
+        .collect();
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    profiles
+}
+
+fn write_profile(root: &Path, meta: &ProfileMeta) -> Result<(), String> {
+    let path = profile_dir(root, &meta.name).join(PROFILE_MARKER);
+    let raw = serde_json::to_string_pretty(meta).map_err(|e| e.to_string())?;
+    fs::write(&path, raw).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Creates an empty profile: OpenCode starts it logged out, with no config or sessions.
+pub fn create_profile(root: &Path, name: &str) -> Result<ProfileMeta, String> {
+    validate_profile_name(name)?;
+    let dir = profile_dir(root, name);
+    if dir.exists() {
+        return Err(format!("OpenCode profile already exists: {name}"));
+    }
+    ProfileDirs::new(&dir).create()?;
+    let meta = ProfileMeta {
+        name: name.to_string(),
+        created_at_ms: now_ms(),
+        cloned_from: None,
+    };
+    write_profile(root, &meta)?;
+    Ok(meta)
+}
+
+/// Copies credentials, config, sessions and state from `source`. The cache is left behind;
+/// OpenCode rebuilds it on first start.
+pub fn clone_profile(root: &Path, source: &str, name: &str) -> Result<ProfileMeta, String> {
+    read_profile(root, source)?;
+    let meta = create_profile(root, name)?;
+    let from = ProfileDirs::new(&profile_dir(root, source));
+    let to = ProfileDirs::new(&profile_dir(root, name));
+    let copied = [
+        (&from.data, &to.data),
+        (&from.config, &to.config),
+        (&from.state, &to.state),
+    ]
+    .into_iter()
+    .try_for_each(|(from, to)| copy_dir_recursive(from, to));
+    if let Err(error) = copied {
+        let _ = fs::remove_dir_all(profile_dir(root, name));
+        return Err(error);
+    }
+
+    let meta = ProfileMeta {
+        cloned_from: Some(source.to_string()),
+        ..meta
+    };
+    write_profile(root, &meta)?;
+    Ok(meta)
+}
+
+pub fn delete_profile(root: &Path, name: &str) -> Result<(), String> {
+    read_profile(root, name)?;
+    let dir = profile_dir(root, name);
+    fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete {}: {e}", dir.display()))
+}
+
+fn read_auth(path: &Path) -> Result<Map<String, Value>, String> {
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+    let raw =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    match serde_json::from_str(&raw) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Err(format!("{} is not a JSON object", path.display())),
+        Err(e) => Err(format!("Failed to parse {}: {e}", path.display())),
+    }
+}
+
+pub fn auth_providers(path: &Path) -> Vec<String> {
+    read_auth(path)
+        .map(|auth| auth.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Copies `auth.json` entries, all of them when `providers` is empty, overwriting any the
+/// target already has. Returns the provider ids copied.
+pub fn copy_credentials(
+    from: &Path,
+    to: &Path,
+    providers: &[String],
+) -> Result<Vec<String>, String> {
+    let source = read_auth(from)?;
+    let mut target = read_auth(to)?;
+    if let Some(missing) = providers.iter().find(|id| !source.contains_key(*id)) {
+        return Err(format!(
+            "No credentials for {missing} in {}",
+            from.display()
+        ));
+    }
+
+    let mut copied = Vec::new();
+    for (id, entry) in source {
+        if providers.is_empty() || providers.contains(&id) {
+            target.insert(id.clone(), entry);
+            copied.push(id);
+        }
+    }
+    if copied.is_empty() {
+        return Ok(copied);
+    }
+
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let raw = serde_json::to_vec_pretty(&Value::Object(target)).map_err(|e| e.to_string())?;
+    write_private(to, &raw)?;
+    Ok(copied)
+}
+
+pub fn profiles_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?;
+    Ok(data_dir.join(PROFILES_DIR))
+}
+
+/// `auth.json` of a profile, or of the shared OpenCode identity when `name` is `None`.
+pub fn app_auth_path(app: &tauri::AppHandle, name: Option<&str>) -> Result<PathBuf, String> {
+    match name {
+        Some(name) => {
+            let root = profiles_root(app)?;
+            read_profile(&root, name)?;
+            Ok(ProfileDirs::new(&profile_dir(&root, name)).auth_path())
+        }
+        None => opencode_auth_path()
+            .ok_or_else(|| "Failed to resolve the OpenCode auth.json path".to_string()),
+    }
+}
+
+pub fn app_profiles(app: &tauri::AppHandle) -> Result<Vec<OpencodeProfile>, String> {
+    let root = profiles_root(app)?;
+    let workspaces = load_workspace_state(app)?.workspaces;
+    Ok(list_profiles(&root)
+        .into_iter()
+        .map(|meta| {
+            let dir = profile_dir(&root, &meta.name);
+            OpencodeProfile {
+                providers: auth_providers(&ProfileDirs::new(&dir).auth_path()),
+                workspaces: workspaces
+                    .iter()
+                    .filter(|w| w.opencode_profile.as_deref() == Some(meta.name.as_str()))
+                    .map(|w| w.id.clone())
+                    .collect(),
+                path: dir.to_string_lossy().to_string(),
+                name: meta.name,
+                created_at_ms: meta.created_at_ms,
+                cloned_from: meta.cloned_from,
+            }
+        })
+        .collect())
+}
+
+/// Profile directories of the workspace at `workspace_path`, if it has its own profile.
+/// A profile that went missing is recreated empty rather than leaking into the shared one.
+pub fn workspace_profile_dirs(app: &tauri::AppHandle, workspace_path: &str) -> Option<ProfileDirs> {
+    let workspace_path = workspace_path.trim();
+    let name = load_workspace_state(app)
+        .ok()?
+        .workspaces
+        .into_iter()
+        .find(|workspace: &WorkspaceInfo| workspace.path == workspace_path)?
+        .opencode_profile?;
+    let root = profiles_root(app).ok()?;
+    if read_profile(&root, &name).is_err() {
+        println!("[workspace] OpenCode profile {name} is missing; recreating it");
+        if let Err(error) = create_profile(&root, &name) {
+            println!("[workspace] Failed to recreate OpenCode profile {name}: {error}");
+        }
+    }
+    Some(ProfileDirs::new(&profile_dir(&root, &name)))
+}
+
+/// `ConfigInputs::from_env`, with the global config read from the workspace's profile.
+pub fn workspace_config_inputs(app: &tauri::AppHandle, project_dir: Option<&Path>) -> ConfigInputs {
+    let mut inputs = ConfigInputs::from_env();
+    let dirs = project_dir.and_then(|dir| workspace_profile_dirs(app, &dir.to_string_lossy()));
+    if let Some(dirs) = dirs {
+        inputs.global_dir = Some(dirs.config.join("opencode"));
+    }
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::TempDir;
+
+    /// A profile root with a `personal` profile holding two credentials and a model cache.
+    fn personal_profile() -> (TempDir, ProfileDirs) {
+        let root = TempDir::new("profiles");
+        create_profile(&root, "personal").expect("create");
+        let personal = ProfileDirs::new(&profile_dir(&root, "personal"));
+        fs::write(
+            personal.auth_path(),
+            r#"{"anthropic":{"type":"api","key":"sk-1"},"openai":{"type":"api","key":"sk-2"}}"#,
+        )
+        .expect("auth");
+        fs::write(personal.cache.join("opencode").join("models.json"), "{}").expect("cache");
+        (root, personal)
+    }
+
+    #[test]
+    fn rejects_duplicate_and_invalid_profile_names() {
+        let (root, _) = personal_profile();
+        assert!(create_profile(&root, "personal").is_err());
+        assert!(create_profile(&root, "Client A").is_err());
+    }
+
+    #[test]
+    fn clones_credentials_but_not_the_cache() {
+        let (root, _) = personal_profile();
+        let clone = clone_profile(&root, "personal", "scratch").expect("clone");
+        assert_eq!(clone.cloned_from.as_deref(), Some("personal"));
+        let scratch = ProfileDirs::new(&profile_dir(&root, "scratch"));
+        assert_eq!(
+            auth_providers(&scratch.auth_path()),
+            ["anthropic", "openai"]
+        );
+        assert!(!scratch.cache.join("opencode").join("models.json").exists());
+    }
+
+    #[test]
+    fn copies_only_the_selected_credentials() {
+        let (root, personal) = personal_profile();
+        create_profile(&root, "client").expect("create");
+        let client = ProfileDirs::new(&profile_dir(&root, "client"));
+        let copied = copy_credentials(
+            &personal.auth_path(),
+            &client.auth_path(),
+            &["openai".to_string()],
+        )
+        .expect("copy");
+        assert_eq!(copied, ["openai"]);
+        assert_eq!(auth_providers(&client.auth_path()), ["openai"]);
+        assert!(copy_credentials(
+            &client.auth_path(),
+            &personal.auth_path(),
+            &["xai".to_string()]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn lists_profiles_by_name_after_a_delete() {
+        let (root, _) = personal_profile();
+        create_profile(&root, "client").expect("create");
+        create_profile(&root, "scratch").expect("create");
+        delete_profile(&root, "scratch").expect("delete");
+        let names: Vec<String> = list_profiles(&root).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["client", "personal"]);
+    }
+}
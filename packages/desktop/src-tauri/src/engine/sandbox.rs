@@ -0,0 +1,383 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::engine::node_runtime::managed_node_bin_dir;
+use crate::engine::profiles::workspace_profile_dirs;
+use crate::paths::{
+    candidate_xdg_data_dirs, home_dir, infer_opencode_config_home, maybe_infer_xdg_home,
+    opencode_cache_candidates, path_entries, resolve_in_path,
+};
+use crate::workspace::env::authorized_roots;
+use crate::workspace::files::read_workspace_openwork_config;
+use crate::workspace::state::load_workspace_state;
+
+/// System locations the toolchain lives in. Mounted read-only when present.
+const SYSTEM_READ_ONLY: &[&str] = &[
+    "/usr",
+    "/bin",
+    "/sbin",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/etc",
+    "/opt",
+    "/nix",
+    "/snap",
+    "/run/systemd/resolve",
+];
+
+/// Messages a process prints when it writes to a read-only mount. Paths outside the plan
+/// aren't mounted at all, so `EACCES`/`EPERM` come from ordinary file permissions rather
+/// than the sandbox and aren't counted.
+const DENIAL_MARKERS: &[&str] = &["EROFS", "Read-only file system"];
+
+const MAX_DENIALS: usize = 20;
+
+/// Mounts for one sandboxed engine. Everything not listed is invisible to OpenCode.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SandboxPlan {
+    pub read_only: Vec<PathBuf>,
+    /// Authorized roots and the OpenCode data, cache and state dirs.
+    pub writable: Vec<PathBuf>,
+    /// Files inside writable roots that stay read-only, so the engine can't widen its own
+    /// sandbox by editing `openwork.json`.
+    pub protected: Vec<PathBuf>,
+    /// Where `npm`/`npx` cache packages, since `~/.npm` isn't writable.
+    pub npm_cache: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxStatus {
+    /// The sandbox is Linux-only and runs through bubblewrap.
+    pub supported: bool,
+    pub bwrap_path: Option<String>,
+    /// The workspace has the sandbox turned on.
+    pub enabled: bool,
+    pub writable: Vec<String>,
+    pub read_only: Vec<String>,
+    /// Recent writes the running engine made to read-only mounts.
+    pub denials: Vec<String>,
+    pub notes: Vec<String>,
+}
+
+/// Whether an engine output line looks like a write the sandbox refused.
+pub fn is_sandbox_denial(line: &str) -> bool {
+    DENIAL_MARKERS.iter().any(|marker| line.contains(marker))
+}
+
+/// Keeps the last `MAX_DENIALS` distinct denial lines.
+pub fn record_denial(denials: &mut Vec<String>, line: &str) -> bool {
+    let line = line.trim();
+    if line.is_empty() || denials.iter().any(|seen| seen == line) {
+        return false;
+    }
+    if denials.len() == MAX_DENIALS {
+        denials.remove(0);
+    }
+    denials.push(line.to_string());
+    true
+}
+
+fn dedupe(paths: &mut Vec<PathBuf>) {
+    let mut seen = std::collections::HashSet::new();
+    paths.retain(|path| seen.insert(path.clone()));
+    // Parents mount before children so a nested mount isn't hidden by its parent.
+    paths.sort_by_key(|path| path.components().count());
+}
+
+fn push_bind(args: &mut Vec<String>, flag: &str, path: &Path) {
+    let path = path.to_string_lossy().to_string();
+    args.extend([flag.to_string(), path.clone(), path]);
+}
+
+/// `bwrap` arguments that run `program` with `plan` applied. Read-only mounts go first so
+/// writable roots can sit on top of them, and protected files go last.
+pub fn bwrap_args(
+    plan: &SandboxPlan,
+    project_dir: &str,
+    program: &str,
+    program_args: &[String],
+) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "--die-with-parent",
+        "--unshare-pid",
+        "--new-session",
+        "--dev",
+        "/dev",
+        "--proc",
+        "/proc",
+        "--tmpfs",
+        "/tmp",
+    ]
+    .map(str::to_string)
+    .to_vec();
+
+    for dir in SYSTEM_READ_ONLY {
+        let path = Path::new(dir);
+        match fs::read_link(path) {
+            // Merged-/usr systems link /bin and friends into /usr.
+            Ok(target) => args.extend([
+                "--symlink".to_string(),
+                target.to_string_lossy().to_string(),
+                dir.to_string(),
+            ]),
+            Err(_) => push_bind(&mut args, "--ro-bind-try", path),
+        }
+    }
+    for path in &plan.read_only {
+        push_bind(&mut args, "--ro-bind-try", path);
+    }
+    for path in &plan.writable {
+        push_bind(&mut args, "--bind", path);
+    }
+    for path in &plan.protected {
+        push_bind(&mut args, "--ro-bind-try", path);
+    }
+
+    if let Some(cache) = &plan.npm_cache {
+        args.extend([
+            "--setenv".to_string(),
+            "npm_config_cache".to_string(),
+            cache.to_string_lossy().to_string(),
+        ]);
+    }
+    args.extend(["--chdir".to_string(), project_dir.to_string()]);
+    args.push("--".to_string());
+    args.push(program.to_string());
+    args.extend(program_args.iter().cloned());
+    args
+}
+
+/// The OpenCode data, state and cache dirs the engine writes to, and its global config,
+/// which stays read-only.
+struct OpencodeDirs {
+    writable: Vec<PathBuf>,
+    cache: Option<PathBuf>,
+    config: Option<PathBuf>,
+}
+
+fn opencode_dirs(app: &tauri::AppHandle, project_dir: &str) -> OpencodeDirs {
+    if let Some(profile) = workspace_profile_dirs(app, project_dir) {
+        return OpencodeDirs {
+            writable: vec![profile.data, profile.state],
+            cache: Some(profile.cache),
+            config: Some(profile.config),
+        };
+    }
+
+    let non_empty = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+    };
+    let home = home_dir();
+    let from_home = |parts: &[&str]| {
+        home.as_ref()
+            .map(|home| parts.iter().fold(home.clone(), |dir, part| dir.join(part)))
+    };
+    let data_home = non_empty("XDG_DATA_HOME")
+        .or_else(|| {
+            maybe_infer_xdg_home(
+                "XDG_DATA_HOME",
+                candidate_xdg_data_dirs(),
+                Path::new("opencode/auth.json"),
+            )
+        })
+        .map(PathBuf::from)
+        .or_else(|| from_home(&[".local", "share"]));
+    let state_home = non_empty("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| from_home(&[".local", "state"]));
+    let config_home = non_empty("XDG_CONFIG_HOME")
+        .or_else(infer_opencode_config_home)
+        .map(PathBuf::from)
+        .or_else(|| from_home(&[".config"]));
+
+    OpencodeDirs {
+        writable: [data_home, state_home]
+            .into_iter()
+            .flatten()
+            .map(|dir| dir.join("opencode"))
+            .collect(),
+        cache: opencode_cache_candidates().into_iter().next(),
+        config: config_home.map(|dir| dir.join("opencode")),
+    }
+}
+
+/// Whether the workspace at `project_dir` runs its engine sandboxed.
+pub fn workspace_sandbox_enabled(app: &tauri::AppHandle, project_dir: &str) -> bool {
+    let project_dir = project_dir.trim();
+    load_workspace_state(app)
+        .map(|state| {
+            state
+                .workspaces
+                .iter()
+                .any(|workspace| workspace.path == project_dir && workspace.engine_sandbox)
+        })
+        .unwrap_or(false)
+}
+
+/// What the sandbox for `project_dir` would mount. Creates the writable OpenCode dirs, since
+/// `bwrap` refuses to bind paths that don't exist.
+pub fn workspace_sandbox_plan(
+    app: &tauri::AppHandle,
+    project_dir: &str,
+    program: Option<&Path>,
+) -> Result<SandboxPlan, String> {
+    let root = PathBuf::from(project_dir.trim());
+    let config = read_workspace_openwork_config(&root)?;
+    let opencode = opencode_dirs(app, project_dir);
+    let opencode_writable: Vec<PathBuf> = opencode
+        .writable
+        .into_iter()
+        .chain(opencode.cache.clone())
+        .collect();
+    for dir in &opencode_writable {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+
+    let mut writable = authorized_roots(&root, &config);
+    if writable.is_empty() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+    writable.extend(opencode_writable);
+
+    let mut read_only: Vec<PathBuf> = opencode.config.into_iter().collect();
+    read_only.extend(path_entries().into_iter().filter(|dir| dir.is_absolute()));
+    read_only.extend(managed_node_bin_dir(app).and_then(|dir| dir.parent().map(Path::to_path_buf)));
+    read_only.extend(program.and_then(|path| path.parent().map(Path::to_path_buf)));
+
+    dedupe(&mut read_only);
+    dedupe(&mut writable);
+    Ok(SandboxPlan {
+        read_only,
+        writable,
+        protected: vec![root.join(".opencode").join("openwork.json")],
+        npm_cache: opencode.cache.map(|dir| dir.join("npm")),
+    })
+}
+
+pub fn bwrap_path() -> Option<PathBuf> {
+    resolve_in_path("bwrap")
+}
+
+/// Sandbox diagnostics for `engine_doctor`.
+pub fn sandbox_status(
+    app: &tauri::AppHandle,
+    project_dir: Option<&Path>,
+    denials: Vec<String>,
+) -> SandboxStatus {
+    let supported = cfg!(target_os = "linux");
+    let project_dir = project_dir.map(|dir| dir.to_string_lossy().to_string());
+    let mut status = SandboxStatus {
+        supported,
+        bwrap_path: bwrap_path().map(|path| path.to_string_lossy().to_string()),
+        enabled: project_dir
+            .as_deref()
+            .is_some_and(|dir| workspace_sandbox_enabled(app, dir)),
+        denials,
+        ..SandboxStatus::default()
+    };
+
+    if !supported {
+        status
+            .notes
+            .push("The engine sandbox is only available on Linux".to_string());
+        return status;
+    }
+    if status.bwrap_path.is_none() {
+        status.notes.push(
+            "bubblewrap (bwrap) not found; install it to run the engine sandboxed".to_string(),
+        );
+    }
+    if let (Some(dir), true) = (project_dir.as_deref(), status.enabled) {
+        match workspace_sandbox_plan(app, dir, None) {
+            Ok(plan) => {
+                let strings = |paths: Vec<PathBuf>| {
+                    paths
+                        .into_iter()
+                        .map(|path| path.to_string_lossy().to_string())
+                        .collect()
+                };
+                status.writable = strings(plan.writable);
+                status.read_only = strings(plan.read_only);
+            }
+            Err(error) => status.notes.push(format!("Sandbox plan failed: {error}")),
+        }
+    }
+    if !status.denials.is_empty() {
+        status.notes.push(format!(
+            "The sandbox refused {} write(s); add the paths to authorizedRoots if they're expected",
+            status.denials.len()
+        ));
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_bwrap_mounts_in_order() {
+        let mut writable = vec![
+            PathBuf::from("/work/site"),
+            PathBuf::from("/home/me/.local/share/opencode"),
+            PathBuf::from("/work/site"),
+        ];
+        dedupe(&mut writable);
+        let plan = SandboxPlan {
+            read_only: vec![PathBuf::from("/home/me/.opencode/bin")],
+            writable,
+            protected: vec![PathBuf::from("/work/site/.opencode/openwork.json")],
+            npm_cache: Some(PathBuf::from("/home/me/.cache/opencode/npm")),
+        };
+        let args = bwrap_args(&plan, "/work/site", "/opt/opencode", &["serve".to_string()]);
+        let joined = args.join(" ");
+
+        assert!(joined.starts_with("--die-with-parent --unshare-pid --new-session"));
+        let position = |needle: &str| joined.find(needle).expect(needle);
+        assert!(
+            position("--ro-bind-try /home/me/.opencode/bin")
+                < position("--bind /work/site /work/site")
+        );
+        assert!(
+            position("--bind /work/site /work/site")
+                < position("--ro-bind-try /work/site/.opencode/openwork.json")
+        );
+        assert_eq!(joined.matches("--bind /work/site /work/site").count(), 1);
+        assert!(joined.ends_with(
+            "--setenv npm_config_cache /home/me/.cache/opencode/npm --chdir /work/site -- /opt/opencode serve"
+        ));
+    }
+
+    #[test]
+    fn counts_only_writes_to_read_only_mounts_as_denials() {
+        assert!(is_sandbox_denial(
+            "Error: EROFS: read-only file system, open '/home/me/.ssh/config'"
+        ));
+        assert!(is_sandbox_denial(
+            "touch: cannot touch '/etc/x': Read-only file system"
+        ));
+        assert!(!is_sandbox_denial("server listening on 4096"));
+        assert!(!is_sandbox_denial(
+            "Error: EACCES: permission denied, open '/work/site/locked.txt'"
+        ));
+        assert!(!is_sandbox_denial("kill: Operation not permitted"));
+    }
+
+    #[test]
+    fn keeps_the_latest_distinct_denials() {
+        let mut denials = Vec::new();
+        assert!(record_denial(&mut denials, "EROFS /etc/hosts\n"));
+        assert!(!record_denial(&mut denials, "EROFS /etc/hosts"));
+        for index in 0..MAX_DENIALS {
+            record_denial(&mut denials, &format!("EROFS /x/{index}"));
+        }
+        assert_eq!(denials.len(), MAX_DENIALS);
+        assert_eq!(denials[0], "EROFS /x/0");
+    }
+}
@@ -5,7 +5,16 @@ use tauri::async_runtime::Receiver;
 use tauri_plugin_shell::process::{CommandChild, CommandEvent};
 use tauri_plugin_shell::ShellExt;
 
-use crate::paths::{candidate_xdg_config_dirs, candidate_xdg_data_dirs, maybe_infer_xdg_home};
+use crate::engine::launcher::{expand_launcher, LaunchContext};
+use crate::engine::node_runtime::managed_node_bin_dir;
+use crate::engine::profiles::workspace_profile_dirs;
+use crate::engine::sandbox::{bwrap_args, bwrap_path, SandboxPlan};
+use crate::limits::{apply_limits, workspace_limits, ProcessLimits};
+use crate::paths::{
+    candidate_xdg_data_dirs, infer_opencode_config_home, maybe_infer_xdg_home, path_with_prefix,
+};
+use crate::types::EngineLauncher;
+use crate::workspace::env::workspace_spawn_env;
 
 pub fn find_free_port() -> Result<u16, String> {
     let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).map_err(|e| e.to_string())?;
@@ -29,56 +38,122 @@ pub fn build_engine_args(bind_host: &str, port: u16) -> Vec<String> {
     ]
 }
 
+/// How OpenCode is started: which binary, and what it runs inside.
+pub struct EngineCommand<'a> {
+    /// May only be missing when the launcher names its own OpenCode.
+    pub program: Option<&'a Path>,
+    pub use_sidecar: bool,
+    pub launcher: Option<&'a EngineLauncher>,
+    /// Wraps the launcher (or OpenCode itself) in `bwrap`.
+    pub sandbox: Option<&'a SandboxPlan>,
+}
+
 pub fn spawn_engine(
     app: &AppHandle,
-    program: &Path,
+    engine: &EngineCommand,
     hostname: &str,
     port: u16,
     project_dir: &str,
-    use_sidecar: bool,
-) -> Result<(Receiver<CommandEvent>, CommandChild), String> {
+) -> Result<(Receiver<CommandEvent>, CommandChild, Option<ProcessLimits>), String> {
     let args = build_engine_args(hostname, port);
 
-    let command = if use_sidecar {
-        app
-            .shell()
+    let invocation = match (engine.launcher, engine.program) {
+        (Some(launcher), program) => {
+            let context = LaunchContext {
+                opencode: program,
+                hostname,
+                port,
+                project_dir,
+                args: &args,
+            };
+            let invocation = expand_launcher(launcher, &context)?;
+            println!(
+                "[engine] starting through launcher {}: {}",
+                launcher.name, invocation.0
+            );
+            invocation
+        }
+        (None, Some(program)) => (program.to_string_lossy().to_string(), args.clone()),
+        (None, None) => return Err("OpenCode CLI not found".to_string()),
+    };
+
+    let wrapped = engine.sandbox.is_some() || engine.launcher.is_some();
+    let invocation = match engine.sandbox {
+        Some(plan) => {
+            let bwrap = bwrap_path().ok_or_else(|| {
+                "The engine sandbox needs bubblewrap (bwrap); install it or turn the sandbox off"
+                    .to_string()
+            })?;
+            let writable: Vec<String> = plan
+                .writable
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            println!("[engine] sandboxed; writable: {}", writable.join(", "));
+            let (program, program_args) = invocation;
+            (
+                bwrap.to_string_lossy().to_string(),
+                bwrap_args(plan, project_dir, &program, &program_args),
+            )
+        }
+        None => invocation,
+    };
+
+    // Limits go outermost so they also cover bwrap and whatever a launcher starts.
+    let (invocation, limits) = match workspace_limits(app, project_dir) {
+        Some(limits) => {
+            let (program, args, applied) =
+                apply_limits(&limits, "engine", invocation.0, invocation.1);
+            println!(
+                "[engine] resource limits ({:?}): {limits:?}",
+                applied.mechanism
+            );
+            ((program, args), Some(applied))
+        }
+        None => (invocation, None),
+    };
+
+    let mut command = if engine.use_sidecar && !wrapped && limits.is_none() {
+        app.shell()
             .sidecar("opencode")
             .map_err(|e| format!("Failed to locate bundled OpenCode sidecar: {e}"))?
+            .args(args)
     } else {
-        app.shell().command(program)
-    };
+        app.shell().command(invocation.0).args(invocation.1)
+    }
+    .current_dir(project_dir);
 
-    let mut command = command.args(args).current_dir(project_dir);
+    // A workspace profile gets its own auth, config, cache and sessions; everything else
+    // shares whichever OpenCode setup the user already has.
+    if let Some(profile) = workspace_profile_dirs(app, project_dir) {
+        command = command.envs(profile.env());
+    } else {
+        if let Some(xdg_data_home) = maybe_infer_xdg_home(
+            "XDG_DATA_HOME",
+            candidate_xdg_data_dirs(),
+            Path::new("opencode/auth.json"),
+        ) {
+            command = command.env("XDG_DATA_HOME", xdg_data_home);
+        }
 
-    if let Some(xdg_data_home) = maybe_infer_xdg_home(
-        "XDG_DATA_HOME",
-        candidate_xdg_data_dirs(),
-        Path::new("opencode/auth.json"),
-    ) {
-        command = command.env("XDG_DATA_HOME", xdg_data_home);
+        if let Some(xdg_config_home) = infer_opencode_config_home() {
+            command = command.env("XDG_CONFIG_HOME", xdg_config_home);
+        }
     }
 
-    let xdg_config_home = maybe_infer_xdg_home(
-        "XDG_CONFIG_HOME",
-        candidate_xdg_config_dirs(),
-        Path::new("opencode/opencode.jsonc"),
-    )
-    .or_else(|| {
-        maybe_infer_xdg_home(
-            "XDG_CONFIG_HOME",
-            candidate_xdg_config_dirs(),
-            Path::new("opencode/opencode.json"),
-        )
-    });
-
-    if let Some(xdg_config_home) = xdg_config_home {
-        command = command.env("XDG_CONFIG_HOME", xdg_config_home);
+    // MCP servers and plugins inherit this PATH, so they find the managed Node first.
+    if let Some(bin_dir) = managed_node_bin_dir(app) {
+        if let Some(path) = path_with_prefix(&[bin_dir]) {
+            command = command.env("PATH", path);
+        }
     }
 
+    command = command.envs(workspace_spawn_env(app, project_dir, "engine"));
     command = command.env("OPENCODE_CLIENT", "openwork");
     command = command.env("OPENWORK", "1");
 
-    command
+    let (rx, child) = command
         .spawn()
-        .map_err(|e| format!("Failed to start opencode: {e}"))
+        .map_err(|e| format!("Failed to start opencode: {e}"))?;
+    Ok((rx, child, limits))
 }
@@ -0,0 +1,862 @@
+//! Targeted edits to JSONC documents (`opencode.jsonc`) that keep comments, key order,
+//! trailing commas and indentation intact. The document is parsed into a small syntax tree
+//! that records byte offsets; every edit is a splice of the original text.
+
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+
+const DEFAULT_INDENT: &str = "  ";
+
+#[derive(Debug)]
+enum Node {
+    Object(ObjectNode),
+    Array(ArrayNode),
+    Scalar { start: usize, end: usize },
+}
+
+#[derive(Debug)]
+struct ObjectNode {
+    open: usize,
+    close: usize,
+    members: Vec<Member>,
+}
+
+#[derive(Debug)]
+struct Member {
+    key: String,
+    key_start: usize,
+    value: Node,
+    comma: Option<usize>,
+}
+
+#[derive(Debug)]
+struct ArrayNode {
+    open: usize,
+    close: usize,
+    items: Vec<Item>,
+}
+
+#[derive(Debug)]
+struct Item {
+    value: Node,
+    comma: Option<usize>,
+}
+
+impl Node {
+    fn start(&self) -> usize {
+        match self {
+            Node::Object(object) => object.open,
+            Node::Array(array) => array.open,
+            Node::Scalar { start, .. } => *start,
+        }
+    }
+
+    fn end(&self) -> usize {
+        match self {
+            Node::Object(object) => object.close + 1,
+            Node::Array(array) => array.close + 1,
+            Node::Scalar { end, .. } => *end,
+        }
+    }
+}
+
+/// A syntax error with its 1-based position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsoncError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for JsoncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid JSONC at line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl From<JsoncError> for String {
+    fn from(error: JsoncError) -> Self {
+        error.to_string()
+    }
+}
+
+/// 1-based line and column of a byte offset.
+fn line_column(text: &str, pos: usize) -> (usize, usize) {
+    let before = &text[..pos];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            bytes: text.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn error(&self, message: &str) -> JsoncError {
+        let (line, column) = line_column(self.text, self.pos.min(self.text.len()));
+        JsoncError {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    /// Skips whitespace and comments.
+    fn skip_trivia(&mut self) -> Result<(), JsoncError> {
+        loop {
+            match self.peek() {
+                Some(b' ' | b'\t' | b'\n' | b'\r') => self.pos += 1,
+                Some(b'/') if self.bytes.get(self.pos + 1) == Some(&b'/') => {
+                    while !matches!(self.peek(), None | Some(b'\n')) {
+                        self.pos += 1;
+                    }
+                }
+                Some(b'/') if self.bytes.get(self.pos + 1) == Some(&b'*') => {
+                    let end = self.text[self.pos + 2..]
+                        .find("*/")
+                        .ok_or_else(|| self.error("unterminated comment"))?;
+                    self.pos += end + 4;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Node, JsoncError> {
+        self.skip_trivia()?;
+        match self.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => {
+                let start = self.pos;
+                self.parse_string()?;
+                Ok(Node::Scalar {
+                    start,
+                    end: self.pos,
+                })
+            }
+            Some(_) => {
+                let start = self.pos;
+                while matches!(
+                    self.peek(),
+                    Some(b'-' | b'+' | b'.' | b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z')
+                ) {
+                    self.pos += 1;
+                }
+                let literal = &self.text[start..self.pos];
+                if serde_json::from_str::<serde_json::Value>(literal).is_err() {
+                    return Err(self.error("expected a value"));
+                }
+                Ok(Node::Scalar {
+                    start,
+                    end: self.pos,
+                })
+            }
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsoncError> {
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                Some(b'\\') => self.pos += 2,
+                Some(b'"') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => self.pos += 1,
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+        serde_json::from_str(&self.text[start..self.pos]).map_err(|_| self.error("invalid string"))
+    }
+
+    /// Consumes an optional comma after an element.
+    fn parse_comma(&mut self) -> Result<Option<usize>, JsoncError> {
+        self.skip_trivia()?;
+        if self.peek() == Some(b',') {
+            self.pos += 1;
+            return Ok(Some(self.pos - 1));
+        }
+        Ok(None)
+    }
+
+    fn parse_object(&mut self) -> Result<Node, JsoncError> {
+        let open = self.pos;
+        self.pos += 1;
+        let mut members = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b'}') => break,
+                Some(b'"') => {}
+                _ => return Err(self.error("expected a quoted key or '}'")),
+            }
+            if members
+                .last()
+                .is_some_and(|member: &Member| member.comma.is_none())
+            {
+                return Err(self.error("expected ','"));
+            }
+            let key_start = self.pos;
+            let key = self.parse_string()?;
+            self.skip_trivia()?;
+            if self.peek() != Some(b':') {
+                return Err(self.error("expected ':'"));
+            }
+            self.pos += 1;
+            let value = self.parse_value()?;
+            let comma = self.parse_comma()?;
+            members.push(Member {
+                key,
+                key_start,
+                value,
+                comma,
+            });
+        }
+        let close = self.pos;
+        self.pos += 1;
+        Ok(Node::Object(ObjectNode {
+            open,
+            close,
+            members,
+        }))
+    }
+
+    fn parse_array(&mut self) -> Result<Node, JsoncError> {
+        let open = self.pos;
+        self.pos += 1;
+        let mut items: Vec<Item> = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(b']') {
+                break;
+            }
+            if items.last().is_some_and(|item| item.comma.is_none()) {
+                return Err(self.error("expected ','"));
+            }
+            let value = self.parse_value()?;
+            let comma = self.parse_comma()?;
+            items.push(Item { value, comma });
+        }
+        let close = self.pos;
+        self.pos += 1;
+        Ok(Node::Array(ArrayNode { open, close, items }))
+    }
+}
+
+fn parse(text: &str) -> Result<Node, JsoncError> {
+    let mut parser = Parser::new(text);
+    let root = parser.parse_value()?;
+    parser.skip_trivia()?;
+    if parser.pos < text.len() {
+        return Err(parser.error("unexpected content after the root value"));
+    }
+    Ok(root)
+}
+
+/// Leading whitespace of the line containing `pos`.
+fn line_indent(text: &str, pos: usize) -> &str {
+    let line_start = text[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line = &text[line_start..];
+    let width = line.len() - line.trim_start_matches([' ', '\t']).len();
+    &line[..width]
+}
+
+fn is_line_start(text: &str, pos: usize) -> bool {
+    text[..pos]
+        .rsplit('\n')
+        .next()
+        .is_some_and(|before| before.trim().is_empty())
+}
+
+fn spans_lines(text: &str, open: usize, close: usize) -> bool {
+    text[open..close].contains('\n')
+}
+
+/// Position after a comment that trails `pos` on the same line, or `pos` itself.
+fn after_trailing_comment(text: &str, pos: usize) -> usize {
+    let rest = &text[pos..];
+    let skipped = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    let rest = &rest[skipped..];
+    if rest.starts_with("//") {
+        return pos + skipped + rest.find('\n').unwrap_or(rest.len());
+    }
+    if rest.starts_with("/*") {
+        if let Some(end) = rest.find("*/") {
+            if !rest[..end].contains('\n') {
+                return pos + skipped + end + 2;
+            }
+        }
+    }
+    pos
+}
+
+fn render_value(value: &serde_json::Value, unit: &str, indent: &str, multiline: bool) -> String {
+    if !multiline {
+        return value.to_string();
+    }
+    let mut out = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(
+        &mut out,
+        PrettyFormatter::with_indent(unit.as_bytes()),
+    );
+    if value.serialize(&mut serializer).is_err() {
+        return value.to_string();
+    }
+    String::from_utf8_lossy(&out).replace('\n', &format!("\n{indent}"))
+}
+
+struct Splice {
+    at: usize,
+    remove: usize,
+    insert: String,
+}
+
+/// Escapes one JSON pointer segment (RFC 6901).
+pub fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// A JSONC document edited in place.
+#[derive(Debug, Clone)]
+pub struct JsoncDocument {
+    text: String,
+}
+
+impl JsoncDocument {
+    pub fn parse(text: &str) -> Result<Self, JsoncError> {
+        parse(text)?;
+        Ok(Self {
+            text: text.to_string(),
+        })
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    /// The document as plain JSON.
+    pub fn value(&self) -> Result<serde_json::Value, String> {
+        json5::from_str(&self.text).map_err(|e| format!("Invalid JSONC: {e}"))
+    }
+
+    pub fn get(&self, path: &[&str]) -> Option<serde_json::Value> {
+        let mut value = self.value().ok()?;
+        for key in path {
+            value = value.get(*key)?.clone();
+        }
+        Some(value)
+    }
+
+    /// Line and column of the value at a JSON pointer, or of its closest existing ancestor.
+    /// Object members point at their key.
+    pub fn locate(&self, pointer: &str) -> (usize, usize) {
+        let Ok(root) = parse(&self.text) else {
+            return (1, 1);
+        };
+        let (pos, _, _) = follow_pointer(&root, pointer);
+        line_column(&self.text, pos)
+    }
+
+    /// Replaces the value at a JSON pointer in place, leaving the rest of the text untouched.
+    /// Returns whether the pointer existed.
+    pub fn replace_at(&mut self, pointer: &str, value: &serde_json::Value) -> Result<bool, String> {
+        let root = parse(&self.text)?;
+        let unit = self.indent_unit(&root);
+        let (_, node, found) = follow_pointer(&root, pointer);
+        if !found {
+            return Ok(false);
+        }
+        let (start, end) = (node.start(), node.end());
+        let indent = line_indent(&self.text, start).to_string();
+        let splice = Splice {
+            at: start,
+            remove: end - start,
+            insert: render_value(value, &unit, &indent, spans_lines(&self.text, start, end)),
+        };
+        self.apply(vec![splice])?;
+        Ok(true)
+    }
+
+    /// Indentation step used by the document, guessed from its first nested line.
+    fn indent_unit(&self, root: &Node) -> String {
+        let first = match root {
+            Node::Object(object) => object.members.first().map(|member| member.key_start),
+            Node::Array(array) => array.items.first().map(|item| item.value.start()),
+            Node::Scalar { .. } => None,
+        };
+        first
+            .filter(|pos| is_line_start(&self.text, *pos))
+            .map(|pos| line_indent(&self.text, pos).to_string())
+            .filter(|indent| !indent.is_empty())
+            .unwrap_or_else(|| DEFAULT_INDENT.to_string())
+    }
+
+    /// Applies splices back to front. Splices at the same offset land in list order.
+    fn apply(&mut self, splices: Vec<Splice>) -> Result<(), String> {
+        let mut ordered: Vec<(usize, Splice)> = splices.into_iter().enumerate().collect();
+        ordered.sort_by(|(a_index, a), (b_index, b)| b.at.cmp(&a.at).then(b_index.cmp(a_index)));
+        let mut text = self.text.clone();
+        for (_, splice) in ordered {
+            text.replace_range(splice.at..splice.at + splice.remove, &splice.insert);
+        }
+        parse(&text)?;
+        self.text = text;
+        Ok(())
+    }
+
+    /// Splices that add `entry` as the last element of an object or array.
+    fn append_entry(
+        &self,
+        unit: &str,
+        open: usize,
+        close: usize,
+        last: Option<(&Node, Option<usize>)>,
+        entry: impl Fn(&str, bool) -> String,
+    ) -> Vec<Splice> {
+        let text = &self.text;
+        let Some((last_value, last_comma)) = last else {
+            let indent = line_indent(text, open);
+            let child = format!("{indent}{unit}");
+            let rendered = entry(&child, true);
+            return if is_line_start(text, close) {
+                let line_start = text[..close].rfind('\n').map(|i| i + 1).unwrap_or(0);
+                vec![Splice {
+                    at: line_start,
+                    remove: 0,
+                    insert: format!("{child}{rendered}\n"),
+                }]
+            } else {
+                vec![Splice {
+                    at: close,
+                    remove: 0,
+                    insert: format!("\n{child}{rendered}\n{indent}"),
+                }]
+            };
+        };
+
+        if !spans_lines(text, open, close) {
+            let rendered = entry("", false);
+            return match last_comma {
+                Some(comma) => vec![Splice {
+                    at: comma + 1,
+                    remove: 0,
+                    insert: format!(" {rendered},"),
+                }],
+                None => vec![Splice {
+                    at: last_value.end(),
+                    remove: 0,
+                    insert: format!(", {rendered}"),
+                }],
+            };
+        }
+
+        let entry_start = last_value.start();
+        let indent = line_indent(text, entry_start).to_string();
+        let rendered = entry(&indent, true);
+        match last_comma {
+            Some(comma) => vec![Splice {
+                at: after_trailing_comment(text, comma + 1),
+                remove: 0,
+                insert: format!("\n{indent}{rendered},"),
+            }],
+            None => {
+                let value_end = last_value.end();
+                vec![
+                    Splice {
+                        at: value_end,
+                        remove: 0,
+                        insert: ",".to_string(),
+                    },
+                    Splice {
+                        at: after_trailing_comment(text, value_end),
+                        remove: 0,
+                        insert: format!("\n{indent}{rendered}"),
+                    },
+                ]
+            }
+        }
+    }
+
+    /// Adds `key` to the object at `parent` unless it is already there. Missing parent objects
+    /// are created. Returns whether the key was added.
+    pub fn insert_key(
+        &mut self,
+        parent: &[&str],
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<bool, String> {
+        self.ensure_object(parent)?;
+        let root = parse(&self.text)?;
+        let unit = self.indent_unit(&root);
+        let Some(Node::Object(object)) = find(&root, parent) else {
+            return Err(format!("{} is not an object", parent.join(".")));
+        };
+        if object.members.iter().any(|member| member.key == key) {
+            return Ok(false);
+        }
+
+        let key_json = serde_json::Value::String(key.to_string()).to_string();
+        let last = object
+            .members
+            .last()
+            .map(|member| (&member.value, member.comma));
+        let splices = self.append_entry(
+            &unit,
+            object.open,
+            object.close,
+            last,
+            |indent, multiline| {
+                format!(
+                    "{key_json}: {}",
+                    render_value(value, &unit, indent, multiline)
+                )
+            },
+        );
+        self.apply(splices)?;
+        Ok(true)
+    }
+
+    /// Sets `key` on the object at `parent`, replacing an existing value in place.
+    pub fn set_key(
+        &mut self,
+        parent: &[&str],
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), String> {
+        let root = parse(&self.text)?;
+        let unit = self.indent_unit(&root);
+        let existing = match find(&root, parent) {
+            Some(Node::Object(object)) => object
+                .members
+                .iter()
+                .rev()
+                .find(|member| member.key == key)
+                .map(|member| (object, member)),
+            _ => None,
+        };
+        let Some((object, member)) = existing else {
+            self.insert_key(parent, key, value)?;
+            return Ok(());
+        };
+
+        let indent = line_indent(&self.text, member.key_start).to_string();
+        let multiline = spans_lines(&self.text, object.open, object.close);
+        let splice = Splice {
+            at: member.value.start(),
+            remove: member.value.end() - member.value.start(),
+            insert: render_value(value, &unit, &indent, multiline),
+        };
+        self.apply(vec![splice])
+    }
+
+    /// Appends `value` to the array at `path`, creating the array if the key is missing.
+    pub fn append_to_array(
+        &mut self,
+        path: &[&str],
+        value: &serde_json::Value,
+    ) -> Result<(), String> {
+        let Some((key, parent)) = path.split_last() else {
+            return Err("Array path is empty".to_string());
+        };
+        let root = parse(&self.text)?;
+        let unit = self.indent_unit(&root);
+        let array = match find(&root, path) {
+            None => {
+                self.insert_key(parent, key, &serde_json::Value::Array(vec![value.clone()]))?;
+                return Ok(());
+            }
+            Some(Node::Array(array)) => array,
+            Some(_) => return Err(format!("{} is not an array", path.join("."))),
+        };
+
+        let last = array.items.last().map(|item| (&item.value, item.comma));
+        let splices =
+            self.append_entry(&unit, array.open, array.close, last, |indent, multiline| {
+                render_value(value, &unit, indent, multiline)
+            });
+        self.apply(splices)
+    }
+
+    /// Splices that remove one object member or array item: the text from `start` through its
+    /// comma, plus a comment trailing it on the same line. `previous` is the entry before it.
+    fn remove_entry(
+        &self,
+        start: usize,
+        value: &Node,
+        comma: Option<usize>,
+        previous: Option<(&Node, Option<usize>)>,
+        multiline: bool,
+    ) -> Vec<Splice> {
+        let text = &self.text;
+        let mut splices = Vec::new();
+        let mut start = start;
+        let mut end = comma.map(|c| c + 1).unwrap_or(value.end());
+        if multiline && is_line_start(text, start) {
+            start = text[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            end = after_trailing_comment(text, end);
+            let rest = &text[end..];
+            let blank = rest.len() - rest.trim_start_matches([' ', '\t', '\r']).len();
+            if rest[blank..].starts_with('\n') {
+                end += blank + 1;
+            }
+        } else if comma.is_some() {
+            let rest = &text[end..];
+            end += rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        }
+
+        if comma.is_none() {
+            // The removed entry was last without a trailing comma; the new last one must not
+            // keep a comma either, or the file would gain a trailing comma it didn't have.
+            if let Some((previous_value, Some(previous_comma))) = previous {
+                if multiline {
+                    splices.push(Splice {
+                        at: previous_comma,
+                        remove: 1,
+                        insert: String::new(),
+                    });
+                } else {
+                    start = previous_value.end();
+                }
+            }
+        }
+        splices.push(Splice {
+            at: start,
+            remove: end - start,
+            insert: String::new(),
+        });
+        splices
+    }
+
+    /// Removes `key` from the object at `parent`, along with its comma and any comment on the
+    /// same line. Returns whether the key existed.
+    pub fn remove_key(&mut self, parent: &[&str], key: &str) -> Result<bool, String> {
+        let root = parse(&self.text)?;
+        let Some(Node::Object(object)) = find(&root, parent) else {
+            return Ok(false);
+        };
+        let Some(index) = object.members.iter().rposition(|member| member.key == key) else {
+            return Ok(false);
+        };
+        let member = &object.members[index];
+        let previous = index
+            .checked_sub(1)
+            .map(|i| (&object.members[i].value, object.members[i].comma));
+        let splices = self.remove_entry(
+            member.key_start,
+            &member.value,
+            member.comma,
+            previous,
+            spans_lines(&self.text, object.open, object.close),
+        );
+        self.apply(splices)?;
+        Ok(true)
+    }
+
+    /// Removes element `index` of the array at `path` the same way, leaving the other elements
+    /// and their comments alone. Returns whether the element existed.
+    pub fn remove_array_item(&mut self, path: &[&str], index: usize) -> Result<bool, String> {
+        let root = parse(&self.text)?;
+        let Some(Node::Array(array)) = find(&root, path) else {
+            return Ok(false);
+        };
+        let Some(item) = array.items.get(index) else {
+            return Ok(false);
+        };
+        let previous = index
+            .checked_sub(1)
+            .map(|i| (&array.items[i].value, array.items[i].comma));
+        let splices = self.remove_entry(
+            item.value.start(),
+            &item.value,
+            item.comma,
+            previous,
+            spans_lines(&self.text, array.open, array.close),
+        );
+        self.apply(splices)?;
+        Ok(true)
+    }
+
+    /// Creates empty objects along `path` where keys are missing.
+    fn ensure_object(&mut self, path: &[&str]) -> Result<(), String> {
+        for depth in 0..path.len() {
+            let root = parse(&self.text)?;
+            match find(&root, &path[..=depth]) {
+                Some(Node::Object(_)) => continue,
+                Some(_) => return Err(format!("{} is not an object", path[..=depth].join("."))),
+                None => {
+                    self.insert_key(&path[..depth], path[depth], &serde_json::json!({}))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn find<'a>(node: &'a Node, path: &[&str]) -> Option<&'a Node> {
+    let Some((key, rest)) = path.split_first() else {
+        return Some(node);
+    };
+    let Node::Object(object) = node else {
+        return None;
+    };
+    let member = object
+        .members
+        .iter()
+        .rev()
+        .find(|member| member.key == *key)?;
+    find(&member.value, rest)
+}
+
+/// Follows a JSON pointer as far as it exists. Returns the position of the last node reached
+/// (an object member's key, an array item's value), the node, and whether every segment
+/// resolved.
+fn follow_pointer<'a>(root: &'a Node, pointer: &str) -> (usize, &'a Node, bool) {
+    let mut node = root;
+    let mut pos = root.start();
+    for raw in pointer.split('/').skip(1) {
+        let segment = raw.replace("~1", "/").replace("~0", "~");
+        let next = match node {
+            Node::Object(object) => object
+                .members
+                .iter()
+                .rev()
+                .find(|member| member.key == segment)
+                .map(|member| (member.key_start, &member.value)),
+            Node::Array(array) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| array.items.get(index))
+                .map(|item| (item.value.start(), &item.value)),
+            Node::Scalar { .. } => None,
+        };
+        let Some((next_pos, next_node)) = next else {
+            return (pos, node, false);
+        };
+        pos = next_pos;
+        node = next_node;
+    }
+    (pos, node, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"{
+  // Shared with the team; keep the schema first.
+  "$schema": "https://opencode.ai/config.json",
+  "plugin": [
+    "opencode-scheduler", // nightly jobs
+  ],
+  /* Servers we run locally */
+  "mcp": {
+    "docs": { "type": "local" } // internal docs
+  }
+}
+"#;
+
+    #[test]
+    fn edits_keep_comments_order_and_trailing_commas() {
+        let mut doc = JsoncDocument::parse(CONFIG).expect("parse");
+        doc.append_to_array(&["plugin"], &serde_json::json!("opencode-notify"))
+            .expect("append");
+        assert!(doc
+            .insert_key(&["mcp"], "crm", &serde_json::json!({ "type": "remote" }))
+            .expect("insert"));
+        assert!(!doc
+            .insert_key(&["mcp"], "docs", &serde_json::json!({}))
+            .expect("insert existing"));
+
+        assert_eq!(
+            doc.text(),
+            r#"{
+  // Shared with the team; keep the schema first.
+  "$schema": "https://opencode.ai/config.json",
+  "plugin": [
+    "opencode-scheduler", // nightly jobs
+    "opencode-notify",
+  ],
+  /* Servers we run locally */
+  "mcp": {
+    "docs": { "type": "local" }, // internal docs
+    "crm": {
+      "type": "remote"
+    }
+  }
+}
+"#
+        );
+
+        assert!(doc.remove_key(&["mcp"], "crm").expect("remove"));
+        assert!(doc.remove_key(&[], "plugin").expect("remove plugin"));
+        assert_eq!(
+            doc.text(),
+            r#"{
+  // Shared with the team; keep the schema first.
+  "$schema": "https://opencode.ai/config.json",
+  /* Servers we run locally */
+  "mcp": {
+    "docs": { "type": "local" } // internal docs
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn creates_missing_containers_and_handles_inline_values() {
+        let mut doc = JsoncDocument::parse("{}").expect("parse");
+        doc.append_to_array(&["plugin"], &serde_json::json!("a"))
+            .expect("create array");
+        doc.insert_key(&["mcp", "nested"], "x", &serde_json::json!(1))
+            .expect("create parents");
+        assert_eq!(
+            doc.value().expect("value"),
+            serde_json::json!({ "plugin": ["a"], "mcp": { "nested": { "x": 1 } } })
+        );
+
+        let mut inline =
+            JsoncDocument::parse(r#"{ "plugin": ["a"], "model": "x" }"#).expect("parse");
+        inline
+            .append_to_array(&["plugin"], &serde_json::json!("b"))
+            .expect("append inline");
+        inline
+            .set_key(&[], "model", &serde_json::json!("y"))
+            .expect("set");
+        inline.remove_key(&[], "model").expect("remove last");
+        assert_eq!(inline.text(), r#"{ "plugin": ["a", "b"] }"#);
+
+        assert!(JsoncDocument::parse(r#"{ "a": 1 "b": 2 }"#).is_err());
+    }
+}
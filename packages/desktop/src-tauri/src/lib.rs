@@ -1,15 +1,21 @@
 mod commands;
 mod config;
+mod crypto;
 mod engine;
 mod fs;
+mod jsonc;
+mod limits;
+mod mcp_probe;
 mod opkg;
 mod openwork_server;
 mod owpenbot;
 mod paths;
 mod platform;
+mod settings;
 mod types;
 mod updater;
 mod utils;
+mod vault;
 mod workspace;
 
 pub use types::*;
@@ -17,8 +23,12 @@ pub use types::*;
 use commands::command_files::{
     opencode_command_delete, opencode_command_list, opencode_command_write,
 };
-use commands::config::{read_opencode_config, write_opencode_config};
+use commands::config::{
+    provider_auth_status, read_opencode_config, resolve_effective_opencode_config,
+    validate_opencode_config, write_opencode_config,
+};
 use commands::engine::{engine_doctor, engine_info, engine_install, engine_start, engine_stop};
+use commands::mcp::{mcp_add, mcp_list, mcp_remove, mcp_test, mcp_update};
 use commands::misc::{opencode_mcp_auth, reset_opencode_cache, reset_openwork_state};
 use commands::openwork_server::openwork_server_info;
 use commands::opkg::{import_skill, opkg_install};
@@ -26,16 +36,37 @@ use commands::owpenbot::{
     owpenbot_config_set, owpenbot_info, owpenbot_pairing_approve, owpenbot_pairing_deny,
     owpenbot_pairing_list, owpenbot_qr, owpenbot_start, owpenbot_status, owpenbot_stop,
 };
+use commands::plugins::{plugin_add, plugin_list, plugin_pin, plugin_remove};
+use commands::profiles::{
+    opencode_profile_copy_credentials, opencode_profile_create, opencode_profile_delete,
+    opencode_profile_list,
+};
+use commands::runtime::{node_runtime_install, node_runtime_remove, node_runtime_status};
+use commands::settings::{app_settings_get, app_settings_set};
 use commands::skills::{install_skill_template, list_local_skills, uninstall_skill};
 use commands::updater::updater_environment;
+use commands::vault::{
+    vault_lock, vault_secret_delete, vault_secret_get, vault_secret_list, vault_secret_set,
+    vault_set_passphrase, vault_status, vault_unlock,
+};
 use commands::workspace::{
-    workspace_add_authorized_root, workspace_bootstrap, workspace_create, workspace_create_remote,
-    workspace_export_config, workspace_forget, workspace_import_config, workspace_openwork_read,
-    workspace_openwork_write, workspace_set_active, workspace_update_remote,
+    workspace_add_authorized_root, workspace_archive_redactions, workspace_bootstrap,
+    workspace_create, workspace_create_remote, workspace_export_config,
+    workspace_export_connections, workspace_forget, workspace_import_config,
+    workspace_import_connections, workspace_import_preview, workspace_launcher_set,
+    workspace_limits_set, workspace_move, workspace_openwork_read, workspace_openwork_write,
+    workspace_preset_upgrade_apply, workspace_preset_upgrade_plan, workspace_presets_list,
+    workspace_profile_set, workspace_remote_token_get, workspace_remote_token_set,
+    workspace_sandbox_set, workspace_save_as_template, workspace_set_active,
+    workspace_update_remote,
+};
+use commands::workspace_env::{
+    workspace_env_files_set, workspace_env_list, workspace_env_remove, workspace_env_set,
 };
 use engine::manager::EngineManager;
 use openwork_server::manager::OpenworkServerManager;
 use owpenbot::manager::OwpenbotManager;
+use vault::VaultSession;
 use workspace::watch::WorkspaceWatchState;
 
 pub fn run() {
@@ -54,6 +85,7 @@ pub fn run() {
         .manage(OpenworkServerManager::default())
         .manage(OwpenbotManager::default())
         .manage(WorkspaceWatchState::default())
+        .manage(VaultSession::default())
         .invoke_handler(tauri::generate_handler![
             engine_start,
             engine_stop,
@@ -70,7 +102,13 @@ pub fn run() {
             owpenbot_pairing_list,
             owpenbot_pairing_approve,
             owpenbot_pairing_deny,
+            app_settings_get,
+            app_settings_set,
             workspace_bootstrap,
+            workspace_presets_list,
+            workspace_save_as_template,
+            workspace_preset_upgrade_plan,
+            workspace_preset_upgrade_apply,
             workspace_set_active,
             workspace_create,
             workspace_create_remote,
@@ -79,6 +117,11 @@ pub fn run() {
             workspace_add_authorized_root,
             workspace_export_config,
             workspace_import_config,
+            workspace_export_connections,
+            workspace_import_connections,
+            workspace_archive_redactions,
+            workspace_import_preview,
+            workspace_move,
             opencode_command_list,
             opencode_command_write,
             opencode_command_delete,
@@ -91,10 +134,47 @@ pub fn run() {
             uninstall_skill,
             read_opencode_config,
             write_opencode_config,
+            validate_opencode_config,
+            resolve_effective_opencode_config,
             updater_environment,
             reset_openwork_state,
             reset_opencode_cache,
-            opencode_mcp_auth
+            opencode_mcp_auth,
+            mcp_list,
+            mcp_add,
+            mcp_update,
+            mcp_remove,
+            mcp_test,
+            plugin_list,
+            plugin_add,
+            plugin_remove,
+            plugin_pin,
+            node_runtime_status,
+            node_runtime_install,
+            node_runtime_remove,
+            workspace_env_list,
+            workspace_env_set,
+            workspace_env_remove,
+            workspace_env_files_set,
+            vault_status,
+            vault_unlock,
+            vault_lock,
+            vault_set_passphrase,
+            vault_secret_list,
+            vault_secret_get,
+            vault_secret_set,
+            vault_secret_delete,
+            workspace_remote_token_set,
+            workspace_remote_token_get,
+            provider_auth_status,
+            opencode_profile_list,
+            opencode_profile_create,
+            opencode_profile_copy_credentials,
+            opencode_profile_delete,
+            workspace_profile_set,
+            workspace_launcher_set,
+            workspace_sandbox_set,
+            workspace_limits_set
         ])
         .run(tauri::generate_context!())
         .expect("error while running OpenWork");
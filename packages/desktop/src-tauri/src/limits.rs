@@ -0,0 +1,553 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::Manager;
+use tauri_plugin_shell::process::{Command, TerminatedPayload};
+use tauri_plugin_shell::ShellExt;
+
+use crate::paths::resolve_in_path;
+use crate::types::ResourceLimits;
+use crate::workspace::state::load_workspace_state;
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+const CGROUP_PREFIX: &str = "openwork-";
+
+/// Upper bounds for configured limits, well past any real machine but far from overflowing.
+const MAX_MEMORY_MB: u64 = 1024 * 1024;
+const MAX_OPEN_FILES: u64 = 1024 * 1024;
+const MAX_PROCESSES: u64 = 4 * 1024 * 1024;
+
+const SIGABRT: i32 = 6;
+const SIGKILL: i32 = 9;
+
+/// Only the end of the output is searched; an earlier allocation error may have been handled.
+const OUTPUT_TAIL_BYTES: usize = 2048;
+
+/// What a process printed when a data-segment limit made an allocation fail.
+const OUT_OF_MEMORY_MARKERS: &[&str] = &[
+    "out of memory",
+    "cannot allocate memory",
+    "enomem",
+    "bad_alloc",
+    "allocation failed",
+];
+
+/// How limits were enforced for one process.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LimitMechanism {
+    /// A cgroup v2 child of a delegated sub-tree, plus rlimits for open files.
+    Cgroup,
+    /// `prlimit`/`nice` only.
+    Rlimit,
+    /// Nothing could be applied on this platform.
+    None,
+}
+
+/// The limits a running process was started with. Reported in `EngineInfo`.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessLimits {
+    pub limits: ResourceLimits,
+    pub mechanism: LimitMechanism,
+    pub cgroup: Option<String>,
+    /// Limits that were requested but couldn't be applied, and why.
+    pub notes: Vec<String>,
+    /// The cgroup's `oom_kill` count when the process started.
+    #[serde(skip)]
+    pub oom_kills_at_start: u64,
+}
+
+impl ProcessLimits {
+    /// Why a process that just exited was killed, if it hit its memory limit. Only abnormal
+    /// exits count; then the cgroup's OOM counter must have moved since the start, or for a
+    /// data-segment rlimit, the end of `output` must show a failed allocation.
+    pub fn kill_reason(&self, exit: &TerminatedPayload, output: &str) -> Option<String> {
+        let memory_mb = self.limits.memory_mb?;
+        let abnormal = exit.code.is_none() || matches!(exit.signal, Some(SIGKILL | SIGABRT));
+        if !abnormal {
+            return None;
+        }
+        let exceeded = match (&self.mechanism, &self.cgroup) {
+            (LimitMechanism::Cgroup, Some(dir)) => {
+                oom_kills(Path::new(dir)) > self.oom_kills_at_start
+            }
+            (LimitMechanism::Rlimit, _) => {
+                let output = output_tail(output).to_ascii_lowercase();
+                OUT_OF_MEMORY_MARKERS
+                    .iter()
+                    .any(|marker| output.contains(marker))
+            }
+            _ => false,
+        };
+        exceeded.then(|| format!("killed for exceeding memory limit ({memory_mb} MB)"))
+    }
+}
+
+fn output_tail(output: &str) -> &str {
+    let mut start = output.len().saturating_sub(OUTPUT_TAIL_BYTES);
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    &output[start..]
+}
+
+pub fn validate_limits(limits: &ResourceLimits) -> Result<(), String> {
+    if limits
+        .memory_mb
+        .is_some_and(|mb| !(64..=MAX_MEMORY_MB).contains(&mb))
+    {
+        return Err(format!("memoryMb must be between 64 and {MAX_MEMORY_MB}"));
+    }
+    if limits.nice.is_some_and(|nice| !(0..=19).contains(&nice)) {
+        return Err("nice must be between 0 and 19; raising priority needs root".to_string());
+    }
+    if limits
+        .open_files
+        .is_some_and(|files| !(64..=MAX_OPEN_FILES).contains(&files))
+    {
+        return Err(format!("openFiles must be between 64 and {MAX_OPEN_FILES}"));
+    }
+    if limits
+        .max_processes
+        .is_some_and(|count| !(16..=MAX_PROCESSES).contains(&count))
+    {
+        return Err(format!(
+            "maxProcesses must be between 16 and {MAX_PROCESSES}"
+        ));
+    }
+    Ok(())
+}
+
+fn memory_bytes(mb: u64) -> Result<u64, String> {
+    mb.checked_mul(1024 * 1024)
+        .ok_or_else(|| format!("memoryMb {mb} is too large"))
+}
+
+fn oom_kills(cgroup: &Path) -> u64 {
+    fs::read_to_string(cgroup.join("memory.events"))
+        .ok()
+        .and_then(|events| {
+            events.lines().find_map(|line| {
+                let (key, value) = line.split_once(' ')?;
+                (key == "oom_kill").then(|| value.trim().parse().ok())?
+            })
+        })
+        .unwrap_or(0)
+}
+
+/// Wrapper arguments, outermost first: join the cgroup, then `nice`, then `prlimit`. Each
+/// step `exec`s the next, so the supervised pid stays the real process.
+pub fn limit_prefix(
+    limits: &ResourceLimits,
+    cgroup_procs: Option<&Path>,
+    prlimit: Option<&Path>,
+    nice: Option<&Path>,
+) -> (Vec<String>, Vec<String>) {
+    let mut prefix = Vec::new();
+    let mut notes = Vec::new();
+
+    if let Some(procs) = cgroup_procs {
+        prefix.extend([
+            "sh".to_string(),
+            "-c".to_string(),
+            "echo $$ > \"$0\" && exec \"$@\"".to_string(),
+            procs.to_string_lossy().to_string(),
+        ]);
+    }
+
+    if let Some(level) = limits.nice.filter(|level| *level > 0) {
+        match nice {
+            Some(nice) => prefix.extend([
+                nice.to_string_lossy().to_string(),
+                "-n".to_string(),
+                level.to_string(),
+            ]),
+            None => notes.push("nice not found; CPU priority not lowered".to_string()),
+        }
+    }
+
+    let mut rlimits = Vec::new();
+    if let Some(files) = limits.open_files {
+        rlimits.push(format!("--nofile={files}:{files}"));
+    }
+    if cgroup_procs.is_none() {
+        // RLIMIT_DATA rather than RLIMIT_AS: runtimes like V8 reserve far more address space
+        // than they ever touch, so an address-space cap fails them long before the limit.
+        match limits.memory_mb.map(memory_bytes) {
+            Some(Ok(bytes)) => rlimits.push(format!("--data={bytes}")),
+            Some(Err(error)) => notes.push(format!("{error}; not applied")),
+            None => {}
+        }
+        if limits.max_processes.is_some() {
+            // RLIMIT_NPROC counts every process the user owns, not just this tree.
+            notes.push("maxProcesses needs a delegated cgroup; not applied".to_string());
+        }
+    }
+    if !rlimits.is_empty() {
+        match prlimit {
+            Some(prlimit) => {
+                prefix.push(prlimit.to_string_lossy().to_string());
+                prefix.extend(rlimits);
+                prefix.push("--".to_string());
+            }
+            None => notes.push(format!(
+                "prlimit not found; {} not applied",
+                rlimits.join(" ")
+            )),
+        }
+    }
+
+    (prefix, notes)
+}
+
+/// The cgroup this app runs in, from `/proc/self/cgroup`.
+fn own_cgroup() -> Option<PathBuf> {
+    let raw = fs::read_to_string("/proc/self/cgroup").ok()?;
+    let path = raw.lines().find_map(|line| line.strip_prefix("0::"))?;
+    Some(Path::new(CGROUP_ROOT).join(path.trim().trim_start_matches('/')))
+}
+
+/// Drops cgroups left by earlier processes. Only empty ones can be removed, so running
+/// processes keep theirs.
+fn remove_stale_cgroups(parent: &Path) {
+    let Ok(entries) = fs::read_dir(parent) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(CGROUP_PREFIX)
+        {
+            let _ = fs::remove_dir(entry.path());
+        }
+    }
+}
+
+fn write_cgroup_value(dir: &Path, file: &str, value: &str) -> Result<(), String> {
+    let path = dir.join(file);
+    fs::write(&path, value).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+fn write_cgroup_limits(dir: &Path, limits: &ResourceLimits) -> Result<(), String> {
+    if let Some(mb) = limits.memory_mb {
+        write_cgroup_value(dir, "memory.max", &memory_bytes(mb)?.to_string())?;
+        // Without this the kernel swaps instead of enforcing the limit.
+        let _ = write_cgroup_value(dir, "memory.swap.max", "0");
+    }
+    if let Some(count) = limits.max_processes {
+        write_cgroup_value(dir, "pids.max", &count.to_string())?;
+    }
+    Ok(())
+}
+
+/// Creates a child cgroup with the memory and pids limits, next to or under this app's
+/// cgroup, wherever the needed controllers are delegated.
+fn prepare_cgroup(limits: &ResourceLimits, label: &str) -> Result<PathBuf, String> {
+    let mut needed = Vec::new();
+    if limits.memory_mb.is_some() {
+        needed.push("memory");
+    }
+    if limits.max_processes.is_some() {
+        needed.push("pids");
+    }
+    let own = own_cgroup().ok_or_else(|| "cgroup v2 is not mounted".to_string())?;
+    let parents = [Some(own.clone()), own.parent().map(Path::to_path_buf)];
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    for parent in parents.into_iter().flatten() {
+        if !parent.starts_with(CGROUP_ROOT) || parent == Path::new(CGROUP_ROOT) {
+            continue;
+        }
+        remove_stale_cgroups(&parent);
+        let dir = parent.join(format!("{CGROUP_PREFIX}{label}-{nanos}"));
+        if fs::create_dir(&dir).is_err() {
+            continue;
+        }
+        let controllers = fs::read_to_string(dir.join("cgroup.controllers")).unwrap_or_default();
+        let delegated = needed
+            .iter()
+            .all(|name| controllers.split_whitespace().any(|c| c == *name));
+        let applied = if delegated {
+            write_cgroup_limits(&dir, limits)
+        } else {
+            Err("controllers not delegated".to_string())
+        };
+        match applied {
+            Ok(()) => return Ok(dir),
+            Err(_) => {
+                let _ = fs::remove_dir(&dir);
+            }
+        }
+    }
+    Err(format!(
+        "no delegated cgroup with the {} controller(s)",
+        needed.join(" and ")
+    ))
+}
+
+/// Wraps `program` so it starts with `limits` applied. Falls back from a cgroup to rlimits
+/// and records what couldn't be enforced instead of failing the spawn.
+pub fn apply_limits(
+    limits: &ResourceLimits,
+    label: &str,
+    program: String,
+    args: Vec<String>,
+) -> (String, Vec<String>, ProcessLimits) {
+    let mut notes = Vec::new();
+    if cfg!(windows) {
+        notes.push("Resource limits aren't supported on Windows".to_string());
+        let report = ProcessLimits {
+            limits: limits.clone(),
+            mechanism: LimitMechanism::None,
+            cgroup: None,
+            notes,
+            oom_kills_at_start: 0,
+        };
+        return (program, args, report);
+    }
+
+    let linux = cfg!(target_os = "linux");
+    let wants_cgroup = limits.memory_mb.is_some() || limits.max_processes.is_some();
+    let cgroup = if linux && wants_cgroup {
+        prepare_cgroup(limits, label)
+            .map_err(|error| notes.push(format!("Using rlimits: {error}")))
+            .ok()
+    } else {
+        None
+    };
+    let prlimit = if linux {
+        resolve_in_path("prlimit")
+    } else {
+        None
+    };
+    if !linux && (limits.memory_mb.is_some() || limits.open_files.is_some()) {
+        notes.push("Memory and open-file limits are only enforced on Linux".to_string());
+    }
+    let (prefix, prefix_notes) = limit_prefix(
+        limits,
+        cgroup
+            .as_ref()
+            .map(|dir| dir.join("cgroup.procs"))
+            .as_deref(),
+        prlimit.as_deref(),
+        resolve_in_path("nice").as_deref(),
+    );
+    notes.extend(prefix_notes);
+
+    let report = ProcessLimits {
+        limits: limits.clone(),
+        mechanism: if cgroup.is_some() {
+            LimitMechanism::Cgroup
+        } else if prefix.is_empty() {
+            LimitMechanism::None
+        } else {
+            LimitMechanism::Rlimit
+        },
+        oom_kills_at_start: cgroup.as_deref().map(oom_kills).unwrap_or(0),
+        cgroup: cgroup.map(|dir| dir.to_string_lossy().to_string()),
+        notes,
+    };
+    let Some((wrapper, wrapper_args)) = prefix.split_first() else {
+        return (program, args, report);
+    };
+    let mut wrapped = wrapper_args.to_vec();
+    wrapped.push(program);
+    wrapped.extend(args);
+    (wrapper.clone(), wrapped, report)
+}
+
+/// Limits configured for the local workspace at `workspace_path`.
+pub fn workspace_limits(app: &tauri::AppHandle, workspace_path: &str) -> Option<ResourceLimits> {
+    let workspace_path = workspace_path.trim();
+    load_workspace_state(app)
+        .ok()?
+        .workspaces
+        .into_iter()
+        .find(|workspace| workspace.path == workspace_path)?
+        .resource_limits
+        .filter(|limits| !limits.is_empty())
+}
+
+fn sidecar_command(app: &tauri::AppHandle, name: &str) -> Command {
+    app.shell()
+        .sidecar(name)
+        .unwrap_or_else(|_| app.shell().command(name))
+}
+
+/// Command for the bundled sidecar `name`, behind the workspace's limits when it has any.
+/// A wrapped sidecar runs from next to the app binary, or from PATH; when nothing could be
+/// applied it starts like an unlimited one.
+pub fn limited_sidecar(
+    app: &tauri::AppHandle,
+    workspace_path: &str,
+    name: &str,
+    args: Vec<String>,
+) -> (Command, Option<ProcessLimits>) {
+    let Some(limits) = workspace_limits(app, workspace_path) else {
+        return (sidecar_command(app, name).args(args), None);
+    };
+
+    let executable = if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    };
+    let program = tauri::process::current_binary(&app.env())
+        .ok()
+        .and_then(|path| path.parent().map(|dir| dir.join(&executable)))
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or(executable);
+    let (program, args, applied) = apply_limits(&limits, name, program, args);
+    println!(
+        "[{name}] resource limits ({:?}): {limits:?}",
+        applied.mechanism
+    );
+    let command = match applied.mechanism {
+        LimitMechanism::None => sidecar_command(app, name),
+        _ => app.shell().command(program),
+    };
+    (command.args(args), Some(applied))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::TempDir;
+
+    fn limits() -> ResourceLimits {
+        ResourceLimits {
+            memory_mb: Some(512),
+            nice: Some(10),
+            open_files: Some(4096),
+            max_processes: Some(256),
+        }
+    }
+
+    fn rlimit_report() -> ProcessLimits {
+        ProcessLimits {
+            limits: limits(),
+            mechanism: LimitMechanism::Rlimit,
+            cgroup: None,
+            notes: Vec::new(),
+            oom_kills_at_start: 0,
+        }
+    }
+
+    const ABORTED: TerminatedPayload = TerminatedPayload {
+        code: None,
+        signal: Some(SIGABRT),
+    };
+    const EXITED: TerminatedPayload = TerminatedPayload {
+        code: Some(1),
+        signal: None,
+    };
+    const KILLED: TerminatedPayload = TerminatedPayload {
+        code: None,
+        signal: Some(SIGKILL),
+    };
+    const HEAP_ERROR: &str =
+        "FATAL ERROR: Reached heap limit Allocation failed - JavaScript heap out of memory";
+
+    #[test]
+    fn wraps_with_nice_and_prlimit_without_a_cgroup() {
+        let prlimit = Path::new("/usr/bin/prlimit");
+        let nice = Path::new("/usr/bin/nice");
+        let (prefix, notes) = limit_prefix(&limits(), None, Some(prlimit), Some(nice));
+        assert_eq!(
+            prefix,
+            [
+                "/usr/bin/nice",
+                "-n",
+                "10",
+                "/usr/bin/prlimit",
+                "--nofile=4096:4096",
+                "--data=536870912",
+                "--",
+            ]
+        );
+        assert_eq!(
+            notes,
+            ["maxProcesses needs a delegated cgroup; not applied"]
+        );
+    }
+
+    #[test]
+    fn joins_the_cgroup_first_and_notes_missing_tools() {
+        let nice = Path::new("/usr/bin/nice");
+        let procs = Path::new("/sys/fs/cgroup/app/openwork-engine-1/cgroup.procs");
+        let (prefix, notes) = limit_prefix(&limits(), Some(procs), None, Some(nice));
+        assert_eq!(
+            prefix[..4],
+            [
+                "sh",
+                "-c",
+                "echo $$ > \"$0\" && exec \"$@\"",
+                &procs.to_string_lossy()
+            ]
+        );
+        assert_eq!(prefix[4..], ["/usr/bin/nice", "-n", "10"]);
+        assert_eq!(notes, ["prlimit not found; --nofile=4096:4096 not applied"]);
+    }
+
+    #[test]
+    fn rejects_negative_nice_and_overflowing_memory() {
+        assert!(validate_limits(&limits()).is_ok());
+        assert!(validate_limits(&ResourceLimits {
+            nice: Some(-5),
+            ..limits()
+        })
+        .is_err());
+        assert!(validate_limits(&ResourceLimits {
+            memory_mb: Some(u64::MAX),
+            ..limits()
+        })
+        .is_err());
+        assert!(memory_bytes(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn blames_the_memory_limit_for_a_heap_abort() {
+        let rlimit = rlimit_report();
+        assert_eq!(
+            rlimit.kill_reason(&ABORTED, HEAP_ERROR).as_deref(),
+            Some("killed for exceeding memory limit (512 MB)")
+        );
+        assert_eq!(rlimit.kill_reason(&EXITED, HEAP_ERROR), None);
+        assert_eq!(rlimit.kill_reason(&ABORTED, "listening on 4096"), None);
+        // A handled allocation error long before the crash doesn't explain it.
+        let earlier = format!("{HEAP_ERROR}\n{}", "retrying\n".repeat(1000));
+        assert_eq!(rlimit.kill_reason(&ABORTED, &earlier), None);
+    }
+
+    #[test]
+    fn blames_the_cgroup_only_for_new_oom_kills() {
+        let dir = TempDir::new("limits");
+        let cgroup = ProcessLimits {
+            mechanism: LimitMechanism::Cgroup,
+            cgroup: Some(dir.to_string_lossy().to_string()),
+            oom_kills_at_start: 1,
+            ..rlimit_report()
+        };
+        fs::write(
+            dir.join("memory.events"),
+            "low 0\nhigh 0\nmax 3\noom 1\noom_kill 1\n",
+        )
+        .expect("events");
+        assert_eq!(cgroup.kill_reason(&KILLED, "Killed"), None);
+        fs::write(
+            dir.join("memory.events"),
+            "low 0\nhigh 0\nmax 9\noom 2\noom_kill 2\n",
+        )
+        .expect("events");
+        assert!(cgroup.kill_reason(&KILLED, "").is_some());
+        assert_eq!(cgroup.kill_reason(&EXITED, ""), None);
+    }
+}
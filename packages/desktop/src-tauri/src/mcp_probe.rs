@@ -0,0 +1,423 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::paths::resolve_command;
+use crate::platform::{command_for_program, configure_hidden};
+
+/// Protocol version offered in `initialize`; servers answer with the one they speak.
+const MCP_PROTOCOL_VERSION: &str = "2025-06-18";
+/// `tools/list` pages fetched before giving up on a server that never stops paginating.
+const MAX_TOOL_PAGES: usize = 20;
+const STDERR_LIMIT: usize = 8 * 1024;
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct McpTool {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Outcome of launching a local MCP server and listing its tools.
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct McpTestResult {
+    pub ok: bool,
+    /// Step that failed: `spawn`, `initialize` or `tools/list`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<String>,
+    pub tools: Vec<McpTool>,
+    /// Tail of what the server wrote to stderr.
+    pub stderr: String,
+    pub elapsed_ms: u64,
+}
+
+struct Session {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    stderr: Arc<Mutex<String>>,
+    stderr_reader: JoinHandle<()>,
+    deadline: Instant,
+}
+
+impl Session {
+    fn send(&mut self, message: &Value) -> Result<(), String> {
+        let mut line = message.to_string();
+        line.push('\n');
+        let written = self
+            .stdin
+            .write_all(line.as_bytes())
+            .and_then(|_| self.stdin.flush());
+        match written {
+            Ok(()) => Ok(()),
+            // A server that dies on startup closes stdin first; report the exit, not the pipe.
+            Err(_) if matches!(self.child.try_wait(), Ok(Some(_))) => Err(self.exited()),
+            Err(e) => Err(format!("Failed to write to server: {e}")),
+        }
+    }
+
+    /// Describes a server that closed stdout. It may only have closed the pipe, so it gets
+    /// until the deadline to exit before it is killed.
+    fn exited(&mut self) -> String {
+        let status = loop {
+            match self.child.try_wait() {
+                Ok(Some(status)) => break status.to_string(),
+                Ok(None) if Instant::now() < self.deadline => {
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Ok(None) => {
+                    let _ = self.child.kill();
+                    self.settle_stderr();
+                    return "Server closed its output without exiting".to_string();
+                }
+                Err(_) => break "unknown status".to_string(),
+            }
+        };
+        self.settle_stderr();
+        format!("Server exited before responding ({status})")
+    }
+
+    /// Waits for the response to request `id`, skipping notifications, server requests and
+    /// log lines written to stdout.
+    fn response(&mut self, id: u64) -> Result<Value, String> {
+        loop {
+            let remaining = self.deadline.saturating_duration_since(Instant::now());
+            let line = match self.lines.recv_timeout(remaining) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err("Timed out waiting for the server to respond".to_string());
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err(self.exited()),
+            };
+            let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            if message.get("id").and_then(Value::as_u64) != Some(id)
+                || message.get("method").is_some()
+            {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                let code = error.get("code").and_then(Value::as_i64).unwrap_or(0);
+                let text = error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown error");
+                return Err(format!("Server returned error {code}: {text}"));
+            }
+            return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    fn request(&mut self, id: u64, method: &str, params: Value) -> Result<Value, String> {
+        self.send(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))?;
+        self.response(id)
+    }
+
+    /// Gives the stderr reader a moment to catch up after the server exited.
+    fn settle_stderr(&self) {
+        let until = Instant::now() + Duration::from_millis(250);
+        while !self.stderr_reader.is_finished() && Instant::now() < until {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn stderr_tail(&self) -> String {
+        self.stderr
+            .lock()
+            .map(|stderr| stderr.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn resolve_program(program: &str) -> PathBuf {
+    let path = Path::new(program);
+    if path.components().count() > 1 {
+        return path.to_path_buf();
+    }
+    resolve_command(program).unwrap_or_else(|| path.to_path_buf())
+}
+
+fn spawn(
+    command: &[String],
+    environment: &BTreeMap<String, String>,
+    cwd: Option<&Path>,
+    timeout: Duration,
+) -> Result<Session, String> {
+    let Some((program, args)) = command.split_first() else {
+        return Err("Server command is empty".to_string());
+    };
+    let mut process = command_for_program(&resolve_program(program));
+    process
+        .args(args)
+        .envs(environment)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(cwd) = cwd {
+        process.current_dir(cwd);
+    }
+    configure_hidden(&mut process);
+
+    let mut child = process
+        .spawn()
+        .map_err(|e| format!("Failed to start {program}: {e}"))?;
+    let stdin = child.stdin.take().ok_or("Server stdin unavailable")?;
+    let stdout = child.stdout.take().ok_or("Server stdout unavailable")?;
+    let stderr_pipe = child.stderr.take().ok_or("Server stderr unavailable")?;
+
+    let (sender, lines) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let stderr = Arc::new(Mutex::new(String::new()));
+    let sink = stderr.clone();
+    let stderr_reader = thread::spawn(move || {
+        let mut reader = BufReader::new(stderr_pipe);
+        let mut buf = [0u8; 4096];
+        while let Ok(read) = reader.read(&mut buf) {
+            if read == 0 {
+                break;
+            }
+            let Ok(mut stderr) = sink.lock() else { break };
+            stderr.push_str(&String::from_utf8_lossy(&buf[..read]));
+            if stderr.len() > STDERR_LIMIT {
+                let mut cut = stderr.len() - STDERR_LIMIT;
+                while !stderr.is_char_boundary(cut) {
+                    cut += 1;
+                }
+                stderr.drain(..cut);
+            }
+        }
+    });
+
+    Ok(Session {
+        child,
+        stdin,
+        lines,
+        stderr,
+        stderr_reader,
+        deadline: Instant::now() + timeout,
+    })
+}
+
+fn list_tools(session: &mut Session) -> Result<Vec<McpTool>, String> {
+    let mut tools = Vec::new();
+    let mut cursor: Option<String> = None;
+    for page in 0..MAX_TOOL_PAGES {
+        let params = match &cursor {
+            Some(cursor) => json!({ "cursor": cursor }),
+            None => json!({}),
+        };
+        let result = session.request(2 + page as u64, "tools/list", params)?;
+        let Some(items) = result.get("tools").and_then(Value::as_array) else {
+            return Err("Response has no tools array".to_string());
+        };
+        for item in items {
+            let Some(name) = item.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            tools.push(McpTool {
+                name: name.to_string(),
+                description: item
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            });
+        }
+        cursor = result
+            .get("nextCursor")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        if cursor.is_none() {
+            return Ok(tools);
+        }
+    }
+    Err(format!(
+        "Server kept paginating after {MAX_TOOL_PAGES} pages"
+    ))
+}
+
+/// Launches a local MCP server, performs the `initialize` handshake over stdio and lists its
+/// tools. The server is killed afterwards; `timeout` covers the whole exchange.
+pub fn test_local_mcp_server(
+    command: &[String],
+    environment: &BTreeMap<String, String>,
+    cwd: Option<&Path>,
+    timeout: Duration,
+) -> McpTestResult {
+    let started = Instant::now();
+    let mut result = McpTestResult::default();
+    let fail = |mut result: McpTestResult, stage: &str, error: String| {
+        result.stage = Some(stage.to_string());
+        result.error = Some(error);
+        result.elapsed_ms = started.elapsed().as_millis() as u64;
+        result
+    };
+
+    let mut session = match spawn(command, environment, cwd, timeout) {
+        Ok(session) => session,
+        Err(error) => return fail(result, "spawn", error),
+    };
+
+    let initialize = session.request(
+        1,
+        "initialize",
+        json!({
+            "protocolVersion": MCP_PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "openwork", "version": env!("CARGO_PKG_VERSION") },
+        }),
+    );
+    let info = match initialize {
+        Ok(info) => info,
+        Err(error) => {
+            result.stderr = session.stderr_tail();
+            return fail(result, "initialize", error);
+        }
+    };
+    let text = |pointer: &str| {
+        info.pointer(pointer)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    result.server_name = text("/serverInfo/name");
+    result.server_version = text("/serverInfo/version");
+    result.protocol_version = text("/protocolVersion");
+
+    let tools = session
+        .send(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+        .and_then(|_| list_tools(&mut session));
+    result.stderr = session.stderr_tail();
+    match tools {
+        Ok(tools) => {
+            result.ok = true;
+            result.tools = tools;
+            result.elapsed_ms = started.elapsed().as_millis() as u64;
+            result
+        }
+        Err(error) => fail(result, "tools/list", error),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn sh(script: &str) -> Vec<String> {
+        vec!["sh".to_string(), "-c".to_string(), script.to_string()]
+    }
+
+    fn probe(script: &str, timeout: Duration) -> McpTestResult {
+        test_local_mcp_server(&sh(script), &BTreeMap::new(), None, timeout)
+    }
+
+    #[test]
+    fn lists_tools_from_a_fake_server() {
+        // Answers initialize, skips the initialized notification, then answers tools/list.
+        let fake = r#"
+read _init
+echo 'starting up'
+echo '{"jsonrpc":"2.0","method":"notifications/message","params":{}}'
+echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","capabilities":{"tools":{}},"serverInfo":{"name":"fake","version":"0.1.0"}}}'
+read _initialized
+read _list
+echo "tools for $FAKE_USER" >&2
+echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"echo","description":"Echo input"},{"name":"ping"}]}}'
+sleep 5
+"#;
+        let env = BTreeMap::from([("FAKE_USER".to_string(), "tester".to_string())]);
+        let result = test_local_mcp_server(&sh(fake), &env, None, Duration::from_secs(10));
+        assert!(result.ok, "{result:?}");
+        assert_eq!(result.server_name.as_deref(), Some("fake"));
+        assert_eq!(result.protocol_version.as_deref(), Some("2025-06-18"));
+        let names: Vec<&str> = result.tools.iter().map(|tool| tool.name.as_str()).collect();
+        assert_eq!(names, vec!["echo", "ping"]);
+        assert!(result.elapsed_ms < 5000);
+    }
+
+    #[test]
+    fn reports_a_crashed_server_with_its_stderr() {
+        let crashed = probe(
+            "echo 'missing API key' >&2; exit 3",
+            Duration::from_secs(10),
+        );
+        assert!(!crashed.ok);
+        assert_eq!(crashed.stage.as_deref(), Some("initialize"));
+        assert!(crashed.error.unwrap_or_default().contains("exited"));
+        assert!(crashed.stderr.contains("missing API key"));
+    }
+
+    #[test]
+    fn gives_up_on_a_server_that_closes_stdout_but_keeps_running() {
+        let started = Instant::now();
+        let closed = probe("exec >&-; sleep 30", Duration::from_millis(300));
+        assert_eq!(closed.stage.as_deref(), Some("initialize"));
+        assert_eq!(
+            closed.error.as_deref(),
+            Some("Server closed its output without exiting")
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn reports_json_rpc_errors() {
+        let rejected = probe(
+            r#"read _; echo '{"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"bad version"}}'"#,
+            Duration::from_secs(10),
+        );
+        assert_eq!(
+            rejected.error.as_deref(),
+            Some("Server returned error -32602: bad version")
+        );
+    }
+
+    #[test]
+    fn times_out_on_a_silent_server() {
+        let silent = probe("sleep 5", Duration::from_millis(200));
+        assert_eq!(silent.stage.as_deref(), Some("initialize"));
+        assert!(silent.error.unwrap_or_default().contains("Timed out"));
+    }
+
+    #[test]
+    fn reports_a_missing_program_at_spawn() {
+        let missing = test_local_mcp_server(
+            &["/nonexistent/mcp-server".to_string()],
+            &BTreeMap::new(),
+            None,
+            Duration::from_secs(1),
+        );
+        assert_eq!(missing.stage.as_deref(), Some("spawn"));
+    }
+}
@@ -54,7 +54,7 @@ pub fn start_openwork_server(
     let client_token = generate_token();
     let host_token = generate_token();
 
-    let (mut rx, child) = spawn_openwork_server(
+    let (mut rx, child, limits) = spawn_openwork_server(
         app,
         &host,
         port,
@@ -101,7 +101,14 @@ pub fn start_openwork_server(
                 CommandEvent::Terminated(payload) => {
                     if let Ok(mut state) = state_handle.try_lock() {
                         state.child_exited = true;
-                        if let Some(code) = payload.code {
+                        let stderr = state.last_stderr.as_deref().unwrap_or_default();
+                        let reason = limits
+                            .as_ref()
+                            .and_then(|limits| limits.kill_reason(&payload, stderr));
+                        if let Some(reason) = reason {
+                            println!("[openwork-server] OpenWork server {reason}");
+                            state.last_stderr = Some(format!("OpenWork server {reason}."));
+                        } else if let Some(code) = payload.code {
                             let next = format!("OpenWork server exited (code {code}).");
                             state.last_stderr = Some(truncate_output(&next, 8000));
                         }
@@ -4,7 +4,9 @@ use std::path::Path;
 use tauri::AppHandle;
 use tauri::async_runtime::Receiver;
 use tauri_plugin_shell::process::{CommandChild, CommandEvent};
-use tauri_plugin_shell::ShellExt;
+
+use crate::limits::{limited_sidecar, ProcessLimits};
+use crate::workspace::env::workspace_spawn_env;
 
 const DEFAULT_OPENWORK_PORT: u16 = 8787;
 
@@ -76,12 +78,7 @@ pub fn spawn_openwork_server(
     host_token: &str,
     opencode_base_url: Option<&str>,
     opencode_directory: Option<&str>,
-) -> Result<(Receiver<CommandEvent>, CommandChild), String> {
-    let command = match app.shell().sidecar("openwork-server") {
-        Ok(command) => command,
-        Err(_) => app.shell().command("openwork-server"),
-    };
-
+) -> Result<(Receiver<CommandEvent>, CommandChild, Option<ProcessLimits>), String> {
     let args = build_openwork_args(
         host,
         port,
@@ -91,9 +88,11 @@ pub fn spawn_openwork_server(
         opencode_base_url,
         opencode_directory,
     );
-    command
-        .args(args)
+    let (command, limits) = limited_sidecar(app, workspace_path, "openwork-server", args);
+    let (rx, child) = command
         .current_dir(Path::new(workspace_path))
+        .envs(workspace_spawn_env(app, workspace_path, "openwork-server"))
         .spawn()
-        .map_err(|e| format!("Failed to start OpenWork server: {e}"))
+        .map_err(|e| format!("Failed to start OpenWork server: {e}"))?;
+    Ok((rx, child, limits))
 }
@@ -3,7 +3,9 @@ use std::path::Path;
 use tauri::AppHandle;
 use tauri::async_runtime::Receiver;
 use tauri_plugin_shell::process::{CommandChild, CommandEvent};
-use tauri_plugin_shell::ShellExt;
+
+use crate::limits::{limited_sidecar, ProcessLimits};
+use crate::workspace::env::workspace_spawn_env;
 
 pub fn build_owpenbot_args(
     workspace_path: &str,
@@ -26,17 +28,14 @@ pub fn spawn_owpenbot(
     app: &AppHandle,
     workspace_path: &str,
     opencode_url: Option<&str>,
-) -> Result<(Receiver<CommandEvent>, CommandChild), String> {
-    let command = match app.shell().sidecar("owpenbot") {
-        Ok(command) => command,
-        Err(_) => app.shell().command("owpenbot"),
-    };
-
+) -> Result<(Receiver<CommandEvent>, CommandChild, Option<ProcessLimits>), String> {
     let args = build_owpenbot_args(workspace_path, opencode_url);
     
-    command
-        .args(args)
+    let (command, limits) = limited_sidecar(app, workspace_path, "owpenbot", args);
+    let (rx, child) = command
         .current_dir(Path::new(workspace_path))
+        .envs(workspace_spawn_env(app, workspace_path, "owpenbot"))
         .spawn()
-        .map_err(|e| format!("Failed to start owpenbot: {e}"))
+        .map_err(|e| format!("Failed to start owpenbot: {e}"))?;
+    Ok((rx, child, limits))
 }
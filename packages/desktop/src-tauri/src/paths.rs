@@ -71,6 +71,82 @@ pub fn maybe_infer_xdg_home(
     None
 }
 
+/// `XDG_CONFIG_HOME` to hand OpenCode when the variable isn't set: whichever candidate
+/// already holds an OpenCode config.
+pub fn infer_opencode_config_home() -> Option<String> {
+    maybe_infer_xdg_home(
+        "XDG_CONFIG_HOME",
+        candidate_xdg_config_dirs(),
+        Path::new("opencode/opencode.jsonc"),
+    )
+    .or_else(|| {
+        maybe_infer_xdg_home(
+            "XDG_CONFIG_HOME",
+            candidate_xdg_config_dirs(),
+            Path::new("opencode/opencode.json"),
+        )
+    })
+}
+
+/// `auth.json` the engine reads: under `XDG_DATA_HOME` when set, else in the candidate
+/// `spawn_engine` infers, else OpenCode's default `~/.local/share`.
+pub fn opencode_auth_path() -> Option<PathBuf> {
+    let marker = Path::new("opencode").join("auth.json");
+    if let Some(value) = env::var_os("XDG_DATA_HOME").filter(|value| !value.is_empty()) {
+        return Some(PathBuf::from(value).join(marker));
+    }
+    if let Some(home) = maybe_infer_xdg_home("XDG_DATA_HOME", candidate_xdg_data_dirs(), &marker) {
+        return Some(PathBuf::from(home).join(marker));
+    }
+    candidate_xdg_data_dirs()
+        .into_iter()
+        .next()
+        .map(|base| base.join(marker))
+}
+
+/// Directories OpenCode may use as its cache, e.g. for installed npm plugins.
+pub fn opencode_cache_candidates() -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+
+    if let Ok(value) = std::env::var("XDG_CACHE_HOME") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            candidates.push(PathBuf::from(trimmed).join("opencode"));
+        }
+    }
+
+    if let Some(home) = home_dir() {
+        candidates.push(home.join(".cache").join("opencode"));
+
+        #[cfg(target_os = "macos")]
+        {
+            candidates.push(home.join("Library").join("Caches").join("opencode"));
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        if let Ok(value) = std::env::var("LOCALAPPDATA") {
+            let trimmed = value.trim();
+            if !trimmed.is_empty() {
+                candidates.push(PathBuf::from(trimmed).join("opencode"));
+            }
+        }
+        if let Ok(value) = std::env::var("APPDATA") {
+            let trimmed = value.trim();
+            if !trimmed.is_empty() {
+                candidates.push(PathBuf::from(trimmed).join("opencode"));
+            }
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|path| seen.insert(path.to_string_lossy().to_string()))
+        .collect()
+}
+
 pub fn path_entries() -> Vec<PathBuf> {
     let mut entries = Vec::new();
     let Some(path) = env::var_os("PATH") else {
@@ -90,3 +166,27 @@ pub fn resolve_in_path(name: &str) -> Option<PathBuf> {
     }
     None
 }
+
+/// Like `resolve_in_path`, but also finds `name.cmd` / `name.exe` launchers on Windows.
+pub fn resolve_command(name: &str) -> Option<PathBuf> {
+    resolve_command_in(name, &path_entries())
+}
+
+pub fn resolve_command_in(name: &str, dirs: &[PathBuf]) -> Option<PathBuf> {
+    let mut names = vec![name.to_string()];
+    if cfg!(windows) {
+        names.extend(["cmd", "exe"].map(|ext| format!("{name}.{ext}")));
+    }
+    names.iter().find_map(|name| {
+        dirs.iter()
+            .map(|dir| dir.join(name))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// `PATH` with `dirs` searched first.
+pub fn path_with_prefix(dirs: &[PathBuf]) -> Option<std::ffi::OsString> {
+    let mut entries = dirs.to_vec();
+    entries.extend(path_entries());
+    env::join_paths(entries).ok()
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -7,6 +9,13 @@ pub struct WorkspaceOpenworkConfig {
     pub workspace: Option<WorkspaceOpenworkWorkspace>,
     #[serde(default, alias = "authorizedRoots")]
     pub authorized_roots: Vec<String>,
+    /// Plain environment variables for the engine and sidecars. Secrets belong in the vault.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// `.env`-style files, relative to the workspace root, that must sit inside an
+    /// authorized root.
+    #[serde(default, alias = "envFiles", skip_serializing_if = "Vec::is_empty")]
+    pub env_files: Vec<String>,
 }
 
 impl Default for WorkspaceOpenworkConfig {
@@ -15,6 +24,8 @@ impl Default for WorkspaceOpenworkConfig {
             version: 1,
             workspace: None,
             authorized_roots: Vec::new(),
+            env: BTreeMap::new(),
+            env_files: Vec::new(),
         }
     }
 }
@@ -46,6 +57,7 @@ impl WorkspaceOpenworkConfig {
                 preset: Some(preset.to_string()),
             }),
             authorized_roots: vec![workspace_path.to_string()],
+            ..Self::default()
         }
     }
 }
@@ -59,6 +71,14 @@ pub struct EngineInfo {
     pub hostname: Option<String>,
     pub port: Option<u16>,
     pub pid: Option<u32>,
+    /// Name of the launcher the engine was started through.
+    pub launcher: Option<String>,
+    pub sandboxed: bool,
+    /// Recent access errors from a sandboxed engine.
+    pub sandbox_denials: Vec<String>,
+    pub limits: Option<crate::limits::ProcessLimits>,
+    /// Why the engine last stopped on its own, e.g. killed for exceeding its memory limit.
+    pub exit_reason: Option<String>,
     pub last_stdout: Option<String>,
     pub last_stderr: Option<String>,
 }
@@ -106,6 +126,34 @@ pub struct EngineDoctorResult {
     pub serve_help_status: Option<i32>,
     pub serve_help_stdout: Option<String>,
     pub serve_help_stderr: Option<String>,
+    pub preflight: RuntimePreflight,
+    pub node_runtime: Option<crate::engine::node_runtime::NodeRuntimeStatus>,
+    pub providers: crate::engine::auth::ProviderAuthReport,
+    pub sandbox: crate::engine::sandbox::SandboxStatus,
+}
+
+/// Whether the launcher of a local MCP server can be found.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct McpRuntimeCheck {
+    pub server: String,
+    /// Config file the server's command came from.
+    pub source: Option<String>,
+    pub command: Option<String>,
+    pub enabled: bool,
+    pub resolved_path: Option<String>,
+    pub runtime_version: Option<String>,
+    pub ok: bool,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimePreflight {
+    pub project_dir: Option<String>,
+    pub mcp: Vec<McpRuntimeCheck>,
+    pub plugins: Vec<crate::config::plugins::PluginEntry>,
+    pub notes: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -183,6 +231,21 @@ pub struct WorkspaceInfo {
     pub openwork_workspace_id: Option<String>,
     #[serde(default)]
     pub openwork_workspace_name: Option<String>,
+    /// Name of the vault secret holding this remote's token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_secret: Option<String>,
+    /// OpenCode profile the engine runs with; `None` shares the user's own OpenCode setup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opencode_profile: Option<String>,
+    /// Id of the `EngineLauncher` the engine starts through; `None` runs OpenCode directly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engine_launcher: Option<String>,
+    /// Run the engine in a Linux sandbox that can only write to the authorized roots.
+    #[serde(default)]
+    pub engine_sandbox: bool,
+    /// Applied to the engine, OpenWork server and owpenbot the next time they start.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_limits: Option<ResourceLimits>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -231,3 +294,154 @@ impl Default for WorkspaceState {
 }
 
 pub const WORKSPACE_STATE_VERSION: u8 = 3;
+
+pub const DEFAULT_ENTERPRISE_SKILLS_REPOSITORY: &str =
+    "https://github.com/different-ai/openwork-enterprise";
+
+fn default_enterprise_skills_repository() -> String {
+    DEFAULT_ENTERPRISE_SKILLS_REPOSITORY.to_string()
+}
+
+fn default_enterprise_skills_ref() -> String {
+    "main".to_string()
+}
+
+/// Where creator skills for the enterprise preset come from.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnterpriseSkillsSettings {
+    #[serde(default = "default_enterprise_skills_repository")]
+    pub repository: String,
+    /// Branch, tag or commit; downloaded as `<repository>/archive/<ref>.zip`.
+    #[serde(default = "default_enterprise_skills_ref", rename = "ref")]
+    pub git_ref: String,
+    /// Full archive URL, for mirrors that don't follow GitHub's layout.
+    #[serde(default)]
+    pub archive_url: Option<String>,
+    /// Expected SHA-256 of the archive. When set, only a matching archive is used.
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+impl Default for EnterpriseSkillsSettings {
+    fn default() -> Self {
+        Self {
+            repository: default_enterprise_skills_repository(),
+            git_ref: default_enterprise_skills_ref(),
+            archive_url: None,
+            sha256: None,
+        }
+    }
+}
+
+impl EnterpriseSkillsSettings {
+    pub fn resolved_archive_url(&self) -> String {
+        if let Some(url) = self
+            .archive_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+        {
+            return url.to_string();
+        }
+        format!(
+            "{}/archive/{}.zip",
+            self.repository.trim().trim_end_matches('/'),
+            self.git_ref.trim()
+        )
+    }
+}
+
+pub const DEFAULT_NODE_RUNTIME_VERSION: &str = "22.12.0";
+pub const DEFAULT_NODE_RUNTIME_MIRROR: &str = "https://nodejs.org/dist";
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_node_runtime_version() -> String {
+    DEFAULT_NODE_RUNTIME_VERSION.to_string()
+}
+
+fn default_node_runtime_mirror() -> String {
+    DEFAULT_NODE_RUNTIME_MIRROR.to_string()
+}
+
+/// The private Node.js runtime OpenWork puts on OpenCode's PATH for MCP servers and plugins.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeRuntimeSettings {
+    /// Use the managed runtime once installed. Off means OpenCode only sees the system PATH.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_node_runtime_version")]
+    pub version: String,
+    /// Base URL laid out like `https://nodejs.org/dist`.
+    #[serde(default = "default_node_runtime_mirror")]
+    pub mirror: String,
+    /// Expected SHA-256 of the archive. Without it the archive is checked against
+    /// `SHASUMS256.txt` from the same mirror, which catches corrupt downloads but not a
+    /// compromised mirror.
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+impl Default for NodeRuntimeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            version: default_node_runtime_version(),
+            mirror: default_node_runtime_mirror(),
+            sha256: None,
+        }
+    }
+}
+
+/// A wrapper OpenCode is started through, e.g. a container, `ssh` or `nice`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineLauncher {
+    pub id: String,
+    pub name: String,
+    /// Program and arguments, one per entry. `{opencode}`, `{hostname}`, `{port}` and
+    /// `{projectDir}` are filled in; `{args}` expands to the `serve` arguments, which are
+    /// appended when it's absent. Values with spaces or shell syntax are only accepted as an
+    /// argument of their own, and never in `ssh` commands or `-c` scripts.
+    pub command: Vec<String>,
+}
+
+/// Caps for the engine and sidecars of one workspace. Unset fields aren't limited.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceLimits {
+    #[serde(default)]
+    pub memory_mb: Option<u64>,
+    /// 0 (normal) to 19 (lowest CPU priority).
+    #[serde(default)]
+    pub nice: Option<i32>,
+    #[serde(default)]
+    pub open_files: Option<u64>,
+    #[serde(default)]
+    pub max_processes: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub fn is_empty(&self) -> bool {
+        self.memory_mb.is_none()
+            && self.nice.is_none_or(|nice| nice == 0)
+            && self.open_files.is_none()
+            && self.max_processes.is_none()
+    }
+}
+
+/// App-wide preferences stored in `openwork-settings.json` under the app data dir.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSettings {
+    #[serde(default)]
+    pub enterprise_skills: EnterpriseSkillsSettings,
+    #[serde(default)]
+    pub node_runtime: NodeRuntimeSettings,
+    #[serde(default)]
+    pub engine_launchers: Vec<EngineLauncher>,
+}
@@ -0,0 +1,516 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::crypto::{
+    decrypt_with_key, decrypt_with_passphrase, encrypt_with_key, encrypt_with_passphrase,
+    generate_key, is_encrypted,
+};
+use crate::fs::write_private;
+use crate::utils::now_ms;
+
+const VAULT_FILE: &str = "openwork-vault.bin";
+const VAULT_KEY_FILE: &str = "openwork-vault.key";
+const VAULT_VERSION: u32 = 1;
+const LOCKED_ERROR: &str = "The vault is locked; unlock it with your passphrase";
+
+/// Serializes read-modify-write cycles so concurrent commands don't drop each other's edits.
+static VAULT_LOCK: Mutex<()> = Mutex::new(());
+
+/// A named secret. Deliberately not `Debug` so values can't end up in logs.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultSecret {
+    pub value: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// Decrypted vault contents. Deliberately not `Debug` so values can't end up in logs.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultData {
+    pub version: u32,
+    /// Secrets referenced by name, e.g. `{vault:GITHUB_TOKEN}`.
+    #[serde(default)]
+    pub secrets: BTreeMap<String, VaultSecret>,
+    /// Secret environment variables keyed by workspace id, then variable name.
+    #[serde(default)]
+    pub workspace_env: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// What the UI may see about a secret.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultSecretInfo {
+    pub name: String,
+    pub redacted: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum VaultMode {
+    /// Sealed with a random key stored next to the vault with owner-only permissions.
+    MachineKey,
+    /// Sealed with a key derived from a passphrase that is never written to disk.
+    Passphrase,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStatus {
+    pub path: String,
+    pub exists: bool,
+    pub mode: VaultMode,
+    pub locked: bool,
+    /// Named secrets; `None` while locked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The passphrase of an unlocked passphrase-mode vault, held in memory for this app run.
+#[derive(Default)]
+pub struct VaultSession {
+    passphrase: Mutex<Option<String>>,
+}
+
+impl VaultSession {
+    pub fn get(&self) -> Option<String> {
+        self.passphrase
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn set(&self, passphrase: Option<String>) {
+        *self.passphrase.lock().unwrap_or_else(|e| e.into_inner()) = passphrase;
+    }
+}
+
+/// Secret names double as environment variable names when injected into the engine.
+pub fn validate_secret_name(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if !valid_start || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!(
+            "Invalid secret name {name:?}: use letters, digits and underscores"
+        ));
+    }
+    Ok(())
+}
+
+/// The secret name in a `{vault:NAME}` reference.
+pub fn secret_reference(value: &str) -> Option<&str> {
+    value
+        .trim()
+        .strip_prefix("{vault:")?
+        .strip_suffix('}')
+        .map(str::trim)
+}
+
+/// Enough of a value to recognize it: the last four characters of long values only.
+pub fn redact_secret(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() < 12 {
+        return "••••".to_string();
+    }
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("••••{tail}")
+}
+
+fn describe(name: &str, secret: &VaultSecret) -> VaultSecretInfo {
+    VaultSecretInfo {
+        name: name.to_string(),
+        redacted: redact_secret(&secret.value),
+        description: secret.description.clone(),
+        created_at: secret.created_at,
+        updated_at: secret.updated_at,
+    }
+}
+
+/// The encrypted store under app data: one file, sealed either with a machine key kept
+/// next to it or with a passphrase. Neither needs a desktop keyring daemon.
+pub struct Vault {
+    dir: PathBuf,
+    passphrase: Option<String>,
+}
+
+impl Vault {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            passphrase: None,
+        }
+    }
+
+    pub fn with_passphrase(mut self, passphrase: Option<String>) -> Self {
+        self.passphrase = passphrase;
+        self
+    }
+
+    /// The app's vault, unlocked with the session passphrase if one was entered.
+    pub fn for_app(app: &tauri::AppHandle) -> Result<Self, String> {
+        let data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to resolve app data dir: {e}"))?;
+        let passphrase = app
+            .try_state::<VaultSession>()
+            .and_then(|session| session.get());
+        Ok(Self::new(&data_dir).with_passphrase(passphrase))
+    }
+
+    fn vault_path(&self) -> PathBuf {
+        self.dir.join(VAULT_FILE)
+    }
+
+    fn key_path(&self) -> PathBuf {
+        self.dir.join(VAULT_KEY_FILE)
+    }
+
+    fn read_key(&self) -> Result<Option<[u8; 32]>, String> {
+        let path = self.key_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let raw = fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let key: [u8; 32] = raw
+            .try_into()
+            .map_err(|_| format!("{} is not a valid vault key", path.display()))?;
+        Ok(Some(key))
+    }
+
+    fn ensure_key(&self) -> Result<[u8; 32], String> {
+        if let Some(key) = self.read_key()? {
+            return Ok(key);
+        }
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create {}: {e}", self.dir.display()))?;
+        let key = generate_key();
+        write_private(&self.key_path(), &key)?;
+        Ok(key)
+    }
+
+    fn read_sealed(&self) -> Result<Option<Vec<u8>>, String> {
+        let path = self.vault_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        fs::read(&path)
+            .map(Some)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))
+    }
+
+    fn mode_of(sealed: Option<&[u8]>) -> VaultMode {
+        match sealed {
+            Some(bytes) if is_encrypted(bytes) => VaultMode::Passphrase,
+            _ => VaultMode::MachineKey,
+        }
+    }
+
+    fn open(&self, sealed: &[u8], passphrase: Option<&str>) -> Result<VaultData, String> {
+        let path = self.vault_path();
+        let plain = match Self::mode_of(Some(sealed)) {
+            VaultMode::Passphrase => {
+                let passphrase = passphrase.ok_or_else(|| LOCKED_ERROR.to_string())?;
+                decrypt_with_passphrase(sealed, passphrase)?
+            }
+            VaultMode::MachineKey => {
+                let Some(key) = self.read_key()? else {
+                    return Err(format!(
+                        "{} exists but its key is missing; the stored secrets can't be read",
+                        path.display()
+                    ));
+                };
+                decrypt_with_key(sealed, &key)
+                    .map_err(|e| format!("Failed to open {}: {e}", path.display()))?
+            }
+        };
+        serde_json::from_slice(&plain)
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+    }
+
+    fn load_unlocked(&self) -> Result<(VaultData, VaultMode), String> {
+        let sealed = self.read_sealed()?;
+        let mode = Self::mode_of(sealed.as_deref());
+        let data = match sealed {
+            Some(sealed) => self.open(&sealed, self.passphrase.as_deref())?,
+            None => VaultData {
+                version: VAULT_VERSION,
+                ..VaultData::default()
+            },
+        };
+        Ok((data, mode))
+    }
+
+    fn save_unlocked(&self, data: &VaultData, passphrase: Option<&str>) -> Result<(), String> {
+        let plain = serde_json::to_vec(data).map_err(|e| e.to_string())?;
+        let sealed = match passphrase {
+            Some(passphrase) => encrypt_with_passphrase(&plain, passphrase)?,
+            None => encrypt_with_key(&plain, &self.ensure_key()?)?,
+        };
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create {}: {e}", self.dir.display()))?;
+        write_private(&self.vault_path(), &sealed)
+    }
+
+    pub fn status(&self) -> VaultStatus {
+        let _guard = VAULT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let sealed = self.read_sealed();
+        let exists = matches!(sealed, Ok(Some(_)));
+        let mode = Self::mode_of(sealed.as_ref().ok().and_then(|s| s.as_deref()));
+        let mut status = VaultStatus {
+            path: self.vault_path().to_string_lossy().to_string(),
+            exists,
+            mode,
+            locked: mode == VaultMode::Passphrase && self.passphrase.is_none(),
+            secret_count: None,
+            error: None,
+        };
+        if status.locked {
+            return status;
+        }
+        match self.load_unlocked() {
+            Ok((data, _)) => status.secret_count = Some(data.secrets.len()),
+            Err(error) => {
+                status.locked = mode == VaultMode::Passphrase;
+                status.error = Some(error);
+            }
+        }
+        status
+    }
+
+    pub fn load(&self) -> Result<VaultData, String> {
+        let _guard = VAULT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(self.load_unlocked()?.0)
+    }
+
+    /// Applies `edit` and writes the vault back in the mode it was already in.
+    pub fn update<T>(
+        &self,
+        edit: impl FnOnce(&mut VaultData) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let _guard = VAULT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let (mut data, mode) = self.load_unlocked()?;
+        let result = edit(&mut data)?;
+        data.version = VAULT_VERSION;
+        let passphrase = match mode {
+            VaultMode::Passphrase => self.passphrase.as_deref(),
+            VaultMode::MachineKey => None,
+        };
+        self.save_unlocked(&data, passphrase)?;
+        Ok(result)
+    }
+
+    /// Checks `passphrase` against a passphrase-mode vault.
+    pub fn verify_passphrase(&self, passphrase: &str) -> Result<(), String> {
+        let _guard = VAULT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        match self.read_sealed()? {
+            Some(sealed) if is_encrypted(&sealed) => {
+                self.open(&sealed, Some(passphrase)).map(|_| ())
+            }
+            _ => Err("The vault doesn't use a passphrase".to_string()),
+        }
+    }
+
+    /// Re-seals the vault with `next`, or with a fresh machine key when `next` is `None`.
+    /// Switching to a passphrase deletes the machine key.
+    pub fn change_passphrase(&self, next: Option<&str>) -> Result<(), String> {
+        if next.is_some_and(|passphrase| passphrase.chars().count() < 8) {
+            return Err("Passphrase must be at least 8 characters".to_string());
+        }
+        let _guard = VAULT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let (data, _) = self.load_unlocked()?;
+        self.save_unlocked(&data, next)?;
+        if next.is_some() {
+            let key_path = self.key_path();
+            if key_path.exists() {
+                fs::remove_file(&key_path)
+                    .map_err(|e| format!("Failed to remove {}: {e}", key_path.display()))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn list_secrets(&self) -> Result<Vec<VaultSecretInfo>, String> {
+        Ok(self
+            .load()?
+            .secrets
+            .iter()
+            .map(|(name, secret)| describe(name, secret))
+            .collect())
+    }
+
+    pub fn secret_info(&self, name: &str) -> Result<VaultSecretInfo, String> {
+        let data = self.load()?;
+        data.secrets
+            .get(name)
+            .map(|secret| describe(name, secret))
+            .ok_or_else(|| format!("Secret {name} not found"))
+    }
+
+    /// The plain value, for subsystems that hand it to a process or a remote server.
+    pub fn secret_value(&self, name: &str) -> Result<Option<String>, String> {
+        Ok(self.load()?.secrets.remove(name).map(|secret| secret.value))
+    }
+
+    pub fn set_secret(
+        &self,
+        name: &str,
+        value: &str,
+        description: Option<&str>,
+    ) -> Result<VaultSecretInfo, String> {
+        validate_secret_name(name)?;
+        if value.is_empty() {
+            return Err("Secret value must not be empty".to_string());
+        }
+        let description = description
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string);
+        self.update(|data| {
+            let now = now_ms();
+            let secret = data
+                .secrets
+                .entry(name.to_string())
+                .or_insert_with(|| VaultSecret {
+                    value: String::new(),
+                    description: None,
+                    created_at: now,
+                    updated_at: now,
+                });
+            secret.value = value.to_string();
+            secret.updated_at = now;
+            if description.is_some() {
+                secret.description = description;
+            }
+            Ok(describe(name, secret))
+        })
+    }
+
+    pub fn delete_secret(&self, name: &str) -> Result<(), String> {
+        self.update(|data| match data.secrets.remove(name) {
+            Some(_) => Ok(()),
+            None => Err(format!("Secret {name} not found")),
+        })
+    }
+
+    /// Secret variables stored for `workspace_id`.
+    pub fn workspace_env(&self, workspace_id: &str) -> Result<BTreeMap<String, String>, String> {
+        Ok(self
+            .load()?
+            .workspace_env
+            .remove(workspace_id)
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::TempDir;
+
+    const PASSPHRASE: &str = "correct horse";
+
+    /// A machine-key vault holding `GITHUB_TOKEN`.
+    fn vault_with_token() -> (TempDir, Vault) {
+        let dir = TempDir::new("vault");
+        let vault = Vault::new(&dir);
+        vault
+            .set_secret("GITHUB_TOKEN", "ghp_0123456789abcdef", Some("CI bot"))
+            .expect("set");
+        (dir, vault)
+    }
+
+    #[test]
+    fn redacts_secrets_and_parses_references() {
+        let (_dir, vault) = vault_with_token();
+        assert_eq!(vault.status().mode, VaultMode::MachineKey);
+        assert_eq!(
+            vault.secret_info("GITHUB_TOKEN").expect("info").redacted,
+            "••••cdef"
+        );
+        assert!(vault.set_secret("bad name", "x", None).is_err());
+        assert_eq!(redact_secret("short"), "••••");
+        assert_eq!(
+            secret_reference(" {vault:GITHUB_TOKEN} "),
+            Some("GITHUB_TOKEN")
+        );
+    }
+
+    #[test]
+    fn a_passphrase_replaces_the_machine_key_and_locks_the_vault() {
+        let (dir, vault) = vault_with_token();
+        assert!(dir.join(VAULT_KEY_FILE).exists());
+
+        assert!(vault.change_passphrase(Some("short")).is_err());
+        vault
+            .change_passphrase(Some(PASSPHRASE))
+            .expect("set passphrase");
+        assert!(!dir.join(VAULT_KEY_FILE).exists());
+        let status = vault.status();
+        assert!(status.locked && status.secret_count.is_none());
+        assert!(vault.list_secrets().is_err());
+        assert!(vault.verify_passphrase("battery staple").is_err());
+        vault
+            .verify_passphrase(PASSPHRASE)
+            .expect("verify passphrase");
+    }
+
+    #[test]
+    fn the_session_passphrase_unlocks_reads_and_writes() {
+        let (dir, vault) = vault_with_token();
+        vault
+            .change_passphrase(Some(PASSPHRASE))
+            .expect("set passphrase");
+
+        let unlocked = Vault::new(&dir).with_passphrase(Some(PASSPHRASE.to_string()));
+        unlocked
+            .set_secret("NPM_TOKEN", "npm_secret_value", None)
+            .expect("set while unlocked");
+        assert_eq!(unlocked.status().secret_count, Some(2));
+        assert_eq!(
+            unlocked
+                .secret_value("GITHUB_TOKEN")
+                .expect("value")
+                .as_deref(),
+            Some("ghp_0123456789abcdef")
+        );
+    }
+
+    #[test]
+    fn dropping_the_passphrase_keeps_secrets_under_a_machine_key() {
+        let (dir, vault) = vault_with_token();
+        vault
+            .change_passphrase(Some(PASSPHRASE))
+            .expect("set passphrase");
+        Vault::new(&dir)
+            .with_passphrase(Some(PASSPHRASE.to_string()))
+            .change_passphrase(None)
+            .expect("back to machine key");
+
+        let names: Vec<String> = Vault::new(&dir)
+            .list_secrets()
+            .expect("list")
+            .into_iter()
+            .map(|info| info.name)
+            .collect();
+        assert_eq!(names, vec!["GITHUB_TOKEN"]);
+        vault.delete_secret("GITHUB_TOKEN").expect("delete");
+        assert!(vault.delete_secret("GITHUB_TOKEN").is_err());
+    }
+}
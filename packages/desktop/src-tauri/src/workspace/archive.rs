@@ -0,0 +1,960 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+use crate::config::add_opencode_entries;
+use crate::jsonc::JsoncDocument;
+use crate::workspace::secrets::{
+    fill_placeholders, is_secret_name, should_exclude, SecretRedaction, PLACEHOLDER_PREFIX,
+};
+use crate::workspace::seed::sha256_hex;
+
+pub const MANIFEST_NAME: &str = "manifest.json";
+/// Version 2 added per-file checksums and the archive digest.
+pub const ARCHIVE_VERSION: u64 = 2;
+const CONFIG_NAMES: &[&str] = &["opencode.jsonc", "opencode.json"];
+const OPENWORK_CONFIG: &str = ".opencode/openwork.json";
+/// Root-level instruction files OpenCode loads into every session.
+const INSTRUCTION_FILES: &[&str] = &["AGENTS.md", "CLAUDE.md"];
+/// Never exported, even when an include glob matches inside them.
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules"];
+
+// Config exports are small; anything near these limits is not something we wrote.
+const MAX_ENTRIES: usize = 5_000;
+const MAX_TOTAL_BYTES: u64 = 64 * 1024 * 1024;
+const MAX_COMPRESSION_RATIO: u64 = 100;
+/// Small files can legitimately compress far better than the ratio limit.
+const RATIO_CHECK_MIN_BYTES: u64 = 1024 * 1024;
+const S_IFMT: u32 = 0o170000;
+const S_IFLNK: u32 = 0o120000;
+
+/// Checksum of one exported file, as recorded in the manifest.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArchiveFileRecord {
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Digest over every `path` and checksum in the manifest, so a truncated or edited file list
+/// is caught as well as edited files.
+pub fn archive_digest(files: &BTreeMap<String, ArchiveFileRecord>) -> String {
+    let mut listing = String::new();
+    for (path, record) in files {
+        listing.push_str(&format!("{path}\0{}\0{}\n", record.sha256, record.size));
+    }
+    sha256_hex(listing.as_bytes())
+}
+
+/// Why an archive was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveError {
+    Unreadable(String),
+    UnsafePath(String),
+    Symlink(String),
+    DuplicateEntry(String),
+    TooManyEntries { count: usize, limit: usize },
+    TooLarge { limit: u64 },
+    CompressionRatio(String),
+    MissingManifest,
+    InvalidManifest(String),
+    UnsupportedVersion(u64),
+    UnlistedEntry(String),
+    MissingEntry(String),
+    SizeMismatch(String),
+    ChecksumMismatch(String),
+    DigestMismatch,
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreadable(error) => write!(f, "Failed to read archive: {error}"),
+            Self::UnsafePath(path) => write!(f, "Archive contains an unsafe path: {path}"),
+            Self::Symlink(path) => write!(f, "Archive contains a symlink: {path}"),
+            Self::DuplicateEntry(path) => write!(f, "Archive contains {path} more than once"),
+            Self::TooManyEntries { count, limit } => write!(
+                f,
+                "Archive has {count} entries; at most {limit} are allowed"
+            ),
+            Self::TooLarge { limit } => write!(
+                f,
+                "Archive expands to more than {} MiB",
+                limit / (1024 * 1024)
+            ),
+            Self::CompressionRatio(path) => {
+                write!(f, "Archive entry {path} has a suspicious compression ratio")
+            }
+            Self::MissingManifest => write!(f, "Archive is missing {MANIFEST_NAME}"),
+            Self::InvalidManifest(error) => write!(f, "Archive manifest is invalid: {error}"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "Archive manifest version {version} is not supported (expected {ARCHIVE_VERSION} or older)"
+            ),
+            Self::UnlistedEntry(path) => {
+                write!(f, "Archive entry {path} is not listed in the manifest")
+            }
+            Self::MissingEntry(path) => {
+                write!(f, "Archive is missing {path}, which the manifest lists")
+            }
+            Self::SizeMismatch(path) => {
+                write!(f, "Size of {path} does not match the manifest")
+            }
+            Self::ChecksumMismatch(path) => {
+                write!(f, "Checksum of {path} does not match the manifest")
+            }
+            Self::DigestMismatch => write!(f, "Archive digest does not match the manifest"),
+        }
+    }
+}
+
+impl From<ArchiveError> for String {
+    fn from(error: ArchiveError) -> Self {
+        error.to_string()
+    }
+}
+
+/// A config file read from an export archive.
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub contents: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceArchive {
+    pub manifest: Option<serde_json::Value>,
+    pub entries: Vec<ArchiveEntry>,
+    /// Entries dropped because their file name looks like a secret.
+    pub skipped_secrets: Vec<String>,
+}
+
+impl WorkspaceArchive {
+    pub fn redactions(&self) -> Vec<SecretRedaction> {
+        self.manifest
+            .as_ref()
+            .and_then(|manifest| manifest.get("redactions"))
+            .cloned()
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+fn normalize_zip_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn build_globs(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern.trim()).map_err(|e| format!("Invalid glob {pattern}: {e}"))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Invalid globs: {e}"))
+}
+
+fn is_skipped_dir(entry: &walkdir::DirEntry) -> bool {
+    entry.file_type().is_dir()
+        && entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| SKIPPED_DIRS.contains(&name))
+}
+
+/// Files a workspace export holds, keyed by archive path, plus the paths left out because
+/// they look like secrets.
+#[derive(Debug, Default)]
+pub struct ExportEntries {
+    pub files: BTreeMap<String, PathBuf>,
+    pub excluded: Vec<String>,
+}
+
+/// Collects both config file names, the root instruction files and `.opencode/`, plus any
+/// workspace file matching `include`. `exclude` wins over everything, including the defaults.
+/// Symlinks are never followed or exported.
+pub fn collect_export_entries(
+    workspace_root: &Path,
+    include: &[String],
+    exclude: &[String],
+) -> Result<ExportEntries, String> {
+    let include = build_globs(include)?;
+    let exclude = build_globs(exclude)?;
+    let mut entries = ExportEntries::default();
+
+    let mut consider = |absolute: PathBuf, rel: String| {
+        if exclude.as_ref().is_some_and(|globs| globs.is_match(&rel)) {
+            return;
+        }
+        if should_exclude(&absolute) {
+            if !entries.excluded.contains(&rel) {
+                entries.excluded.push(rel);
+            }
+            return;
+        }
+        entries.files.insert(rel, absolute);
+    };
+
+    for name in CONFIG_NAMES.iter().chain(INSTRUCTION_FILES) {
+        let path = workspace_root.join(name);
+        if path.symlink_metadata().is_ok_and(|meta| meta.is_file()) {
+            consider(path, name.to_string());
+        }
+    }
+
+    let mut walk_roots = vec![workspace_root.join(".opencode")];
+    if include.is_some() {
+        walk_roots.push(workspace_root.to_path_buf());
+    }
+    for (index, walk_root) in walk_roots.iter().enumerate() {
+        if !walk_root.is_dir() {
+            continue;
+        }
+        let walker = WalkDir::new(walk_root)
+            .into_iter()
+            .filter_entry(|entry| !is_skipped_dir(entry));
+        for entry in walker {
+            let entry = entry.map_err(|e| e.to_string())?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let absolute = entry.path().to_path_buf();
+            let rel = absolute
+                .strip_prefix(workspace_root)
+                .map_err(|e| format!("Failed to compute relative path: {e}"))?;
+            let rel = normalize_zip_path(rel);
+            // The first walk takes all of `.opencode/`; the second only what `include` asks for.
+            let wanted = index == 0 || include.as_ref().is_some_and(|globs| globs.is_match(&rel));
+            if wanted {
+                consider(absolute, rel);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Reasons an archive may not import cleanly into this app, such as an export from a newer
+/// release or a preset this install doesn't know.
+pub fn compatibility_warnings(
+    manifest: &serde_json::Value,
+    app_version: &str,
+    known_presets: &[String],
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if let Some(exported_by) = manifest.get("appVersion").and_then(|value| value.as_str()) {
+        if let (Some(exported), Some(current)) = (
+            semver::Version::parse(exported_by).ok(),
+            semver::Version::parse(app_version).ok(),
+        ) {
+            if exported > current {
+                warnings.push(format!(
+                    "Exported by OpenWork {exported}, which is newer than this version ({current})"
+                ));
+            }
+        }
+    }
+
+    if let Some(preset) = manifest
+        .pointer("/workspace/preset")
+        .and_then(|value| value.as_str())
+        .filter(|preset| !preset.trim().is_empty())
+    {
+        if !known_presets.iter().any(|known| known == preset) {
+            warnings.push(format!(
+                "Preset \"{preset}\" is not installed; preset upgrades won't be available"
+            ));
+        }
+    }
+
+    warnings
+}
+
+fn is_unsafe_path(name: &str) -> bool {
+    Path::new(name).components().any(|component| {
+        matches!(
+            component,
+            Component::ParentDir | Component::RootDir | Component::Prefix(_)
+        )
+    })
+}
+
+/// Reads every entry, enforcing the entry count, expanded size and compression ratio limits
+/// against the bytes actually inflated rather than the sizes the headers claim.
+fn read_zip_entries(bytes: Vec<u8>) -> Result<BTreeMap<String, Vec<u8>>, ArchiveError> {
+    let mut zip =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|e| ArchiveError::Unreadable(e.to_string()))?;
+    if zip.len() > MAX_ENTRIES {
+        return Err(ArchiveError::TooManyEntries {
+            count: zip.len(),
+            limit: MAX_ENTRIES,
+        });
+    }
+
+    let mut files = BTreeMap::new();
+    let mut total: u64 = 0;
+    for i in 0..zip.len() {
+        let entry = zip
+            .by_index(i)
+            .map_err(|e| ArchiveError::Unreadable(e.to_string()))?;
+        let name = entry.name().to_string();
+        if is_unsafe_path(&name) {
+            return Err(ArchiveError::UnsafePath(name));
+        }
+        if entry
+            .unix_mode()
+            .is_some_and(|mode| mode & S_IFMT == S_IFLNK)
+        {
+            return Err(ArchiveError::Symlink(name));
+        }
+        if entry.is_dir() {
+            continue;
+        }
+
+        let compressed = entry.compressed_size();
+        let remaining = MAX_TOTAL_BYTES - total;
+        let mut contents = Vec::new();
+        entry
+            .take(remaining + 1)
+            .read_to_end(&mut contents)
+            .map_err(|e| ArchiveError::Unreadable(format!("{name}: {e}")))?;
+        let size = contents.len() as u64;
+        if size > remaining {
+            return Err(ArchiveError::TooLarge {
+                limit: MAX_TOTAL_BYTES,
+            });
+        }
+        if size >= RATIO_CHECK_MIN_BYTES && size / compressed.max(1) > MAX_COMPRESSION_RATIO {
+            return Err(ArchiveError::CompressionRatio(name));
+        }
+        total += size;
+
+        if files.insert(name.clone(), contents).is_some() {
+            return Err(ArchiveError::DuplicateEntry(name));
+        }
+    }
+
+    Ok(files)
+}
+
+fn manifest_paths(manifest: &serde_json::Value) -> Result<Vec<String>, ArchiveError> {
+    manifest
+        .get("included")
+        .and_then(|value| value.as_array())
+        .ok_or_else(|| ArchiveError::InvalidManifest("missing `included`".to_string()))?
+        .iter()
+        .map(|value| {
+            value
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| ArchiveError::InvalidManifest("non-string path".to_string()))
+        })
+        .collect()
+}
+
+/// Checks the archive contents against the manifest: the file list must match exactly and,
+/// from version 2 on, every file must match its checksum and the list its digest.
+fn verify_manifest(
+    manifest: &serde_json::Value,
+    files: &BTreeMap<String, Vec<u8>>,
+) -> Result<(), ArchiveError> {
+    let version = manifest
+        .get("version")
+        .and_then(|value| value.as_u64())
+        .ok_or_else(|| ArchiveError::InvalidManifest("missing `version`".to_string()))?;
+    if version == 0 || version > ARCHIVE_VERSION {
+        return Err(ArchiveError::UnsupportedVersion(version));
+    }
+
+    let included = manifest_paths(manifest)?;
+    let listed: HashSet<&str> = included.iter().map(String::as_str).collect();
+    if let Some(path) = files.keys().find(|path| !listed.contains(path.as_str())) {
+        return Err(ArchiveError::UnlistedEntry(path.clone()));
+    }
+    if let Some(path) = included.iter().find(|path| !files.contains_key(*path)) {
+        return Err(ArchiveError::MissingEntry(path.clone()));
+    }
+    if version < 2 {
+        return Ok(());
+    }
+
+    let records: BTreeMap<String, ArchiveFileRecord> = manifest
+        .get("files")
+        .cloned()
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| ArchiveError::InvalidManifest(format!("`files`: {e}")))?
+        .ok_or_else(|| ArchiveError::InvalidManifest("missing `files`".to_string()))?;
+    for path in &included {
+        let record = records
+            .get(path)
+            .ok_or_else(|| ArchiveError::InvalidManifest(format!("no checksum for {path}")))?;
+        let contents = &files[path];
+        if contents.len() as u64 != record.size {
+            return Err(ArchiveError::SizeMismatch(path.clone()));
+        }
+        if !sha256_hex(contents).eq_ignore_ascii_case(&record.sha256) {
+            return Err(ArchiveError::ChecksumMismatch(path.clone()));
+        }
+    }
+    if let Some(path) = records.keys().find(|path| !listed.contains(path.as_str())) {
+        return Err(ArchiveError::MissingEntry(path.clone()));
+    }
+
+    let digest = manifest
+        .get("digest")
+        .and_then(|value| value.as_str())
+        .ok_or_else(|| ArchiveError::InvalidManifest("missing `digest`".to_string()))?;
+    if !archive_digest(&records).eq_ignore_ascii_case(digest) {
+        return Err(ArchiveError::DigestMismatch);
+    }
+    Ok(())
+}
+
+pub fn read_workspace_archive(bytes: Vec<u8>) -> Result<WorkspaceArchive, ArchiveError> {
+    let mut files = read_zip_entries(bytes)?;
+    let manifest_bytes = files
+        .remove(MANIFEST_NAME)
+        .ok_or(ArchiveError::MissingManifest)?;
+    let manifest: serde_json::Value = serde_json::from_slice(&manifest_bytes)
+        .map_err(|e| ArchiveError::InvalidManifest(e.to_string()))?;
+    verify_manifest(&manifest, &files)?;
+
+    let mut archive = WorkspaceArchive {
+        manifest: Some(manifest),
+        ..WorkspaceArchive::default()
+    };
+    for (name, contents) in files {
+        let file_name = Path::new(&name)
+            .file_name()
+            .and_then(|entry| entry.to_str())
+            .unwrap_or("");
+        if is_secret_name(file_name) {
+            archive.skipped_secrets.push(name);
+            continue;
+        }
+        archive.entries.push(ArchiveEntry {
+            path: name,
+            contents,
+        });
+    }
+
+    Ok(archive)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportResolution {
+    Keep,
+    Overwrite,
+    Rename,
+    Merge,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFileStatus {
+    New,
+    Identical,
+    Conflict,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportAction {
+    Create,
+    Skip,
+    Overwrite,
+    Rename,
+    Merge,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportFilePlan {
+    /// Path inside the archive.
+    pub path: String,
+    pub status: ImportFileStatus,
+    /// Whether the file supports a JSON-aware merge.
+    pub mergeable: bool,
+    pub action: ImportAction,
+    /// Workspace-relative path the file is written to, when it is written at all.
+    pub write_path: Option<String>,
+}
+
+fn is_config_path(path: &str) -> bool {
+    CONFIG_NAMES.contains(&path)
+}
+
+fn join_relative(root: &Path, rel: &str) -> PathBuf {
+    rel.split('/')
+        .fold(root.to_path_buf(), |path, part| path.join(part))
+}
+
+/// Where an archive entry lands in the target. An exported `opencode.json` merges into an
+/// existing `opencode.jsonc` rather than sitting next to it.
+fn existing_target(target_root: &Path, rel: &str) -> String {
+    if is_config_path(rel) {
+        if let Some(existing) = CONFIG_NAMES
+            .iter()
+            .find(|name| target_root.join(name).is_file())
+        {
+            return existing.to_string();
+        }
+    }
+    rel.to_string()
+}
+
+fn renamed_path(target_root: &Path, rel: &str, taken: &HashSet<String>) -> String {
+    let (dir, file) = rel.rsplit_once('/').unwrap_or(("", rel));
+    let (stem, ext) = match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{ext}")),
+        _ => (file, String::new()),
+    };
+    let mut attempt = 1;
+    loop {
+        let suffix = if attempt == 1 {
+            "-imported".to_string()
+        } else {
+            format!("-imported-{attempt}")
+        };
+        let name = format!("{stem}{suffix}{ext}");
+        let candidate = if dir.is_empty() {
+            name
+        } else {
+            format!("{dir}/{name}")
+        };
+        if !taken.contains(&candidate) && !join_relative(target_root, &candidate).exists() {
+            return candidate;
+        }
+        attempt += 1;
+    }
+}
+
+/// Decides what happens to every archive entry. Conflicts default to a merge for the
+/// OpenCode config and to keeping the local copy for everything else.
+pub fn plan_import(
+    archive: &WorkspaceArchive,
+    target_root: &Path,
+    resolutions: &BTreeMap<String, ImportResolution>,
+) -> Vec<ImportFilePlan> {
+    let mut taken = HashSet::new();
+    let mut plans = Vec::new();
+
+    for entry in &archive.entries {
+        let target_rel = existing_target(target_root, &entry.path);
+        let mergeable = is_config_path(&entry.path);
+        let existing = fs::read(join_relative(target_root, &target_rel)).ok();
+        let status = match existing {
+            None => ImportFileStatus::New,
+            Some(current) if current == entry.contents => ImportFileStatus::Identical,
+            Some(_) => ImportFileStatus::Conflict,
+        };
+
+        let (action, write_path) = match status {
+            ImportFileStatus::New => (ImportAction::Create, Some(target_rel)),
+            ImportFileStatus::Identical => (ImportAction::Skip, None),
+            ImportFileStatus::Conflict => {
+                let default = if mergeable {
+                    ImportResolution::Merge
+                } else {
+                    ImportResolution::Keep
+                };
+                // The workspace's own openwork.json carries local roots; never replace it.
+                let resolution = if entry.path == OPENWORK_CONFIG {
+                    ImportResolution::Keep
+                } else {
+                    resolutions.get(&entry.path).copied().unwrap_or(default)
+                };
+                match resolution {
+                    ImportResolution::Keep => (ImportAction::Skip, None),
+                    ImportResolution::Overwrite => (ImportAction::Overwrite, Some(target_rel)),
+                    ImportResolution::Merge if mergeable => (ImportAction::Merge, Some(target_rel)),
+                    ImportResolution::Merge => (ImportAction::Skip, None),
+                    ImportResolution::Rename => {
+                        let renamed = renamed_path(target_root, &entry.path, &taken);
+                        (ImportAction::Rename, Some(renamed))
+                    }
+                }
+            }
+        };
+
+        if let Some(path) = &write_path {
+            taken.insert(path.clone());
+        }
+        plans.push(ImportFilePlan {
+            path: entry.path.clone(),
+            status,
+            mergeable,
+            action,
+            write_path,
+        });
+    }
+
+    plans
+}
+
+fn plugin_list(config: &serde_json::Value) -> Vec<serde_json::Value> {
+    match config.get("plugin") {
+        Some(serde_json::Value::Array(items)) => items.clone(),
+        Some(value @ serde_json::Value::String(_)) => vec![value.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// Merges the `plugin` and `mcp` sections of an imported config into the local one. Local
+/// MCP servers win on name clashes; everything else in the local config is left alone,
+/// comments included.
+pub fn merge_opencode_config(
+    local: &mut JsoncDocument,
+    incoming: &serde_json::Value,
+) -> Result<(), String> {
+    let mcp = incoming
+        .get("mcp")
+        .and_then(|value| value.as_object())
+        .cloned()
+        .unwrap_or_default();
+    add_opencode_entries(local, &plugin_list(incoming), &mcp)?;
+    Ok(())
+}
+
+/// Writes the planned files. Returns secret placeholders left unfilled.
+pub fn apply_import(
+    archive: &WorkspaceArchive,
+    target_root: &Path,
+    plans: &[ImportFilePlan],
+    secrets: &BTreeMap<String, String>,
+) -> Result<Vec<String>, String> {
+    let mut missing = Vec::new();
+
+    for (entry, plan) in archive.entries.iter().zip(plans) {
+        let Some(write_path) = &plan.write_path else {
+            continue;
+        };
+        let out_path = join_relative(target_root, write_path);
+
+        let mut contents = entry.contents.clone();
+        if let Ok(text) = std::str::from_utf8(&contents) {
+            if text.contains(PLACEHOLDER_PREFIX) {
+                let mut text = text.to_string();
+                for placeholder in fill_placeholders(&mut text, secrets) {
+                    if !missing.contains(&placeholder) {
+                        missing.push(placeholder);
+                    }
+                }
+                contents = text.into_bytes();
+            }
+        }
+
+        if plan.action == ImportAction::Merge {
+            let raw = fs::read_to_string(&out_path)
+                .map_err(|e| format!("Failed to read {}: {e}", out_path.display()))?;
+            let mut local = JsoncDocument::parse(&raw)
+                .map_err(|e| format!("Failed to parse {}: {e}", out_path.display()))?;
+            let incoming: serde_json::Value = json5::from_str(&String::from_utf8_lossy(&contents))
+                .map_err(|e| format!("Failed to parse {}: {e}", entry.path))?;
+            merge_opencode_config(&mut local, &incoming)?;
+            contents = local.into_string().into_bytes();
+        }
+
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        fs::write(&out_path, contents)
+            .map_err(|e| format!("Failed to write {}: {e}", out_path.display()))?;
+    }
+
+    Ok(missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::TempDir;
+
+    fn entry(path: &str, contents: &str) -> ArchiveEntry {
+        ArchiveEntry {
+            path: path.to_string(),
+            contents: contents.as_bytes().to_vec(),
+        }
+    }
+
+    fn build_zip(files: &[(&str, &[u8])], symlink: Option<&str>) -> Vec<u8> {
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (path, contents) in files {
+            zip.start_file(*path, options).expect("start file");
+            zip.write_all(contents).expect("write file");
+        }
+        if let Some(path) = symlink {
+            zip.add_symlink(path, "/etc/passwd", options)
+                .expect("add symlink");
+        }
+        zip.finish().expect("finish zip").into_inner()
+    }
+
+    fn manifest_for(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let records: BTreeMap<String, ArchiveFileRecord> = files
+            .iter()
+            .map(|(path, contents)| {
+                (
+                    path.to_string(),
+                    ArchiveFileRecord {
+                        sha256: sha256_hex(contents),
+                        size: contents.len() as u64,
+                    },
+                )
+            })
+            .collect();
+        serde_json::to_vec(&serde_json::json!({
+            "version": ARCHIVE_VERSION,
+            "included": records.keys().collect::<Vec<_>>(),
+            "digest": archive_digest(&records),
+            "files": records,
+        }))
+        .expect("manifest")
+    }
+
+    fn with_manifest<'a>(
+        manifest: &'a [u8],
+        files: &[(&'a str, &'a [u8])],
+    ) -> Vec<(&'a str, &'a [u8])> {
+        let mut all = vec![(MANIFEST_NAME, manifest)];
+        all.extend_from_slice(files);
+        all
+    }
+
+    fn export_fixture() -> TempDir {
+        let root = TempDir::new("export-collect-test");
+        fs::create_dir_all(root.join(".opencode/node_modules/pkg")).expect("node_modules");
+        fs::create_dir_all(root.join("docs")).expect("docs");
+        fs::write(root.join("opencode.jsonc"), "{}").expect("config");
+        fs::write(root.join("AGENTS.md"), "rules").expect("agents");
+        fs::write(root.join(".opencode/openwork.json"), "{}").expect("openwork");
+        fs::write(root.join(".opencode/.env"), "TOKEN=1").expect("env");
+        fs::write(root.join(".opencode/node_modules/pkg/index.js"), "").expect("module");
+        fs::write(root.join("docs/guide.md"), "guide").expect("guide");
+        fs::write(root.join("docs/draft.md"), "draft").expect("draft");
+        root
+    }
+
+    #[test]
+    fn collects_jsonc_config_and_instructions_by_default() {
+        let root = export_fixture();
+        let entries = collect_export_entries(&root, &[], &[]).expect("collect");
+        let paths: Vec<&str> = entries.files.keys().map(String::as_str).collect();
+        assert_eq!(
+            paths,
+            vec![".opencode/openwork.json", "AGENTS.md", "opencode.jsonc"]
+        );
+    }
+
+    #[test]
+    fn collects_selected_extras_minus_exclusions() {
+        let root = export_fixture();
+        let entries = collect_export_entries(
+            &root,
+            &["docs/*.md".to_string()],
+            &["docs/draft.md".to_string()],
+        )
+        .expect("collect");
+        assert!(entries.files.contains_key("docs/guide.md"));
+        assert!(!entries.files.contains_key("docs/draft.md"));
+    }
+
+    #[test]
+    fn never_collects_env_files_or_node_modules() {
+        let root = export_fixture();
+        let entries = collect_export_entries(&root, &["**/*".to_string()], &[]).expect("collect");
+        assert!(entries
+            .files
+            .keys()
+            .all(|path| !path.contains("node_modules") && !path.ends_with(".env")));
+        assert_eq!(entries.excluded, vec![".opencode/.env".to_string()]);
+    }
+
+    const LISTED: &[(&str, &[u8])] = &[
+        ("opencode.json", b"{}"),
+        (".opencode/commands/daily.md", b"daily"),
+    ];
+
+    #[test]
+    fn reads_an_archive_that_matches_its_manifest() {
+        let manifest = manifest_for(LISTED);
+        let archive = read_workspace_archive(build_zip(&with_manifest(&manifest, LISTED), None))
+            .expect("valid archive");
+        assert_eq!(archive.entries.len(), 2);
+    }
+
+    #[test]
+    fn rejects_entries_whose_checksum_differs() {
+        let manifest = manifest_for(LISTED);
+        let tampered: &[(&str, &[u8])] = &[
+            ("opencode.json", b"{}"),
+            (".opencode/commands/daily.md", b"dAily"),
+        ];
+        assert_eq!(
+            read_workspace_archive(build_zip(&with_manifest(&manifest, tampered), None))
+                .unwrap_err(),
+            ArchiveError::ChecksumMismatch(".opencode/commands/daily.md".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_unlisted_and_missing_entries() {
+        let manifest = manifest_for(LISTED);
+        let mut extra = LISTED.to_vec();
+        extra.push((".opencode/commands/extra.md", b"extra"));
+        assert_eq!(
+            read_workspace_archive(build_zip(&with_manifest(&manifest, &extra), None)).unwrap_err(),
+            ArchiveError::UnlistedEntry(".opencode/commands/extra.md".to_string())
+        );
+
+        assert_eq!(
+            read_workspace_archive(build_zip(&with_manifest(&manifest, &LISTED[..1]), None))
+                .unwrap_err(),
+            ArchiveError::MissingEntry(".opencode/commands/daily.md".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_symlink_entries() {
+        let manifest = manifest_for(LISTED);
+        assert_eq!(
+            read_workspace_archive(build_zip(
+                &with_manifest(&manifest, LISTED),
+                Some(".opencode/link")
+            ))
+            .unwrap_err(),
+            ArchiveError::Symlink(".opencode/link".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_entries_that_expand_past_the_compression_ratio() {
+        let zeros = vec![0u8; 2 * 1024 * 1024];
+        let bomb: &[(&str, &[u8])] = &[(".opencode/zeros.md", &zeros)];
+        let bomb_manifest = manifest_for(bomb);
+        assert_eq!(
+            read_workspace_archive(build_zip(
+                &[(MANIFEST_NAME, bomb_manifest.as_slice()), bomb[0]],
+                None
+            ))
+            .unwrap_err(),
+            ArchiveError::CompressionRatio(".opencode/zeros.md".to_string())
+        );
+    }
+
+    fn existing_workspace() -> TempDir {
+        let target = TempDir::new("import-merge-test");
+        fs::create_dir_all(target.join(".opencode/commands")).expect("create target");
+        fs::write(
+            target.join("opencode.jsonc"),
+            r#"{ "plugin": ["local"], "mcp": { "docs": { "type": "local" } } }"#,
+        )
+        .expect("local config");
+        fs::write(target.join(".opencode/commands/daily.md"), "mine").expect("daily");
+        fs::write(target.join(".opencode/commands/weekly.md"), "mine").expect("weekly");
+        target
+    }
+
+    fn incoming_archive() -> WorkspaceArchive {
+        WorkspaceArchive {
+            entries: vec![
+                entry(
+                    "opencode.json",
+                    r#"{ "plugin": ["local", "shared"], "mcp": { "docs": { "type": "remote" }, "crm": { "type": "remote" } } }"#,
+                ),
+                entry(".opencode/commands/daily.md", "theirs"),
+                entry(".opencode/commands/weekly.md", "theirs"),
+                entry(".opencode/commands/standup.md", "new"),
+            ],
+            ..WorkspaceArchive::default()
+        }
+    }
+
+    fn rename_weekly() -> BTreeMap<String, ImportResolution> {
+        BTreeMap::from([(
+            ".opencode/commands/weekly.md".to_string(),
+            ImportResolution::Rename,
+        )])
+    }
+
+    #[test]
+    fn plans_per_file_choices_against_existing_workspace() {
+        let target = existing_workspace();
+        let plans = plan_import(&incoming_archive(), &target, &rename_weekly());
+        let actions: Vec<(ImportAction, Option<&str>)> = plans
+            .iter()
+            .map(|plan| (plan.action, plan.write_path.as_deref()))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                (ImportAction::Merge, Some("opencode.jsonc")),
+                (ImportAction::Skip, None),
+                (
+                    ImportAction::Rename,
+                    Some(".opencode/commands/weekly-imported.md")
+                ),
+                (ImportAction::Create, Some(".opencode/commands/standup.md")),
+            ]
+        );
+    }
+
+    #[test]
+    fn merges_config_into_the_existing_jsonc() {
+        let target = existing_workspace();
+        let archive = incoming_archive();
+        let plans = plan_import(&archive, &target, &rename_weekly());
+        apply_import(&archive, &target, &plans, &BTreeMap::new()).expect("apply");
+
+        let merged: serde_json::Value =
+            json5::from_str(&fs::read_to_string(target.join("opencode.jsonc")).unwrap()).unwrap();
+        assert_eq!(merged["plugin"], serde_json::json!(["local", "shared"]));
+        assert_eq!(merged["mcp"]["docs"]["type"], "local");
+        assert_eq!(merged["mcp"]["crm"]["type"], "remote");
+        assert!(!target.join("opencode.json").exists());
+    }
+
+    #[test]
+    fn skipped_files_stay_local_and_renamed_files_land_beside_them() {
+        let target = existing_workspace();
+        let archive = incoming_archive();
+        let plans = plan_import(&archive, &target, &rename_weekly());
+        apply_import(&archive, &target, &plans, &BTreeMap::new()).expect("apply");
+
+        assert_eq!(
+            fs::read_to_string(target.join(".opencode/commands/daily.md")).unwrap(),
+            "mine"
+        );
+        assert_eq!(
+            fs::read_to_string(target.join(".opencode/commands/weekly.md")).unwrap(),
+            "mine"
+        );
+        assert_eq!(
+            fs::read_to_string(target.join(".opencode/commands/weekly-imported.md")).unwrap(),
+            "theirs"
+        );
+        assert_eq!(
+            fs::read_to_string(target.join(".opencode/commands/standup.md")).unwrap(),
+            "new"
+        );
+    }
+}
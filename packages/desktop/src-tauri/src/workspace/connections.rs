@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{RemoteType, WorkspaceInfo, WorkspaceState, WorkspaceType};
+use crate::workspace::state::{stable_workspace_id_for_openwork, stable_workspace_id_for_remote};
+
+pub const CONNECTION_BUNDLE_KIND: &str = "openwork-connections";
+pub const CONNECTION_BUNDLE_VERSION: u32 = 1;
+
+/// Everything needed to recreate a remote workspace on another machine.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionProfile {
+    pub name: String,
+    #[serde(default)]
+    pub remote_type: RemoteType,
+    pub base_url: String,
+    #[serde(default)]
+    pub directory: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub openwork_host_url: Option<String>,
+    #[serde(default)]
+    pub openwork_workspace_id: Option<String>,
+    #[serde(default)]
+    pub openwork_workspace_name: Option<String>,
+    /// Only present when the exporter opted in to sharing tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionBundle {
+    pub kind: String,
+    pub version: u32,
+    pub created_at_ms: u64,
+    pub profiles: Vec<ConnectionProfile>,
+}
+
+impl ConnectionBundle {
+    pub fn new(profiles: Vec<ConnectionProfile>, created_at_ms: u64) -> Self {
+        Self {
+            kind: CONNECTION_BUNDLE_KIND.to_string(),
+            version: CONNECTION_BUNDLE_VERSION,
+            created_at_ms,
+            profiles,
+        }
+    }
+
+    pub fn parse(raw: &[u8]) -> Result<Self, String> {
+        let bundle: ConnectionBundle = serde_json::from_slice(raw)
+            .map_err(|e| format!("Failed to parse connection bundle: {e}"))?;
+        if bundle.kind != CONNECTION_BUNDLE_KIND {
+            return Err("File is not an OpenWork connection bundle".to_string());
+        }
+        if bundle.version == 0 || bundle.version > CONNECTION_BUNDLE_VERSION {
+            return Err(format!(
+                "Connection bundle version {} is not supported",
+                bundle.version
+            ));
+        }
+        Ok(bundle)
+    }
+}
+
+/// Remote workspaces added and skipped by an import.
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionImportSummary {
+    pub added: Vec<String>,
+    pub skipped: Vec<String>,
+    /// Tokens carried by the bundle that couldn't be stored in the vault, keyed by the new
+    /// workspace id, for the UI to store.
+    pub tokens: BTreeMap<String, String>,
+}
+
+fn clean(value: &Option<String>) -> Option<String> {
+    value
+        .as_ref()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Vault secret a remote workspace's token is stored under unless it references another.
+pub fn remote_token_secret_name(workspace_id: &str) -> String {
+    let suffix: String = workspace_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("OPENWORK_REMOTE_TOKEN_{suffix}")
+}
+
+fn is_http_url(value: &str) -> bool {
+    value.starts_with("http://") || value.starts_with("https://")
+}
+
+pub fn profile_from_workspace(
+    workspace: &WorkspaceInfo,
+    token: Option<String>,
+) -> Option<ConnectionProfile> {
+    if workspace.workspace_type != WorkspaceType::Remote {
+        return None;
+    }
+    Some(ConnectionProfile {
+        name: workspace.name.clone(),
+        remote_type: workspace.remote_type.clone().unwrap_or_default(),
+        base_url: workspace.base_url.clone()?,
+        directory: workspace.directory.clone(),
+        display_name: workspace.display_name.clone(),
+        openwork_host_url: workspace.openwork_host_url.clone(),
+        openwork_workspace_id: workspace.openwork_workspace_id.clone(),
+        openwork_workspace_name: workspace.openwork_workspace_name.clone(),
+        token: token.filter(|value| !value.is_empty()),
+    })
+}
+
+/// Builds the workspace entry `workspace_create_remote` would for the same inputs.
+pub fn workspace_from_profile(profile: &ConnectionProfile) -> Result<WorkspaceInfo, String> {
+    let base_url = profile.base_url.trim().to_string();
+    if !is_http_url(&base_url) {
+        return Err(format!(
+            "{}: baseUrl must start with http:// or https://",
+            profile.name
+        ));
+    }
+    let directory = clean(&profile.directory);
+    let openwork_host_url = clean(&profile.openwork_host_url);
+    let openwork_workspace_id = clean(&profile.openwork_workspace_id);
+
+    let id = if profile.remote_type == RemoteType::Openwork {
+        let host_url = openwork_host_url.as_deref().unwrap_or("");
+        if !is_http_url(host_url) {
+            return Err(format!(
+                "{}: openworkHostUrl must start with http:// or https://",
+                profile.name
+            ));
+        }
+        stable_workspace_id_for_openwork(host_url, openwork_workspace_id.as_deref())
+    } else {
+        stable_workspace_id_for_remote(&base_url, directory.as_deref())
+    };
+
+    let name = Some(profile.name.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| base_url.clone());
+
+    Ok(WorkspaceInfo {
+        id,
+        name,
+        path: directory.clone().unwrap_or_default(),
+        preset: "remote".to_string(),
+        workspace_type: WorkspaceType::Remote,
+        remote_type: Some(profile.remote_type.clone()),
+        base_url: Some(base_url),
+        directory,
+        display_name: clean(&profile.display_name),
+        openwork_host_url,
+        openwork_workspace_id,
+        openwork_workspace_name: clean(&profile.openwork_workspace_name),
+        token_secret: None,
+        opencode_profile: None,
+        engine_launcher: None,
+        engine_sandbox: false,
+        resource_limits: None,
+    })
+}
+
+/// Two entries point at the same place, even if one was edited after it was created and no
+/// longer has the id its fields would produce today.
+fn same_connection(a: &WorkspaceInfo, b: &WorkspaceInfo) -> bool {
+    let remote_type = a.remote_type.clone().unwrap_or_default();
+    if remote_type != b.remote_type.clone().unwrap_or_default() {
+        return false;
+    }
+    if remote_type == RemoteType::Openwork {
+        a.openwork_host_url == b.openwork_host_url
+            && a.openwork_workspace_id == b.openwork_workspace_id
+    } else {
+        a.base_url == b.base_url && a.directory == b.directory
+    }
+}
+
+/// Adds the bundle's workspaces to `state`, skipping ones that already exist.
+pub fn import_connection_profiles(
+    state: &mut WorkspaceState,
+    bundle: &ConnectionBundle,
+) -> Result<ConnectionImportSummary, String> {
+    let mut summary = ConnectionImportSummary::default();
+
+    for profile in &bundle.profiles {
+        let workspace = workspace_from_profile(profile)?;
+        let exists = state.workspaces.iter().any(|existing| {
+            existing.id == workspace.id
+                || (existing.workspace_type == WorkspaceType::Remote
+                    && same_connection(existing, &workspace))
+        });
+        if exists {
+            summary.skipped.push(workspace.id);
+            continue;
+        }
+        if let Some(token) = &profile.token {
+            summary.tokens.insert(workspace.id.clone(), token.clone());
+        }
+        summary.added.push(workspace.id.clone());
+        state.workspaces.push(workspace);
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(base_url: &str, directory: Option<&str>) -> ConnectionProfile {
+        ConnectionProfile {
+            name: "Team".to_string(),
+            remote_type: RemoteType::Opencode,
+            base_url: base_url.to_string(),
+            directory: directory.map(str::to_string),
+            display_name: None,
+            openwork_host_url: None,
+            openwork_workspace_id: None,
+            openwork_workspace_name: None,
+            token: None,
+        }
+    }
+
+    #[test]
+    fn imports_profiles_with_stable_ids_and_skips_duplicates() {
+        let existing = workspace_from_profile(&profile("https://a.example", Some("/srv/a")))
+            .expect("existing");
+        let mut state = WorkspaceState::default();
+        state.workspaces.push(existing.clone());
+
+        let mut with_token = profile("https://b.example", None);
+        with_token.token = Some("tok".to_string());
+        let mut openwork = profile("https://c.example", None);
+        openwork.remote_type = RemoteType::Openwork;
+        openwork.openwork_host_url = Some("https://c.example".to_string());
+        openwork.openwork_workspace_id = Some("ws-1".to_string());
+
+        let raw = serde_json::to_vec(&ConnectionBundle::new(
+            vec![
+                profile("https://a.example", Some("/srv/a")),
+                with_token,
+                openwork,
+            ],
+            0,
+        ))
+        .expect("serialize");
+        let bundle = ConnectionBundle::parse(&raw).expect("parse");
+
+        let summary = import_connection_profiles(&mut state, &bundle).expect("import");
+        let remote_id = stable_workspace_id_for_remote("https://b.example", None);
+        let openwork_id = stable_workspace_id_for_openwork("https://c.example", Some("ws-1"));
+        assert_eq!(summary.skipped, vec![existing.id]);
+        assert_eq!(summary.added, vec![remote_id.clone(), openwork_id]);
+        assert_eq!(
+            summary.tokens.get(&remote_id).map(String::as_str),
+            Some("tok")
+        );
+        assert_eq!(state.workspaces.len(), 3);
+
+        let again = import_connection_profiles(&mut state, &bundle).expect("reimport");
+        assert!(again.added.is_empty());
+        assert_eq!(state.workspaces.len(), 3);
+    }
+}
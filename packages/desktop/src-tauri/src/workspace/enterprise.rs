@@ -0,0 +1,469 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+use zip::ZipArchive;
+
+use crate::settings::load_app_settings;
+use crate::workspace::seed::sha256_hex;
+
+pub const ENTERPRISE_SEED_EVENT: &str = "openwork://enterprise-skills-progress";
+const ENTERPRISE_SEED_MARKER: &str = ".openwork-enterprise-creators";
+const ARCHIVE_LABEL: &str = "enterprise archive";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveOrigin {
+    Network,
+    Cache,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EnterpriseSeedProgress {
+    pub workspace_path: String,
+    pub phase: String,
+    pub origin: Option<ArchiveOrigin>,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub seeded_skills: usize,
+    pub error: Option<String>,
+}
+
+impl EnterpriseSeedProgress {
+    fn new(workspace_path: &str, phase: &str) -> Self {
+        Self {
+            workspace_path: workspace_path.to_string(),
+            phase: phase.to_string(),
+            origin: None,
+            downloaded_bytes: 0,
+            total_bytes: None,
+            seeded_skills: 0,
+            error: None,
+        }
+    }
+}
+
+/// Where to look for the enterprise archive, in order: network, then cache.
+#[derive(Debug, Clone)]
+pub struct ArchiveRequest {
+    pub url: String,
+    pub sha256: Option<String>,
+    pub cache_path: PathBuf,
+    pub timeout: Duration,
+}
+
+fn verify_archive(bytes: &[u8], expected: Option<&str>) -> Result<(), String> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+    let actual = sha256_hex(bytes);
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Enterprise archive checksum mismatch: expected {expected}, got {actual}"
+        ))
+    }
+}
+
+fn read_verified(path: &Path, expected: Option<&str>) -> Result<Vec<u8>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    verify_archive(&bytes, expected)?;
+    Ok(bytes)
+}
+
+/// Downloads `url` into memory, reporting progress at most every 100ms. `label` names the
+/// download in errors.
+pub fn download_archive(
+    url: &str,
+    label: &str,
+    timeout: Duration,
+    on_progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<Vec<u8>, String> {
+    let agent = ureq::AgentBuilder::new()
+        .redirects(5)
+        .timeout_connect(CONNECT_TIMEOUT.min(timeout))
+        .timeout(timeout)
+        .build();
+    let response = agent
+        .get(url)
+        .call()
+        .map_err(|e| format!("Failed to download {label}: {e}"))?;
+    let total = response
+        .header("Content-Length")
+        .and_then(|value| value.parse::<u64>().ok());
+
+    let mut reader = response.into_reader();
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 64 * 1024];
+    let mut last_emit: Option<Instant> = None;
+    loop {
+        let read = reader
+            .read(&mut chunk)
+            .map_err(|e| format!("Failed to read {label}: {e}"))?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if last_emit.is_none_or(|at| at.elapsed() >= Duration::from_millis(100)) {
+            last_emit = Some(Instant::now());
+            on_progress(buffer.len() as u64, total);
+        }
+    }
+    on_progress(buffer.len() as u64, total);
+    Ok(buffer)
+}
+
+fn write_cache(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let partial = path.with_extension("zip.partial");
+    fs::write(&partial, bytes)
+        .map_err(|e| format!("Failed to write {}: {e}", partial.display()))?;
+    fs::rename(&partial, path).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Fetches the archive, preferring a verified cache when the checksum is pinned and
+/// falling back to the cache when the network is unavailable.
+pub fn resolve_enterprise_archive(
+    request: &ArchiveRequest,
+    on_progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<(Vec<u8>, ArchiveOrigin), String> {
+    let expected = request
+        .sha256
+        .as_deref()
+        .map(str::trim)
+        .filter(|sha| !sha.is_empty());
+
+    if expected.is_some() {
+        if let Ok(bytes) = read_verified(&request.cache_path, expected) {
+            return Ok((bytes, ArchiveOrigin::Cache));
+        }
+    }
+
+    let downloaded = download_archive(&request.url, ARCHIVE_LABEL, request.timeout, on_progress)
+        .and_then(|bytes| verify_archive(&bytes, expected).map(|_| bytes));
+    let network_error = match downloaded {
+        Ok(bytes) => {
+            if let Err(error) = write_cache(&request.cache_path, &bytes) {
+                println!("[workspace] Failed to cache enterprise archive: {error}");
+            }
+            return Ok((bytes, ArchiveOrigin::Network));
+        }
+        Err(error) => error,
+    };
+    println!("[workspace] {network_error}; trying the cached copy");
+
+    match read_verified(&request.cache_path, expected) {
+        Ok(bytes) => Ok((bytes, ArchiveOrigin::Cache)),
+        Err(_) => Err(network_error),
+    }
+}
+
+/// Extracts `*/.opencode/skills/*-creator/**` into `skill_root`, leaving existing skills alone.
+pub fn extract_creator_skills(bytes: Vec<u8>, skill_root: &Path) -> Result<usize, String> {
+    let mut existing = HashSet::new();
+    if let Ok(entries) = fs::read_dir(skill_root) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.is_empty() {
+                existing.insert(name);
+            }
+        }
+    }
+
+    let mut archive = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| format!("Failed to open enterprise archive: {e}"))?;
+    let mut seeded = HashSet::new();
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read enterprise entry: {e}"))?;
+        let name = entry.name().to_string();
+        let entry_path = Path::new(&name);
+        if entry_path.components().any(|component| {
+            matches!(
+                component,
+                Component::ParentDir | Component::RootDir | Component::Prefix(_)
+            )
+        }) {
+            continue;
+        }
+
+        let parts: Vec<String> = entry_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect();
+        if parts.len() < 5 {
+            continue;
+        }
+        if parts[1] != ".opencode" || parts[2] != "skills" {
+            continue;
+        }
+
+        let skill_name = &parts[3];
+        if !skill_name.ends_with("-creator") {
+            continue;
+        }
+        if existing.contains(skill_name) {
+            continue;
+        }
+
+        let dest_root = skill_root.join(skill_name);
+        let mut dest_path = dest_root.clone();
+        for part in parts.iter().skip(4) {
+            dest_path = dest_path.join(part);
+        }
+
+        if name.ends_with('/') {
+            fs::create_dir_all(&dest_path)
+                .map_err(|e| format!("Failed to create {}: {e}", dest_path.display()))?;
+            continue;
+        }
+
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+
+        let mut file_buffer = Vec::new();
+        entry
+            .read_to_end(&mut file_buffer)
+            .map_err(|e| format!("Failed to read enterprise entry: {e}"))?;
+        fs::write(&dest_path, file_buffer)
+            .map_err(|e| format!("Failed to write {}: {e}", dest_path.display()))?;
+        seeded.insert(skill_name.clone());
+    }
+
+    Ok(seeded.len())
+}
+
+fn seed_enterprise_creator_skills(
+    root: &Path,
+    request: &ArchiveRequest,
+    emit: &mut dyn FnMut(EnterpriseSeedProgress),
+) -> Result<Option<usize>, String> {
+    let workspace_path = root.to_string_lossy().to_string();
+    let marker_path = root.join(".opencode").join(ENTERPRISE_SEED_MARKER);
+    if marker_path.exists() {
+        return Ok(None);
+    }
+
+    emit(EnterpriseSeedProgress::new(&workspace_path, "downloading"));
+    let (bytes, origin) = resolve_enterprise_archive(request, &mut |downloaded, total| {
+        let mut progress = EnterpriseSeedProgress::new(&workspace_path, "downloading");
+        progress.downloaded_bytes = downloaded;
+        progress.total_bytes = total;
+        emit(progress);
+    })?;
+
+    let mut progress = EnterpriseSeedProgress::new(&workspace_path, "extracting");
+    progress.origin = Some(origin);
+    emit(progress);
+
+    let sha = sha256_hex(&bytes);
+    let skill_root = root.join(".opencode").join("skills");
+    let seeded = extract_creator_skills(bytes, &skill_root)?;
+    fs::write(&marker_path, format!("seeded {sha}\n"))
+        .map_err(|e| format!("Failed to write {}: {e}", marker_path.display()))?;
+
+    let mut progress = EnterpriseSeedProgress::new(&workspace_path, "done");
+    progress.origin = Some(origin);
+    progress.seeded_skills = seeded;
+    emit(progress);
+    Ok(Some(seeded))
+}
+
+fn in_flight() -> &'static Mutex<HashSet<PathBuf>> {
+    static IN_FLIGHT: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+    IN_FLIGHT.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+fn archive_request(app: &tauri::AppHandle) -> Result<ArchiveRequest, String> {
+    let settings = load_app_settings(app)?.enterprise_skills;
+    let url = settings.resolved_archive_url();
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to resolve app cache dir: {e}"))?
+        .join("enterprise-skills");
+    // Key the cache by URL so switching refs never serves a stale archive.
+    let cache_key = &sha256_hex(url.as_bytes())[..16];
+
+    Ok(ArchiveRequest {
+        url,
+        sha256: settings.sha256,
+        cache_path: cache_dir.join(format!("{cache_key}.zip")),
+        timeout: DOWNLOAD_TIMEOUT,
+    })
+}
+
+/// Seeds creator skills on a background thread so bootstrap never waits on the network.
+/// Progress and failures are reported through [`ENTERPRISE_SEED_EVENT`].
+pub fn schedule_enterprise_seed(app: &tauri::AppHandle, workspace_path: &str) {
+    let root = PathBuf::from(workspace_path);
+    if root.join(".opencode").join(ENTERPRISE_SEED_MARKER).exists() {
+        return;
+    }
+    {
+        let mut running = in_flight().lock().expect("enterprise seed lock poisoned");
+        if !running.insert(root.clone()) {
+            return;
+        }
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut emit = |progress: EnterpriseSeedProgress| {
+            let _ = app.emit(ENTERPRISE_SEED_EVENT, progress);
+        };
+        let result = archive_request(&app)
+            .and_then(|request| seed_enterprise_creator_skills(&root, &request, &mut emit));
+        match result {
+            Ok(Some(seeded)) => {
+                println!(
+                    "[workspace] Seeded {seeded} creator skills into {}",
+                    root.display()
+                );
+            }
+            Ok(None) => {}
+            Err(error) => {
+                println!("[workspace] Failed to seed creator skills: {error}");
+                let mut progress = EnterpriseSeedProgress::new(&root.to_string_lossy(), "failed");
+                progress.error = Some(error);
+                emit(progress);
+            }
+        }
+        if let Ok(mut running) = in_flight().lock() {
+            running.remove(&root);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::TempDir;
+    use std::io::Write;
+    use std::net::TcpListener;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    fn enterprise_zip() -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default();
+        for (path, body) in [
+            (
+                "openwork-enterprise-main/.opencode/skills/docs-creator/SKILL.md",
+                "docs",
+            ),
+            (
+                "openwork-enterprise-main/.opencode/skills/internal/SKILL.md",
+                "skip",
+            ),
+        ] {
+            writer.start_file(path, options).expect("start file");
+            writer.write_all(body.as_bytes()).expect("write file");
+        }
+        writer.finish().expect("finish zip").into_inner()
+    }
+
+    /// Serves `body` once over plain HTTP and returns the URL.
+    fn serve_once(body: Vec<u8>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept");
+            let mut request = Vec::new();
+            let mut byte = [0u8; 1];
+            while !request.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap_or(0) == 1 {
+                request.push(byte[0]);
+            }
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(header.as_bytes());
+            let _ = stream.write_all(&body);
+        });
+        format!("http://{addr}/archive/main.zip")
+    }
+
+    fn unreachable_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        drop(listener);
+        format!("http://{addr}/archive/main.zip")
+    }
+
+    fn request(base: &Path, url: String, sha256: Option<String>) -> ArchiveRequest {
+        ArchiveRequest {
+            url,
+            sha256,
+            cache_path: base.join("cache").join("archive.zip"),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    #[test]
+    fn downloads_verifies_and_caches_the_archive() {
+        let base = TempDir::new("enterprise-download");
+        let archive = enterprise_zip();
+        let request = request(
+            &base,
+            serve_once(archive.clone()),
+            Some(sha256_hex(&archive)),
+        );
+
+        let (bytes, origin) = resolve_enterprise_archive(&request, &mut |_, _| {}).expect("fetch");
+        assert_eq!(origin, ArchiveOrigin::Network);
+        assert_eq!(bytes, archive);
+        assert_eq!(fs::read(&request.cache_path).expect("cache"), archive);
+    }
+
+    #[test]
+    fn falls_back_to_the_cache_when_offline() {
+        let base = TempDir::new("enterprise-offline");
+        let archive = enterprise_zip();
+        let request = request(&base, unreachable_url(), None);
+        assert!(resolve_enterprise_archive(&request, &mut |_, _| {}).is_err());
+
+        write_cache(&request.cache_path, &archive).expect("cache");
+        let (bytes, origin) = resolve_enterprise_archive(&request, &mut |_, _| {}).expect("cache");
+        assert_eq!(origin, ArchiveOrigin::Cache);
+        assert_eq!(bytes, archive);
+    }
+
+    #[test]
+    fn rejects_a_cached_archive_with_the_wrong_checksum() {
+        let base = TempDir::new("enterprise-checksum");
+        let request = request(&base, unreachable_url(), Some("0".repeat(64)));
+        write_cache(&request.cache_path, &enterprise_zip()).expect("cache");
+
+        assert!(resolve_enterprise_archive(&request, &mut |_, _| {}).is_err());
+    }
+
+    #[test]
+    fn extracts_only_creator_skills() {
+        let base = TempDir::new("enterprise-extract");
+        let skill_root = base.join("skills");
+
+        assert_eq!(
+            extract_creator_skills(enterprise_zip(), &skill_root).expect("extract"),
+            1
+        );
+        assert!(skill_root.join("docs-creator").join("SKILL.md").is_file());
+        assert!(!skill_root.join("internal").exists());
+    }
+}
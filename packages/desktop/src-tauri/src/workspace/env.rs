@@ -0,0 +1,643 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::effective::resolve_effective_config;
+use crate::config::mcp::env_references;
+use crate::engine::profiles::workspace_config_inputs;
+use crate::types::WorkspaceOpenworkConfig;
+use crate::vault::{secret_reference, Vault};
+use crate::workspace::files::{read_workspace_openwork_config, write_workspace_openwork_config};
+use crate::workspace::state::{load_workspace_state, stable_workspace_id};
+
+/// Set by every spawn after the workspace env, so they always win.
+pub const RESERVED_ENV_NAMES: &[&str] = &["OPENCODE_CLIENT", "OPENWORK"];
+
+/// Where a variable came from. Later sources override earlier ones in this order.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum EnvSource {
+    EnvFile,
+    Openwork,
+    Vault,
+}
+
+impl EnvSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EnvSource::EnvFile => "env-file",
+            EnvSource::Openwork => "openwork",
+            EnvSource::Vault => "vault",
+        }
+    }
+}
+
+/// One effective variable. `value` is only filled for plain `openwork.json` entries.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceEnvVar {
+    pub name: String,
+    pub source: EnvSource,
+    /// The env file that defined it, for `env-file` variables.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvFileStatus {
+    /// Entry as written in `envFiles`.
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved: Option<String>,
+    pub loaded: bool,
+    pub keys: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceEnvReport {
+    pub vars: Vec<WorkspaceEnvVar>,
+    pub files: Vec<EnvFileStatus>,
+    /// Never contains values, only names, files and line numbers.
+    pub warnings: Vec<String>,
+}
+
+/// What the vault contributes to a workspace's environment.
+#[derive(Default)]
+pub struct EnvSecrets {
+    /// Secret variables stored for this workspace.
+    pub workspace: BTreeMap<String, String>,
+    /// Named vault secrets, for `{vault:NAME}` values in `openwork.json`.
+    pub named: BTreeMap<String, String>,
+    /// Variables the OpenCode config reads through `{env:NAME}` in MCP headers and
+    /// environments; matching named secrets are injected under that name.
+    pub referenced: BTreeSet<String>,
+}
+
+pub struct ResolvedEnv {
+    pub vars: BTreeMap<String, String>,
+    pub report: WorkspaceEnvReport,
+}
+
+pub fn validate_env_name(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if !valid_start || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("Invalid environment variable name: {name:?}"));
+    }
+    if RESERVED_ENV_NAMES.contains(&name) {
+        return Err(format!("{name} is set by OpenWork and can't be overridden"));
+    }
+    Ok(())
+}
+
+fn unescape_double_quoted(raw: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(out),
+            '\\' => match chars.next()? {
+                'n' => out.push('\n'),
+                'r' => out.push('\r'),
+                't' => out.push('\t'),
+                other => out.push(other),
+            },
+            other => out.push(other),
+        }
+    }
+    None
+}
+
+/// Parses `.env` syntax: `KEY=value`, optional `export`, single or double quotes and `#`
+/// comments. Warnings name the line, never the value, and never a rejected name either: the
+/// text before `=` may be a pasted token.
+pub fn parse_env_file(contents: &str) -> (Vec<(String, String)>, Vec<String>) {
+    let mut vars = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+        let Some((name, raw)) = line.split_once('=') else {
+            warnings.push(format!("line {line_no}: expected KEY=value"));
+            continue;
+        };
+        let name = name.trim();
+        if validate_env_name(name).is_err() {
+            let reason = if RESERVED_ENV_NAMES.contains(&name) {
+                "variable is set by OpenWork and can't be overridden"
+            } else {
+                "invalid variable name"
+            };
+            warnings.push(format!("line {line_no}: {reason}"));
+            continue;
+        }
+
+        let raw = raw.trim();
+        let value = if let Some(rest) = raw.strip_prefix('"') {
+            unescape_double_quoted(rest)
+        } else if let Some(rest) = raw.strip_prefix('\'') {
+            rest.find('\'').map(|end| rest[..end].to_string())
+        } else {
+            let end = raw.find(" #").unwrap_or(raw.len());
+            Some(raw[..end].trim_end().to_string())
+        };
+        match value {
+            Some(value) => vars.push((name.to_string(), value)),
+            None => warnings.push(format!("line {line_no}: unterminated quote for {name}")),
+        }
+    }
+
+    (vars, warnings)
+}
+
+/// The workspace root and its `authorizedRoots`, canonicalized. Missing ones are dropped.
+pub fn authorized_roots(root: &Path, config: &WorkspaceOpenworkConfig) -> Vec<PathBuf> {
+    std::iter::once(root.to_path_buf())
+        .chain(
+            config
+                .authorized_roots
+                .iter()
+                .map(|entry| entry.trim())
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| root.join(entry)),
+        )
+        .filter_map(|dir| fs::canonicalize(dir).ok())
+        .collect()
+}
+
+/// Resolves an `envFiles` entry and checks it stays inside an authorized root, following
+/// symlinks. The file itself doesn't have to exist yet.
+pub fn resolve_env_file(
+    root: &Path,
+    config: &WorkspaceOpenworkConfig,
+    entry: &str,
+) -> Result<PathBuf, String> {
+    let entry = entry.trim();
+    if entry.is_empty() {
+        return Err("Env file path is empty".to_string());
+    }
+    let path = root.join(entry);
+    let resolved = if path.exists() {
+        fs::canonicalize(&path).map_err(|e| format!("Failed to resolve {}: {e}", path.display()))?
+    } else {
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            return Err(format!("Invalid env file path: {entry}"));
+        };
+        fs::canonicalize(parent)
+            .map_err(|e| format!("Failed to resolve {}: {e}", parent.display()))?
+            .join(name)
+    };
+
+    if !authorized_roots(root, config)
+        .iter()
+        .any(|allowed| resolved.starts_with(allowed))
+    {
+        return Err(format!("{entry} is not within an authorized root"));
+    }
+    Ok(resolved)
+}
+
+/// Merges, lowest priority first: named secrets referenced by MCP entries, `envFiles` in
+/// listed order, `env` from `openwork.json` (where `{vault:NAME}` reads a named secret),
+/// then workspace secrets. The result overrides the inherited environment and OpenWork's
+/// inferred `XDG_*`/`PATH`, but never `RESERVED_ENV_NAMES`.
+pub fn resolve_workspace_env(
+    root: &Path,
+    config: &WorkspaceOpenworkConfig,
+    secrets: &EnvSecrets,
+) -> ResolvedEnv {
+    let mut vars = BTreeMap::new();
+    let mut entries: BTreeMap<String, WorkspaceEnvVar> = BTreeMap::new();
+    let mut report = WorkspaceEnvReport::default();
+
+    let mut define = |name: &str, value: &str, var: WorkspaceEnvVar, warnings: &mut Vec<String>| {
+        if let Err(error) = validate_env_name(name) {
+            warnings.push(error);
+            return;
+        }
+        vars.insert(name.to_string(), value.to_string());
+        entries.insert(name.to_string(), var);
+    };
+
+    for name in &secrets.referenced {
+        let Some(value) = secrets.named.get(name) else {
+            continue;
+        };
+        let var = WorkspaceEnvVar {
+            name: name.clone(),
+            source: EnvSource::Vault,
+            origin: Some("mcp".to_string()),
+            value: None,
+        };
+        define(name, value, var, &mut report.warnings);
+    }
+
+    for entry in &config.env_files {
+        let mut status = EnvFileStatus {
+            path: entry.clone(),
+            resolved: None,
+            loaded: false,
+            keys: Vec::new(),
+            error: None,
+        };
+        let contents = resolve_env_file(root, config, entry).and_then(|path| {
+            status.resolved = Some(path.to_string_lossy().to_string());
+            fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+        });
+        match contents {
+            Ok(contents) => {
+                let (parsed, warnings) = parse_env_file(&contents);
+                report.warnings.extend(
+                    warnings
+                        .into_iter()
+                        .map(|warning| format!("{entry} {warning}")),
+                );
+                for (name, value) in parsed {
+                    let var = WorkspaceEnvVar {
+                        name: name.clone(),
+                        source: EnvSource::EnvFile,
+                        origin: Some(entry.clone()),
+                        value: None,
+                    };
+                    define(&name, &value, var, &mut report.warnings);
+                    status.keys.push(name);
+                }
+                status.loaded = true;
+            }
+            Err(error) => status.error = Some(error),
+        }
+        report.files.push(status);
+    }
+
+    for (name, value) in &config.env {
+        if let Some(secret) = secret_reference(value) {
+            let Some(resolved) = secrets.named.get(secret) else {
+                report
+                    .warnings
+                    .push(format!("{name} references missing vault secret {secret}"));
+                continue;
+            };
+            let var = WorkspaceEnvVar {
+                name: name.clone(),
+                source: EnvSource::Vault,
+                origin: Some(value.trim().to_string()),
+                value: None,
+            };
+            define(name, resolved, var, &mut report.warnings);
+            continue;
+        }
+        let var = WorkspaceEnvVar {
+            name: name.clone(),
+            source: EnvSource::Openwork,
+            origin: None,
+            value: Some(value.clone()),
+        };
+        define(name, value, var, &mut report.warnings);
+    }
+
+    for (name, value) in &secrets.workspace {
+        let var = WorkspaceEnvVar {
+            name: name.clone(),
+            source: EnvSource::Vault,
+            origin: None,
+            value: None,
+        };
+        define(name, value, var, &mut report.warnings);
+    }
+
+    report.vars = entries.into_values().collect();
+    ResolvedEnv { vars, report }
+}
+
+/// Stores `name` in `openwork.json` or, for secrets, in the vault, and drops it from the
+/// other place so only one definition remains.
+pub fn set_workspace_env(
+    root: &Path,
+    vault: &Vault,
+    workspace_id: &str,
+    name: &str,
+    value: &str,
+    secret: bool,
+) -> Result<(), String> {
+    validate_env_name(name)?;
+    let mut config = read_workspace_openwork_config(root)?;
+
+    if secret {
+        vault.update(|data| {
+            data.workspace_env
+                .entry(workspace_id.to_string())
+                .or_default()
+                .insert(name.to_string(), value.to_string());
+            Ok(())
+        })?;
+        if config.env.remove(name).is_some() {
+            write_workspace_openwork_config(root, &config)?;
+        }
+    } else {
+        config.env.insert(name.to_string(), value.to_string());
+        write_workspace_openwork_config(root, &config)?;
+        remove_vault_entry(vault, workspace_id, name)?;
+    }
+    Ok(())
+}
+
+fn remove_vault_entry(vault: &Vault, workspace_id: &str, name: &str) -> Result<bool, String> {
+    if !vault.workspace_env(workspace_id)?.contains_key(name) {
+        return Ok(false);
+    }
+    vault.update(|data| {
+        let Some(env) = data.workspace_env.get_mut(workspace_id) else {
+            return Ok(false);
+        };
+        let removed = env.remove(name).is_some();
+        if env.is_empty() {
+            data.workspace_env.remove(workspace_id);
+        }
+        Ok(removed)
+    })
+}
+
+pub fn remove_workspace_env(
+    root: &Path,
+    vault: &Vault,
+    workspace_id: &str,
+    name: &str,
+) -> Result<(), String> {
+    let mut config = read_workspace_openwork_config(root)?;
+    let in_config = config.env.remove(name).is_some();
+    if in_config {
+        write_workspace_openwork_config(root, &config)?;
+    }
+    let in_vault = remove_vault_entry(vault, workspace_id, name)?;
+    if !in_config && !in_vault {
+        return Err(format!(
+            "{name} is not set in openwork.json or the vault; edit the env file that defines it"
+        ));
+    }
+    Ok(())
+}
+
+pub fn set_workspace_env_files(root: &Path, files: &[String]) -> Result<(), String> {
+    let mut config = read_workspace_openwork_config(root)?;
+    let mut entries = Vec::new();
+    for entry in files.iter().map(|entry| entry.trim()) {
+        resolve_env_file(root, &config, entry)?;
+        if !entries.iter().any(|existing| existing == entry) {
+            entries.push(entry.to_string());
+        }
+    }
+    config.env_files = entries;
+    write_workspace_openwork_config(root, &config)
+}
+
+/// Vault entries are keyed by workspace id so they follow a workspace when it moves.
+pub fn workspace_id_for_path(app: &tauri::AppHandle, workspace_path: &str) -> String {
+    let workspace_path = workspace_path.trim();
+    load_workspace_state(app)
+        .ok()
+        .and_then(|state| {
+            state
+                .workspaces
+                .into_iter()
+                .find(|workspace| workspace.path == workspace_path)
+                .map(|workspace| workspace.id)
+        })
+        .unwrap_or_else(|| stable_workspace_id(workspace_path))
+}
+
+pub fn load_workspace_env(
+    app: &tauri::AppHandle,
+    workspace_path: &str,
+) -> Result<ResolvedEnv, String> {
+    let root = PathBuf::from(workspace_path.trim());
+    let config = read_workspace_openwork_config(&root)?;
+    let effective =
+        resolve_effective_config(&workspace_config_inputs(app, Some(&root)), Some(&root));
+
+    // A locked or unreadable vault shouldn't keep the engine from starting without secrets.
+    let (secrets, vault_error) = match Vault::for_app(app).and_then(|vault| vault.load()) {
+        Ok(mut data) => {
+            let secrets = EnvSecrets {
+                workspace: data
+                    .workspace_env
+                    .remove(&workspace_id_for_path(app, workspace_path))
+                    .unwrap_or_default(),
+                named: data
+                    .secrets
+                    .into_iter()
+                    .map(|(name, secret)| (name, secret.value))
+                    .collect(),
+                referenced: env_references(&effective.config),
+            };
+            (secrets, None)
+        }
+        Err(error) => (EnvSecrets::default(), Some(error)),
+    };
+
+    let mut resolved = resolve_workspace_env(&root, &config, &secrets);
+    if let Some(error) = vault_error {
+        resolved
+            .report
+            .warnings
+            .push(format!("Vault secrets not injected: {error}"));
+    }
+    Ok(resolved)
+}
+
+/// Variables to inject into a process spawned for `workspace_path`. Logs names and sources
+/// only; a broken config or vault is reported and skipped rather than blocking the spawn.
+pub fn workspace_spawn_env(
+    app: &tauri::AppHandle,
+    workspace_path: &str,
+    label: &str,
+) -> Vec<(String, String)> {
+    let resolved = match load_workspace_env(app, workspace_path) {
+        Ok(resolved) => resolved,
+        Err(error) => {
+            println!("[workspace] {label} env skipped: {error}");
+            return Vec::new();
+        }
+    };
+    for warning in &resolved.report.warnings {
+        println!("[workspace] {label} env: {warning}");
+    }
+    for file in &resolved.report.files {
+        if let Some(error) = &file.error {
+            println!("[workspace] {label} env file {}: {error}", file.path);
+        }
+    }
+    if !resolved.vars.is_empty() {
+        let names: Vec<String> = resolved
+            .report
+            .vars
+            .iter()
+            .map(|var| format!("{} ({})", var.name, var.source.label()))
+            .collect();
+        println!("[workspace] {label} env: {}", names.join(", "));
+    }
+    resolved.vars.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::TempDir;
+
+    struct Fixture {
+        base: TempDir,
+        root: PathBuf,
+        vault: Vault,
+    }
+
+    /// A workspace with a `.env` file, `openwork.json` entries and a workspace secret.
+    fn workspace() -> Fixture {
+        let base = TempDir::new("workspace-env");
+        let root = base.join("ws");
+        fs::create_dir_all(&root).expect("root");
+        fs::write(
+            root.join(".env"),
+            "# shared\nexport GITHUB_TOKEN=from-file\nREGION='eu west' # comment\nQUOTED=\"a\\nb\"\nOPENWORK=0\nbroken line\n",
+        )
+        .expect("env file");
+        fs::write(base.join("outside.env"), "LEAK=1\n").expect("outside");
+
+        let vault = Vault::new(&base.join("app-data"));
+        set_workspace_env(&root, &vault, "ws-1", "REGION", "us-east", false).expect("plain");
+        set_workspace_env(&root, &vault, "ws-1", "NPM_TOKEN", "{vault:NPM}", false)
+            .expect("reference");
+        set_workspace_env(&root, &vault, "ws-1", "GITHUB_TOKEN", "ghp_secret", true)
+            .expect("secret");
+        set_workspace_env_files(&root, &[".env".to_string(), "missing.env".to_string()])
+            .expect("env files");
+        Fixture { base, root, vault }
+    }
+
+    fn resolve(fixture: &Fixture) -> ResolvedEnv {
+        let config = read_workspace_openwork_config(&fixture.root).expect("config");
+        let secrets = EnvSecrets {
+            workspace: fixture.vault.workspace_env("ws-1").expect("secrets"),
+            named: BTreeMap::from([
+                ("NPM".to_string(), "npm_named".to_string()),
+                ("SLACK_TOKEN".to_string(), "xoxb-named".to_string()),
+            ]),
+            referenced: BTreeSet::from(["SLACK_TOKEN".to_string(), "UNSET".to_string()]),
+        };
+        resolve_workspace_env(&fixture.root, &config, &secrets)
+    }
+
+    #[test]
+    fn parses_exports_quotes_and_comments() {
+        let (vars, warnings) = parse_env_file(
+            "# shared\nexport A=1\nB='eu west' # comment\nC=\"a\\nb\"\nD=x # note\n",
+        );
+        assert_eq!(
+            vars,
+            vec![
+                ("A".to_string(), "1".to_string()),
+                ("B".to_string(), "eu west".to_string()),
+                ("C".to_string(), "a\nb".to_string()),
+                ("D".to_string(), "x".to_string()),
+            ]
+        );
+        assert!(warnings.is_empty(), "{warnings:?}");
+    }
+
+    #[test]
+    fn warnings_never_echo_a_rejected_line() {
+        let (vars, warnings) = parse_env_file(
+            "sk-proj-abc123DEF456ghi789==\nexport ghp_x-y=token-value\nOPENWORK=0\nQUOTE=\"open\n",
+        );
+        assert!(vars.is_empty());
+        assert_eq!(
+            warnings,
+            vec![
+                "line 1: invalid variable name",
+                "line 2: invalid variable name",
+                "line 3: variable is set by OpenWork and can't be overridden",
+                "line 4: unterminated quote for QUOTE",
+            ]
+        );
+        let joined = warnings.join("\n");
+        assert!(!joined.contains("sk-proj") && !joined.contains("ghp_x"));
+    }
+
+    #[test]
+    fn keeps_secret_values_out_of_openwork_json() {
+        let fixture = workspace();
+        let raw =
+            fs::read_to_string(fixture.root.join(".opencode/openwork.json")).expect("openwork");
+        assert!(raw.contains("\"envFiles\"") && !raw.contains("ghp_secret"));
+        let sealed = fs::read(fixture.base.join("app-data/openwork-vault.bin")).expect("vault");
+        assert!(!String::from_utf8_lossy(&sealed).contains("ghp_secret"));
+    }
+
+    #[test]
+    fn rejects_reserved_names_and_env_files_outside_the_workspace() {
+        let fixture = workspace();
+        let (root, vault) = (&fixture.root, &fixture.vault);
+        assert!(set_workspace_env(root, vault, "ws-1", "OPENCODE_CLIENT", "x", false).is_err());
+        assert!(set_workspace_env_files(root, &["../outside.env".to_string()]).is_err());
+    }
+
+    #[test]
+    fn later_sources_override_earlier_ones() {
+        let resolved = resolve(&workspace());
+        assert_eq!(resolved.vars["GITHUB_TOKEN"], "ghp_secret");
+        assert_eq!(resolved.vars["REGION"], "us-east");
+        assert_eq!(resolved.vars["QUOTED"], "a\nb");
+        assert_eq!(resolved.vars["NPM_TOKEN"], "npm_named");
+        assert_eq!(resolved.vars["SLACK_TOKEN"], "xoxb-named");
+        assert!(!resolved.vars.contains_key("UNSET"));
+        assert!(!resolved.vars.contains_key("OPENWORK"));
+
+        let sources: Vec<(&str, EnvSource)> = resolved
+            .report
+            .vars
+            .iter()
+            .map(|var| (var.name.as_str(), var.source))
+            .collect();
+        assert_eq!(
+            sources,
+            vec![
+                ("GITHUB_TOKEN", EnvSource::Vault),
+                ("NPM_TOKEN", EnvSource::Vault),
+                ("QUOTED", EnvSource::EnvFile),
+                ("REGION", EnvSource::Openwork),
+                ("SLACK_TOKEN", EnvSource::Vault),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_missing_files_and_bad_lines_without_values() {
+        let report = resolve(&workspace()).report;
+        assert!(report.files[0].loaded);
+        assert!(report.files[1].error.is_some());
+        assert_eq!(report.warnings.len(), 2);
+        assert!(!report.warnings.join("\n").contains("from-file"));
+    }
+
+    #[test]
+    fn removes_vault_entries_but_not_env_file_ones() {
+        let fixture = workspace();
+        let (root, vault) = (&fixture.root, &fixture.vault);
+        remove_workspace_env(root, vault, "ws-1", "GITHUB_TOKEN").expect("remove secret");
+        assert!(vault.workspace_env("ws-1").expect("secrets").is_empty());
+        assert!(remove_workspace_env(root, vault, "ws-1", "QUOTED").is_err());
+    }
+}
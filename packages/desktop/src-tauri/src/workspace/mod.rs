@@ -1,4 +1,13 @@
+pub mod archive;
 pub mod commands;
+pub mod connections;
+pub mod enterprise;
+pub mod env;
 pub mod files;
+pub mod presets;
+pub mod relocate;
+pub mod secrets;
+pub mod seed;
 pub mod state;
+pub mod templates;
 pub mod watch;
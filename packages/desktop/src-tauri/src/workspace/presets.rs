@@ -0,0 +1,279 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::commands::skills::validate_skill_name;
+use crate::types::OpencodeCommand;
+
+const BUILTIN_PRESETS: &[&str] = &[
+    include_str!("../../presets/starter.json"),
+    include_str!("../../presets/automation.json"),
+    include_str!("../../presets/minimal.json"),
+];
+
+const BUILTIN_SKILLS: &[(&str, &str)] = &[
+    (
+        "workspace-guide",
+        include_str!("../../presets/skills/workspace-guide/SKILL.md"),
+    ),
+    (
+        "get-started",
+        include_str!("../../presets/skills/get-started/SKILL.md"),
+    ),
+];
+
+/// Preset used when a workspace names one that isn't registered.
+const FALLBACK_PRESET_ID: &str = "minimal";
+const PRESET_MANIFEST: &str = "preset.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PresetSource {
+    #[default]
+    Builtin,
+    Bundled,
+    User,
+}
+
+/// A workspace preset manifest. Presets are either a single `<id>.json` manifest or a
+/// `<id>/preset.json` folder that may also carry `skills/<name>/`, `commands/*.md` and
+/// `agents/*.md`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspacePreset {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    #[serde(default)]
+    pub commands: Vec<OpencodeCommand>,
+    #[serde(default)]
+    pub plugins: Vec<String>,
+    #[serde(default)]
+    pub mcp: serde_json::Map<String, serde_json::Value>,
+    /// Defaults merged into a newly created `.opencode/openwork.json`.
+    #[serde(default)]
+    pub openwork: Option<serde_json::Value>,
+    #[serde(default)]
+    pub enterprise_creator_skills: bool,
+    #[serde(default, skip_deserializing)]
+    pub source: PresetSource,
+    #[serde(skip)]
+    pub root: Option<PathBuf>,
+}
+
+impl WorkspacePreset {
+    pub fn builtin_skill(name: &str) -> Option<&'static str> {
+        BUILTIN_SKILLS
+            .iter()
+            .find(|(skill, _)| *skill == name)
+            .map(|(_, doc)| *doc)
+    }
+
+    /// Folder holding one of this preset's asset kinds (`skills`, `commands`, `agents`).
+    pub fn asset_dir(&self, kind: &str) -> Option<PathBuf> {
+        let dir = self.root.as_ref()?.join(kind);
+        dir.is_dir().then_some(dir)
+    }
+}
+
+pub fn is_valid_preset_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn parse_preset(
+    raw: &str,
+    fallback_id: &str,
+    source: PresetSource,
+    root: Option<PathBuf>,
+) -> Result<WorkspacePreset, String> {
+    let mut preset: WorkspacePreset = json5::from_str(raw).map_err(|e| e.to_string())?;
+    if preset.id.trim().is_empty() {
+        preset.id = fallback_id.to_string();
+    }
+    preset.id = preset.id.trim().to_string();
+    if !is_valid_preset_id(&preset.id) {
+        return Err(format!("invalid preset id '{}'", preset.id));
+    }
+    for skill in &preset.skills {
+        validate_skill_name(skill).map_err(|e| format!("invalid skill '{skill}': {e}"))?;
+    }
+    preset.source = source;
+    preset.root = root;
+    Ok(preset)
+}
+
+pub fn builtin_presets() -> Vec<WorkspacePreset> {
+    BUILTIN_PRESETS
+        .iter()
+        .filter_map(|raw| parse_preset(raw, "", PresetSource::Builtin, None).ok())
+        .collect()
+}
+
+fn read_preset_entry(path: &Path, source: PresetSource) -> Option<Result<WorkspacePreset, String>> {
+    let (manifest, root) = if path.is_dir() {
+        let manifest = path.join(PRESET_MANIFEST);
+        if !manifest.is_file() {
+            return None;
+        }
+        (manifest, Some(path.to_path_buf()))
+    } else if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+        (path.to_path_buf(), None)
+    } else {
+        return None;
+    };
+
+    let fallback_id = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let result = fs::read_to_string(&manifest)
+        .map_err(|e| e.to_string())
+        .and_then(|raw| parse_preset(&raw, &fallback_id, source, root));
+    Some(result)
+}
+
+pub fn read_preset_dir(dir: &Path, source: PresetSource) -> Vec<WorkspacePreset> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    // Dot entries are half-written templates or editor droppings.
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+
+    let mut presets = Vec::new();
+    for path in paths {
+        match read_preset_entry(&path, source) {
+            Some(Ok(preset)) => presets.push(preset),
+            Some(Err(error)) => {
+                println!("[workspace] Skipping preset {}: {error}", path.display());
+            }
+            None => {}
+        }
+    }
+    presets
+}
+
+pub fn user_presets_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?;
+    Ok(data_dir.join("presets"))
+}
+
+/// Merges preset sources; later sources replace earlier ones with the same id so a company
+/// build or a user can override a builtin preset.
+pub fn merge_preset_sources(sources: Vec<Vec<WorkspacePreset>>) -> Vec<WorkspacePreset> {
+    let mut merged: Vec<WorkspacePreset> = Vec::new();
+    for preset in sources.into_iter().flatten() {
+        match merged.iter_mut().find(|existing| existing.id == preset.id) {
+            Some(existing) => *existing = preset,
+            None => merged.push(preset),
+        }
+    }
+    merged
+}
+
+pub fn load_workspace_presets(app: &tauri::AppHandle) -> Vec<WorkspacePreset> {
+    let mut sources = vec![builtin_presets()];
+
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        sources.push(read_preset_dir(
+            &resource_dir.join("presets"),
+            PresetSource::Bundled,
+        ));
+    }
+
+    if let Ok(user_dir) = user_presets_dir(app) {
+        sources.push(read_preset_dir(&user_dir, PresetSource::User));
+    }
+
+    merge_preset_sources(sources)
+}
+
+pub fn find_preset(presets: &[WorkspacePreset], id: &str) -> WorkspacePreset {
+    if let Some(preset) = presets.iter().find(|preset| preset.id == id) {
+        return preset.clone();
+    }
+
+    let mut fallback = presets
+        .iter()
+        .find(|preset| preset.id == FALLBACK_PRESET_ID)
+        .cloned()
+        .unwrap_or_default();
+    // Keep the requested id so openwork.json still records what the user picked.
+    fallback.id = id.to_string();
+    fallback
+}
+
+pub fn resolve_workspace_preset(app: &tauri::AppHandle, id: &str) -> WorkspacePreset {
+    find_preset(&load_workspace_presets(app), id.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_presets_parse_and_reference_known_skills() {
+        let presets = builtin_presets();
+        let ids: Vec<&str> = presets.iter().map(|preset| preset.id.as_str()).collect();
+        assert_eq!(ids, vec!["starter", "automation", "minimal"]);
+
+        for preset in &presets {
+            for skill in &preset.skills {
+                assert!(
+                    WorkspacePreset::builtin_skill(skill).is_some(),
+                    "preset {} references unknown skill {skill}",
+                    preset.id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn later_sources_override_and_unknown_ids_fall_back() {
+        let custom = parse_preset(
+            r#"{ "name": "Acme starter", "plugins": ["acme-plugin"] }"#,
+            "starter",
+            PresetSource::User,
+            None,
+        )
+        .expect("parse custom preset");
+        let presets = merge_preset_sources(vec![builtin_presets(), vec![custom]]);
+
+        let starter = find_preset(&presets, "starter");
+        assert_eq!(starter.source, PresetSource::User);
+        assert_eq!(starter.plugins, vec!["acme-plugin".to_string()]);
+
+        let unknown = find_preset(&presets, "custom-team");
+        assert_eq!(unknown.id, "custom-team");
+        assert_eq!(unknown.skills, vec!["workspace-guide".to_string()]);
+    }
+
+    #[test]
+    fn rejects_skill_names_outside_the_skills_dir() {
+        for skill in ["../x", "nested/skill", "/etc", "Skill"] {
+            let raw = format!(r#"{{ "skills": ["{skill}"] }}"#);
+            let error = parse_preset(&raw, "custom", PresetSource::User, None)
+                .expect_err("invalid skill name");
+            assert!(error.contains("invalid skill"), "{error}");
+        }
+    }
+}
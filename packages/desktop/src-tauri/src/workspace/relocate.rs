@@ -0,0 +1,449 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::types::WorkspaceOpenworkConfig;
+
+pub const MOVE_PROGRESS_EVENT: &str = "openwork://workspace-move-progress";
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMoveProgress {
+    pub workspace_id: String,
+    pub phase: String,
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+    pub copied_files: u64,
+    pub total_files: u64,
+    pub current_path: Option<String>,
+}
+
+impl WorkspaceMoveProgress {
+    pub fn new(workspace_id: &str, phase: &str) -> Self {
+        Self {
+            workspace_id: workspace_id.to_string(),
+            phase: phase.to_string(),
+            copied_bytes: 0,
+            total_bytes: 0,
+            copied_files: 0,
+            total_files: 0,
+            current_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveStrategy {
+    Renamed,
+    Copied,
+}
+
+fn is_within(path: &Path, root: &Path) -> bool {
+    path == root || path.starts_with(root)
+}
+
+pub fn validate_move_target(source: &Path, target: &Path) -> Result<(), String> {
+    if !target.is_absolute() {
+        return Err("targetDir must be an absolute path".to_string());
+    }
+    if !source.is_dir() {
+        return Err(format!("Workspace path not found: {}", source.display()));
+    }
+
+    let source_canonical = fs::canonicalize(source)
+        .map_err(|e| format!("Failed to resolve {}: {e}", source.display()))?;
+    // The target may not exist yet; resolve its closest existing ancestor instead.
+    let mut probe = target.to_path_buf();
+    let mut suffix = Vec::new();
+    while !probe.exists() {
+        let Some(name) = probe.file_name().map(|name| name.to_os_string()) else {
+            break;
+        };
+        suffix.push(name);
+        if !probe.pop() {
+            break;
+        }
+    }
+    let mut target_canonical = fs::canonicalize(&probe).unwrap_or(probe);
+    for part in suffix.into_iter().rev() {
+        target_canonical.push(part);
+    }
+
+    if is_within(&target_canonical, &source_canonical) {
+        return Err("targetDir must not be inside the workspace".to_string());
+    }
+    if is_within(&source_canonical, &target_canonical) {
+        return Err("targetDir must not contain the workspace".to_string());
+    }
+
+    if target.exists() {
+        if !target.is_dir() {
+            return Err("targetDir must be a folder".to_string());
+        }
+        let mut entries = fs::read_dir(target)
+            .map_err(|e| format!("Failed to read {}: {e}", target.display()))?;
+        if entries.next().is_some() {
+            return Err("Target folder must be empty".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Rewrites `authorizedRoots` entries that live under `old_root` so they point at `new_root`.
+pub fn rebase_authorized_roots(
+    config: &mut WorkspaceOpenworkConfig,
+    old_root: &Path,
+    new_root: &Path,
+) -> bool {
+    let mut changed = false;
+    for entry in config.authorized_roots.iter_mut() {
+        let current = PathBuf::from(entry.trim());
+        let Ok(rest) = current.strip_prefix(old_root) else {
+            continue;
+        };
+        let next = if rest.as_os_str().is_empty() {
+            new_root.to_path_buf()
+        } else {
+            new_root.join(rest)
+        };
+        *entry = next.to_string_lossy().to_string();
+        changed = true;
+    }
+    changed
+}
+
+fn openwork_path(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(".opencode").join("openwork.json")
+}
+
+/// Points `authorizedRoots` in the moved workspace's `openwork.json` at its new root. Returns
+/// the file's previous contents when it was rewritten, for [`rollback_move`].
+pub fn rewrite_openwork_roots(
+    workspace_root: &Path,
+    old_root: &Path,
+) -> Result<Option<String>, String> {
+    let openwork_path = openwork_path(workspace_root);
+    if !openwork_path.exists() {
+        return Ok(None);
+    }
+
+    let raw = fs::read_to_string(&openwork_path)
+        .map_err(|e| format!("Failed to read {}: {e}", openwork_path.display()))?;
+    let mut config: WorkspaceOpenworkConfig = serde_json::from_str(&raw)
+        .map_err(|e| format!("Failed to parse {}: {e}", openwork_path.display()))?;
+
+    if !rebase_authorized_roots(&mut config, old_root, workspace_root) {
+        return Ok(None);
+    }
+
+    fs::write(
+        &openwork_path,
+        serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?,
+    )
+    .map_err(|e| format!("Failed to write {}: {e}", openwork_path.display()))?;
+    Ok(Some(raw))
+}
+
+struct CopyProgress<'a, F: FnMut(&WorkspaceMoveProgress)> {
+    report: WorkspaceMoveProgress,
+    last_emit: Option<Instant>,
+    on_progress: &'a mut F,
+}
+
+impl<F: FnMut(&WorkspaceMoveProgress)> CopyProgress<'_, F> {
+    fn emit(&mut self, force: bool) {
+        let now = Instant::now();
+        if !force {
+            if let Some(previous) = self.last_emit {
+                if now.duration_since(previous) < Duration::from_millis(100) {
+                    return;
+                }
+            }
+        }
+        self.last_emit = Some(now);
+        (self.on_progress)(&self.report);
+    }
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> Result<(), String> {
+    let link =
+        fs::read_link(from).map_err(|e| format!("Failed to read link {}: {e}", from.display()))?;
+    std::os::unix::fs::symlink(&link, to)
+        .map_err(|e| format!("Failed to link {}: {e}", to.display()))
+}
+
+#[cfg(windows)]
+fn copy_symlink(from: &Path, _to: &Path) -> Result<(), String> {
+    Err(format!(
+        "Cannot move symlink {} to another drive",
+        from.display()
+    ))
+}
+
+fn copy_tree<F: FnMut(&WorkspaceMoveProgress)>(
+    source: &Path,
+    target: &Path,
+    progress: &mut CopyProgress<'_, F>,
+) -> Result<(), String> {
+    fs::create_dir_all(target)
+        .map_err(|e| format!("Failed to create {}: {e}", target.display()))?;
+
+    for entry in WalkDir::new(source).min_depth(1) {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let from = entry.path();
+        let rel = from
+            .strip_prefix(source)
+            .map_err(|e| format!("Failed to compute relative path: {e}"))?;
+        let to = target.join(rel);
+        let file_type = entry.file_type();
+
+        if file_type.is_dir() {
+            fs::create_dir_all(&to)
+                .map_err(|e| format!("Failed to create {}: {e}", to.display()))?;
+            continue;
+        }
+
+        if file_type.is_symlink() {
+            copy_symlink(from, &to)?;
+            continue;
+        }
+
+        progress.report.current_path = Some(rel.to_string_lossy().to_string());
+        let copied = fs::copy(from, &to)
+            .map_err(|e| format!("Failed to copy {} -> {}: {e}", from.display(), to.display()))?;
+        progress.report.copied_bytes += copied;
+        progress.report.copied_files += 1;
+        progress.emit(false);
+    }
+
+    Ok(())
+}
+
+fn measure_tree(source: &Path) -> (u64, u64) {
+    let mut bytes = 0;
+    let mut files = 0;
+    for entry in WalkDir::new(source).into_iter().flatten() {
+        if entry.file_type().is_file() {
+            files += 1;
+            bytes += entry.metadata().map(|meta| meta.len()).unwrap_or(0);
+        }
+    }
+    (bytes, files)
+}
+
+/// Moves `source` to `target`, preferring a rename and falling back to a copy only when the
+/// folders live on different filesystems. The source is left untouched by a copy; callers
+/// remove it with [`remove_moved_source`] once everything else has been committed.
+pub fn move_workspace_dir<F: FnMut(&WorkspaceMoveProgress)>(
+    workspace_id: &str,
+    source: &Path,
+    target: &Path,
+    mut on_progress: F,
+) -> Result<MoveStrategy, String> {
+    let mut report = WorkspaceMoveProgress::new(workspace_id, "moving");
+    on_progress(&report);
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+
+    // An empty target folder is fine to replace; rename refuses it on some platforms.
+    let target_existed = target.exists();
+    if target_existed {
+        let _ = fs::remove_dir(target);
+    }
+
+    match fs::rename(source, target) {
+        Ok(()) => {
+            report.phase = "renamed".to_string();
+            on_progress(&report);
+            return Ok(MoveStrategy::Renamed);
+        }
+        Err(error) if error.kind() == ErrorKind::CrossesDevices => {}
+        Err(error) => {
+            if target_existed {
+                let _ = fs::create_dir_all(target);
+            }
+            return Err(format!(
+                "Failed to move {} to {}: {error}",
+                source.display(),
+                target.display()
+            ));
+        }
+    }
+
+    let (total_bytes, total_files) = measure_tree(source);
+    report.phase = "copying".to_string();
+    report.total_bytes = total_bytes;
+    report.total_files = total_files;
+    let mut progress = CopyProgress {
+        report,
+        last_emit: None,
+        on_progress: &mut on_progress,
+    };
+    progress.emit(true);
+
+    if let Err(error) = copy_tree(source, target, &mut progress) {
+        progress.report.phase = "rolling-back".to_string();
+        progress.emit(true);
+        let _ = fs::remove_dir_all(target);
+        if target_existed {
+            let _ = fs::create_dir_all(target);
+        }
+        return Err(error);
+    }
+
+    progress.report.phase = "copied".to_string();
+    progress.report.current_path = None;
+    progress.emit(true);
+    Ok(MoveStrategy::Copied)
+}
+
+/// Undoes [`move_workspace_dir`] after a later step failed. `openwork` is what
+/// [`rewrite_openwork_roots`] returned, if it already ran; a copy left the source untouched,
+/// but a renamed workspace takes its old `openwork.json` back.
+pub fn rollback_move(
+    strategy: MoveStrategy,
+    source: &Path,
+    target: &Path,
+    openwork: Option<&str>,
+) -> Result<(), String> {
+    match strategy {
+        MoveStrategy::Renamed => {
+            fs::rename(target, source).map_err(|e| {
+                format!(
+                    "Failed to move {} back to {}: {e}",
+                    target.display(),
+                    source.display()
+                )
+            })?;
+            match openwork {
+                Some(raw) => {
+                    let path = openwork_path(source);
+                    fs::write(&path, raw)
+                        .map_err(|e| format!("Failed to restore {}: {e}", path.display()))
+                }
+                None => Ok(()),
+            }
+        }
+        MoveStrategy::Copied => fs::remove_dir_all(target)
+            .map_err(|e| format!("Failed to remove {}: {e}", target.display())),
+    }
+}
+
+pub fn remove_moved_source(strategy: MoveStrategy, source: &Path) -> Result<(), String> {
+    if strategy == MoveStrategy::Renamed || !source.exists() {
+        return Ok(());
+    }
+    fs::remove_dir_all(source).map_err(|e| format!("Failed to remove {}: {e}", source.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::TempDir;
+
+    #[test]
+    fn rebases_roots_under_old_prefix_only() {
+        let old_root = PathBuf::from("/data/workspaces/starter");
+        let new_root = PathBuf::from("/home/me/Documents/starter");
+        let mut config = WorkspaceOpenworkConfig {
+            authorized_roots: vec![
+                "/data/workspaces/starter".to_string(),
+                "/data/workspaces/starter/notes".to_string(),
+                "/data/workspaces/starter-2".to_string(),
+                "/srv/shared".to_string(),
+            ],
+            ..WorkspaceOpenworkConfig::default()
+        };
+
+        assert!(rebase_authorized_roots(&mut config, &old_root, &new_root));
+        assert_eq!(
+            config.authorized_roots,
+            vec![
+                new_root.to_string_lossy().to_string(),
+                new_root.join("notes").to_string_lossy().to_string(),
+                "/data/workspaces/starter-2".to_string(),
+                "/srv/shared".to_string(),
+            ]
+        );
+    }
+
+    fn workspace_with_notes(base: &Path) -> PathBuf {
+        let source = base.join("source");
+        fs::create_dir_all(source.join(".opencode")).expect("create source");
+        fs::write(source.join(".opencode").join("notes.md"), b"hello").expect("seed file");
+        source
+    }
+
+    #[test]
+    fn rejects_target_inside_or_around_the_workspace() {
+        let base = TempDir::new("relocate-nested");
+        let source = workspace_with_notes(&base);
+
+        assert!(validate_move_target(&source, &source.join("inner")).is_err());
+        assert!(validate_move_target(&source, &base).is_err());
+    }
+
+    #[test]
+    fn rejects_non_empty_target() {
+        let base = TempDir::new("relocate-non-empty");
+        let source = workspace_with_notes(&base);
+        let target = base.join("target");
+        fs::create_dir_all(&target).expect("create target");
+        fs::write(target.join("keep.txt"), b"keep").expect("target file");
+
+        let error = validate_move_target(&source, &target).expect_err("non-empty target");
+        assert!(error.contains("must be empty"), "{error}");
+    }
+
+    #[test]
+    fn moves_workspace_into_an_empty_target() {
+        let base = TempDir::new("relocate-move");
+        let source = workspace_with_notes(&base);
+        let target = base.join("target");
+        fs::create_dir_all(&target).expect("create target");
+
+        validate_move_target(&source, &target).expect("valid target");
+        let strategy = move_workspace_dir("ws-test", &source, &target, |_| {}).expect("move");
+        remove_moved_source(strategy, &source).expect("cleanup");
+
+        assert!(!source.exists());
+        assert_eq!(
+            fs::read(target.join(".opencode").join("notes.md")).expect("read moved"),
+            b"hello"
+        );
+    }
+
+    #[test]
+    fn rollback_restores_the_original_openwork_json() {
+        let base = TempDir::new("relocate-rollback");
+        let source = workspace_with_notes(&base);
+        let original = format!(
+            "{{\n  \"version\": 1,\n  \"authorizedRoots\": [\"{}\"]\n}}\n",
+            source.join("notes").display()
+        );
+        fs::write(openwork_path(&source), &original).expect("openwork.json");
+        let target = base.join("target");
+
+        let strategy = move_workspace_dir("ws-test", &source, &target, |_| {}).expect("move");
+        let previous = rewrite_openwork_roots(&target, &source).expect("rewrite");
+        assert_eq!(previous.as_deref(), Some(original.as_str()));
+        let rewritten = fs::read_to_string(openwork_path(&target)).expect("read rewritten");
+        assert!(rewritten.contains(&target.join("notes").to_string_lossy().to_string()));
+
+        rollback_move(strategy, &source, &target, previous.as_deref()).expect("rollback");
+        assert!(!target.exists());
+        assert_eq!(
+            fs::read_to_string(openwork_path(&source)).expect("read restored"),
+            original
+        );
+    }
+}
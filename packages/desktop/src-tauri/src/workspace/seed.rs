@@ -0,0 +1,528 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use similar::TextDiff;
+use walkdir::WalkDir;
+
+use crate::workspace::commands::{sanitize_command_name, serialize_command_frontmatter};
+use crate::workspace::presets::WorkspacePreset;
+
+const SEED_MANIFEST_VERSION: u32 = 1;
+const SEED_MANIFEST_FILE: &str = "openwork-seed.json";
+
+/// Records what OpenWork wrote into a workspace so later releases can tell untouched
+/// seeded files apart from ones the user edited.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedManifest {
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub preset: String,
+    /// Keyed by workspace-relative path using `/` separators.
+    #[serde(default)]
+    pub files: BTreeMap<String, SeedRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedRecord {
+    pub sha256: String,
+}
+
+/// A file a preset wants in the workspace, with its current bundled contents.
+#[derive(Debug, Clone)]
+pub struct SeedFile {
+    pub path: String,
+    pub contents: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SeedFileStatus {
+    /// Already matches the preset.
+    Current,
+    /// Untouched since seeding; safe to replace.
+    Update,
+    /// New in the preset and missing from the workspace.
+    New,
+    /// Edited by the user while the preset stayed the same; kept as is.
+    Customized,
+    /// Edited by the user and changed in the preset; needs confirmation.
+    Modified,
+    /// Seeded earlier and deleted by the user; needs confirmation.
+    Removed,
+}
+
+impl SeedFileStatus {
+    pub fn needs_confirmation(self) -> bool {
+        matches!(self, SeedFileStatus::Modified | SeedFileStatus::Removed)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedFileUpgrade {
+    pub path: String,
+    pub status: SeedFileStatus,
+    /// Unified diff from the workspace copy to the preset copy, for modified files.
+    pub diff: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceUpgradePlan {
+    pub workspace_id: String,
+    pub preset: String,
+    pub files: Vec<SeedFileUpgrade>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceUpgradeResult {
+    pub workspace_id: String,
+    pub updated: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn seed_manifest_path(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(".opencode").join(SEED_MANIFEST_FILE)
+}
+
+fn relative_key(parts: &[&str]) -> String {
+    parts.join("/")
+}
+
+pub fn read_seed_manifest(workspace_root: &Path) -> SeedManifest {
+    fs::read_to_string(seed_manifest_path(workspace_root))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_seed_manifest(workspace_root: &Path, manifest: &SeedManifest) -> Result<(), String> {
+    let path = seed_manifest_path(workspace_root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let mut manifest = manifest.clone();
+    manifest.version = SEED_MANIFEST_VERSION;
+    fs::write(
+        &path,
+        serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?,
+    )
+    .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+fn push_asset_files(out: &mut Vec<SeedFile>, source: &Path, prefix: &str) -> Result<(), String> {
+    for entry in WalkDir::new(source).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| e.to_string())?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry
+            .path()
+            .strip_prefix(source)
+            .map_err(|e| format!("Failed to compute relative path: {e}"))?;
+        let rel = rel
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        let contents = fs::read(entry.path())
+            .map_err(|e| format!("Failed to read {}: {e}", entry.path().display()))?;
+        out.push(SeedFile {
+            path: format!("{prefix}/{rel}"),
+            contents,
+        });
+    }
+    Ok(())
+}
+
+fn push_markdown_assets(
+    out: &mut Vec<SeedFile>,
+    source: &Path,
+    prefix: &str,
+) -> Result<(), String> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(source)
+        .map_err(|e| format!("Failed to read {}: {e}", source.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("md"))
+        .collect();
+    entries.sort();
+    for path in entries {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let key = format!("{prefix}/{name}");
+        if out.iter().any(|file| file.path == key) {
+            continue;
+        }
+        let contents =
+            fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        out.push(SeedFile {
+            path: key,
+            contents,
+        });
+    }
+    Ok(())
+}
+
+/// Lists every file seeding `preset` can produce, keyed by workspace-relative path.
+pub fn preset_seed_files(preset: &WorkspacePreset) -> Result<Vec<SeedFile>, String> {
+    let mut out = Vec::new();
+
+    let mut skills = preset.skills.clone();
+    if let Some(dir) = preset.asset_dir("skills") {
+        let entries =
+            fs::read_dir(&dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+        let mut extra: Vec<String> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| !skills.contains(name))
+            .collect();
+        extra.sort();
+        skills.extend(extra);
+    }
+    for name in skills {
+        let prefix = relative_key(&[".opencode", "skills", &name]);
+        if let Some(source) = preset
+            .asset_dir("skills")
+            .map(|dir| dir.join(&name))
+            .filter(|dir| dir.is_dir())
+        {
+            push_asset_files(&mut out, &source, &prefix)?;
+        } else if let Some(doc) = WorkspacePreset::builtin_skill(&name) {
+            out.push(SeedFile {
+                path: format!("{prefix}/SKILL.md"),
+                contents: doc.as_bytes().to_vec(),
+            });
+        }
+    }
+
+    let commands_prefix = relative_key(&[".opencode", "commands"]);
+    for command in &preset.commands {
+        let Some(name) = sanitize_command_name(&command.name) else {
+            continue;
+        };
+        out.push(SeedFile {
+            path: format!("{commands_prefix}/{name}.md"),
+            contents: serialize_command_frontmatter(command)?.into_bytes(),
+        });
+    }
+    if let Some(dir) = preset.asset_dir("commands") {
+        push_markdown_assets(&mut out, &dir, &commands_prefix)?;
+    }
+    if let Some(dir) = preset.asset_dir("agents") {
+        push_markdown_assets(&mut out, &dir, &relative_key(&[".opencode", "agents"]))?;
+    }
+
+    Ok(out)
+}
+
+fn workspace_path(workspace_root: &Path, key: &str) -> PathBuf {
+    key.split('/')
+        .fold(workspace_root.to_path_buf(), |path, part| path.join(part))
+}
+
+/// Records preset files that currently match what the preset ships. Files that already
+/// have a record keep it, so the hash always describes what was originally seeded.
+pub fn record_seeded_files(workspace_root: &Path, preset: &WorkspacePreset) -> Result<(), String> {
+    let mut manifest = read_seed_manifest(workspace_root);
+    let mut changed = manifest.preset != preset.id;
+    manifest.preset = preset.id.clone();
+
+    for file in preset_seed_files(preset)? {
+        if manifest.files.contains_key(&file.path) {
+            continue;
+        }
+        let Ok(current) = fs::read(workspace_path(workspace_root, &file.path)) else {
+            continue;
+        };
+        if current == file.contents {
+            manifest.files.insert(
+                file.path,
+                SeedRecord {
+                    sha256: sha256_hex(&current),
+                },
+            );
+            changed = true;
+        }
+    }
+
+    if changed {
+        write_seed_manifest(workspace_root, &manifest)?;
+    }
+    Ok(())
+}
+
+/// Three-way comparison of the workspace copy against the seeded base and the preset.
+/// Without a base (files from before seeding was recorded) any difference is `Modified`.
+fn classify(
+    manifest: &SeedManifest,
+    file: &SeedFile,
+    current: Option<&[u8]>,
+) -> (SeedFileStatus, Option<String>) {
+    let base = manifest.files.get(&file.path).map(|record| &record.sha256);
+    let Some(current) = current else {
+        return if base.is_some() {
+            (SeedFileStatus::Removed, None)
+        } else {
+            (SeedFileStatus::New, None)
+        };
+    };
+
+    if current == file.contents.as_slice() {
+        return (SeedFileStatus::Current, None);
+    }
+    if base.is_some_and(|base| *base == sha256_hex(current)) {
+        return (SeedFileStatus::Update, None);
+    }
+    if base.is_some_and(|base| *base == sha256_hex(&file.contents)) {
+        return (SeedFileStatus::Customized, None);
+    }
+
+    let ours = String::from_utf8_lossy(current);
+    let theirs = String::from_utf8_lossy(&file.contents);
+    let diff = TextDiff::from_lines(ours.as_ref(), theirs.as_ref())
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", file.path), &format!("b/{}", file.path))
+        .to_string();
+    (SeedFileStatus::Modified, Some(diff))
+}
+
+/// Plans an upgrade to `preset`. Files that already match it get a base recorded first, so
+/// workspaces seeded before the manifest existed can be compared three ways next time.
+pub fn plan_seed_upgrade(
+    workspace_id: &str,
+    workspace_root: &Path,
+    preset: &WorkspacePreset,
+) -> Result<WorkspaceUpgradePlan, String> {
+    record_seeded_files(workspace_root, preset)?;
+    let manifest = read_seed_manifest(workspace_root);
+    let mut files = Vec::new();
+    for file in preset_seed_files(preset)? {
+        let current = fs::read(workspace_path(workspace_root, &file.path)).ok();
+        let (status, diff) = classify(&manifest, &file, current.as_deref());
+        files.push(SeedFileUpgrade {
+            path: file.path,
+            status,
+            diff,
+        });
+    }
+
+    Ok(WorkspaceUpgradePlan {
+        workspace_id: workspace_id.to_string(),
+        preset: preset.id.clone(),
+        files,
+    })
+}
+
+/// Writes untouched and new files, plus the modified or removed ones listed in `accepted`.
+/// Customized files are left alone.
+pub fn apply_seed_upgrade(
+    workspace_id: &str,
+    workspace_root: &Path,
+    preset: &WorkspacePreset,
+    accepted: &[String],
+) -> Result<WorkspaceUpgradeResult, String> {
+    let mut manifest = read_seed_manifest(workspace_root);
+    manifest.preset = preset.id.clone();
+    let mut updated = Vec::new();
+    let mut skipped = Vec::new();
+
+    for file in preset_seed_files(preset)? {
+        let target = workspace_path(workspace_root, &file.path);
+        let current = fs::read(&target).ok();
+        let (status, _) = classify(&manifest, &file, current.as_deref());
+        if status == SeedFileStatus::Current {
+            manifest
+                .files
+                .entry(file.path.clone())
+                .or_insert_with(|| SeedRecord {
+                    sha256: sha256_hex(&file.contents),
+                });
+            continue;
+        }
+        if status == SeedFileStatus::Customized {
+            continue;
+        }
+        if status.needs_confirmation() && !accepted.contains(&file.path) {
+            skipped.push(file.path);
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        fs::write(&target, &file.contents)
+            .map_err(|e| format!("Failed to write {}: {e}", target.display()))?;
+        manifest.files.insert(
+            file.path.clone(),
+            SeedRecord {
+                sha256: sha256_hex(&file.contents),
+            },
+        );
+        updated.push(file.path);
+    }
+
+    write_seed_manifest(workspace_root, &manifest)?;
+    Ok(WorkspaceUpgradeResult {
+        workspace_id: workspace_id.to_string(),
+        updated,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::TempDir;
+
+    const NOTES: &str = ".opencode/skills/notes/SKILL.md";
+    const TASKS: &str = ".opencode/skills/tasks/SKILL.md";
+
+    fn preset_with_skills(root: &Path, notes: &str, tasks: &str) -> WorkspacePreset {
+        for (name, doc) in [("notes", notes), ("tasks", tasks)] {
+            let dir = root.join("skills").join(name);
+            fs::create_dir_all(&dir).expect("create preset skill");
+            fs::write(dir.join("SKILL.md"), doc).expect("write skill");
+        }
+        WorkspacePreset {
+            id: "team".to_string(),
+            root: Some(root.to_path_buf()),
+            ..WorkspacePreset::default()
+        }
+    }
+
+    fn seed(workspace: &Path, preset: &WorkspacePreset) {
+        for file in preset_seed_files(preset).expect("seed files") {
+            let path = workspace_path(workspace, &file.path);
+            fs::create_dir_all(path.parent().unwrap()).expect("create dir");
+            fs::write(path, &file.contents).expect("seed");
+        }
+    }
+
+    fn statuses(plan: &WorkspaceUpgradePlan) -> Vec<(&str, SeedFileStatus)> {
+        plan.files
+            .iter()
+            .map(|file| (file.path.as_str(), file.status))
+            .collect()
+    }
+
+    fn read(workspace: &Path, key: &str) -> String {
+        fs::read_to_string(workspace_path(workspace, key)).expect("read seeded file")
+    }
+
+    #[test]
+    fn updates_files_the_user_left_alone() {
+        let base = TempDir::new("seed-untouched");
+        let workspace = base.join("workspace");
+        let preset = preset_with_skills(&base.join("v1"), "v1 notes", "v1 tasks");
+        seed(&workspace, &preset);
+        record_seeded_files(&workspace, &preset).expect("record");
+
+        let next = preset_with_skills(&base.join("v2"), "v2 notes", "v1 tasks");
+        let plan = plan_seed_upgrade("ws", &workspace, &next).expect("plan");
+        assert_eq!(
+            statuses(&plan),
+            vec![
+                (NOTES, SeedFileStatus::Update),
+                (TASKS, SeedFileStatus::Current)
+            ]
+        );
+
+        let result = apply_seed_upgrade("ws", &workspace, &next, &[]).expect("apply");
+        assert_eq!(result.updated, vec![NOTES]);
+        assert_eq!(read(&workspace, NOTES), "v2 notes");
+    }
+
+    #[test]
+    fn keeps_user_edits_when_the_preset_did_not_change() {
+        let base = TempDir::new("seed-customized");
+        let workspace = base.join("workspace");
+        let preset = preset_with_skills(&base.join("v1"), "v1 notes", "v1 tasks");
+        seed(&workspace, &preset);
+        record_seeded_files(&workspace, &preset).expect("record");
+        fs::write(workspace_path(&workspace, TASKS), "my own tasks").expect("edit");
+
+        let plan = plan_seed_upgrade("ws", &workspace, &preset).expect("plan");
+        assert_eq!(
+            statuses(&plan),
+            vec![
+                (NOTES, SeedFileStatus::Current),
+                (TASKS, SeedFileStatus::Customized)
+            ]
+        );
+        assert!(plan.files[1].diff.is_none());
+
+        let accepted = vec![TASKS.to_string()];
+        let result = apply_seed_upgrade("ws", &workspace, &preset, &accepted).expect("apply");
+        assert!(result.updated.is_empty());
+        assert_eq!(read(&workspace, TASKS), "my own tasks");
+    }
+
+    #[test]
+    fn asks_before_replacing_files_both_sides_changed() {
+        let base = TempDir::new("seed-modified");
+        let workspace = base.join("workspace");
+        let preset = preset_with_skills(&base.join("v1"), "v1 notes", "v1 tasks");
+        seed(&workspace, &preset);
+        record_seeded_files(&workspace, &preset).expect("record");
+        fs::write(workspace_path(&workspace, TASKS), "my own tasks").expect("edit");
+
+        let next = preset_with_skills(&base.join("v2"), "v1 notes", "v2 tasks");
+        let plan = plan_seed_upgrade("ws", &workspace, &next).expect("plan");
+        assert_eq!(plan.files[1].status, SeedFileStatus::Modified);
+        assert!(plan.files[1]
+            .diff
+            .as_deref()
+            .is_some_and(|diff| diff.contains("-my own tasks") && diff.contains("+v2 tasks")));
+
+        let result = apply_seed_upgrade("ws", &workspace, &next, &[]).expect("apply");
+        assert_eq!(result.skipped, vec![TASKS]);
+        assert_eq!(read(&workspace, TASKS), "my own tasks");
+
+        let accepted = vec![TASKS.to_string()];
+        apply_seed_upgrade("ws", &workspace, &next, &accepted).expect("accept");
+        assert_eq!(read(&workspace, TASKS), "v2 tasks");
+    }
+
+    #[test]
+    fn records_a_base_for_workspaces_seeded_before_the_manifest() {
+        let base = TempDir::new("seed-legacy");
+        let workspace = base.join("workspace");
+        let preset = preset_with_skills(&base.join("v1"), "v1 notes", "v1 tasks");
+        seed(&workspace, &preset);
+        fs::write(workspace_path(&workspace, TASKS), "my own tasks").expect("edit");
+
+        // Without a base the edited file can't be told apart from an outdated one.
+        let plan = plan_seed_upgrade("ws", &workspace, &preset).expect("plan");
+        assert_eq!(
+            statuses(&plan),
+            vec![
+                (NOTES, SeedFileStatus::Current),
+                (TASKS, SeedFileStatus::Modified)
+            ]
+        );
+        let manifest = read_seed_manifest(&workspace);
+        assert!(manifest.files.contains_key(NOTES));
+        assert!(!manifest.files.contains_key(TASKS));
+
+        let next = preset_with_skills(&base.join("v2"), "v2 notes", "v1 tasks");
+        let plan = plan_seed_upgrade("ws", &workspace, &next).expect("plan");
+        assert_eq!(plan.files[0].status, SeedFileStatus::Update);
+    }
+}
@@ -50,14 +50,12 @@ pub fn save_workspace_state(app: &tauri::AppHandle, state: &WorkspaceState) -> R
     Ok(())
 }
 
-pub fn ensure_starter_workspace(app: &tauri::AppHandle) -> Result<WorkspaceInfo, String> {
+pub fn starter_workspace_info(app: &tauri::AppHandle) -> Result<WorkspaceInfo, String> {
     let data_dir = app
         .path()
         .app_data_dir()
         .map_err(|e| format!("Failed to resolve app data dir: {e}"))?;
     let starter_dir = data_dir.join("workspaces").join("starter");
-    fs::create_dir_all(&starter_dir)
-        .map_err(|e| format!("Failed to create starter workspace: {e}"))?;
 
     Ok(WorkspaceInfo {
         id: stable_workspace_id(starter_dir.to_string_lossy().as_ref()),
@@ -72,9 +70,21 @@ pub fn ensure_starter_workspace(app: &tauri::AppHandle) -> Result<WorkspaceInfo,
         openwork_host_url: None,
         openwork_workspace_id: None,
         openwork_workspace_name: None,
+        token_secret: None,
+        opencode_profile: None,
+        engine_launcher: None,
+        engine_sandbox: false,
+        resource_limits: None,
     })
 }
 
+pub fn ensure_starter_workspace(app: &tauri::AppHandle) -> Result<WorkspaceInfo, String> {
+    let starter = starter_workspace_info(app)?;
+    fs::create_dir_all(&starter.path)
+        .map_err(|e| format!("Failed to create starter workspace: {e}"))?;
+    Ok(starter)
+}
+
 pub fn stable_workspace_id_for_remote(base_url: &str, directory: Option<&str>) -> String {
     let mut key = format!("remote::{base_url}");
     if let Some(dir) = directory {